@@ -0,0 +1,479 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    hash::Hash,
+};
+
+use super::Model;
+
+/// The part of a model reachable from some start states, with the states numbered in BFS order from the start states
+pub(crate) struct Graph<'a, S> {
+    pub(crate) states: Vec<&'a S>,
+    /// The number of distinct start states, which are the first states
+    pub(crate) starts: usize,
+    pub(crate) successors: Vec<Vec<usize>>,
+    pub(crate) predecessors: Vec<Vec<usize>>,
+}
+
+impl<'a, S: PartialEq> Graph<'a, S> {
+    pub(crate) fn explore<A>(model: &'a dyn Model<S, A>, start: Vec<&'a S>) -> Self {
+        Self::explore_up_to(model, start, None)
+    }
+
+    /// Explores the states within `depth` transitions of the start states, or all reachable states if `depth` is `None`.
+    /// The states at distance `depth` are left without successors. States are looked up in `O(n)`, see [`Graph::explore_hashed`]
+    pub(crate) fn explore_up_to<A>(
+        model: &'a dyn Model<S, A>,
+        start: Vec<&'a S>,
+        depth: Option<usize>,
+    ) -> Self {
+        let mut seen: Vec<&'a S> = vec![];
+        Self::search(model, start, depth, |state| {
+            let found = seen.iter().position(|s| *s == state);
+            if found.is_none() {
+                seen.push(state);
+            }
+            found
+        })
+    }
+
+    // BFS from the start states, where `find` returns the number of a state that was already found,
+    // or records the state under the next number and returns `None`
+    fn search<A>(
+        model: &'a dyn Model<S, A>,
+        start: Vec<&'a S>,
+        depth: Option<usize>,
+        mut find: impl FnMut(&'a S) -> Option<usize>,
+    ) -> Self {
+        let mut states: Vec<&'a S> = vec![];
+        for state in start {
+            if find(state).is_none() {
+                states.push(state);
+            }
+        }
+        let starts = states.len();
+
+        let mut distances = vec![0; starts];
+        let mut successors = vec![];
+        let mut i = 0;
+        while i < states.len() {
+            let mut nexts = vec![];
+            if depth.is_none_or(|depth| distances[i] < depth) {
+                for next in model.transitions(states[i]) {
+                    let j = match find(next) {
+                        Some(j) => j,
+                        None => {
+                            states.push(next);
+                            distances.push(distances[i] + 1);
+                            states.len() - 1
+                        }
+                    };
+                    if !nexts.contains(&j) {
+                        nexts.push(j);
+                    }
+                }
+            }
+            successors.push(nexts);
+            i += 1;
+        }
+
+        let mut predecessors = vec![vec![]; states.len()];
+        for (i, nexts) in successors.iter().enumerate() {
+            for j in nexts.iter() {
+                predecessors[*j].push(i);
+            }
+        }
+
+        Self {
+            states,
+            starts,
+            successors,
+            predecessors,
+        }
+    }
+
+    pub(crate) fn index(&self, state: &S) -> Option<usize> {
+        self.states.iter().position(|s| *s == state)
+    }
+
+    /// Tarjan's algorithm restricted to the states in `include`, see [`components`]
+    pub(crate) fn components_within(&self, include: &[bool]) -> Vec<Vec<usize>> {
+        components(&self.successors, include)
+    }
+
+    /// Whether a component has a cycle, i.e. more than one state or a state with a self-loop
+    pub(crate) fn is_cyclic(&self, component: &[usize]) -> bool {
+        component.len() > 1 || self.successors[component[0]].contains(&component[0])
+    }
+
+    /// BFS distances from `from`, `None` for states that cannot be reached
+    pub(crate) fn distances(&self, from: usize) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
+        let mut distances = vec![None; self.states.len()];
+        let mut parents = vec![None; self.states.len()];
+        distances[from] = Some(0);
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(i) = queue.pop_front() {
+            for &j in self.successors[i].iter() {
+                if distances[j].is_none() {
+                    distances[j] = Some(distances[i].unwrap() + 1);
+                    parents[j] = Some(i);
+                    queue.push_back(j);
+                }
+            }
+        }
+
+        (distances, parents)
+    }
+}
+
+impl<'a, S: Eq + Hash> Graph<'a, S> {
+    /// Like [`Graph::explore_up_to`], but looks states up in `O(1)`
+    pub(crate) fn explore_hashed<A>(
+        model: &'a dyn Model<S, A>,
+        start: Vec<&'a S>,
+        depth: Option<usize>,
+    ) -> Self {
+        let mut seen: HashMap<&'a S, usize> = HashMap::new();
+        Self::search(model, start, depth, |state| {
+            let n = seen.len();
+            match seen.entry(state) {
+                Entry::Occupied(entry) => Some(*entry.get()),
+                Entry::Vacant(entry) => {
+                    entry.insert(n);
+                    None
+                }
+            }
+        })
+    }
+}
+
+/// Tarjan's algorithm on the graph given by `successors`, restricted to the states in `include`.
+/// The components are returned in reverse topological order, so no component has a transition to a later one.
+pub(crate) fn components(successors: &[Vec<usize>], include: &[bool]) -> Vec<Vec<usize>> {
+    let n = successors.len();
+    let mut index = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = vec![];
+    let mut components = vec![];
+    let mut counter = 0;
+
+    for root in 0..n {
+        if !include[root] || index[root] != usize::MAX {
+            continue;
+        }
+
+        // Explicit call stack of (state, next successor to visit) to avoid recursion on large models
+        let mut calls = vec![(root, 0)];
+        index[root] = counter;
+        low[root] = counter;
+        counter += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((v, i)) = calls.last().copied() {
+            if let Some(&w) = successors[v].get(i) {
+                calls.last_mut().unwrap().1 += 1;
+                if !include[w] {
+                    continue;
+                }
+                if index[w] == usize::MAX {
+                    index[w] = counter;
+                    low[w] = counter;
+                    counter += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    calls.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if low[v] == index[v] {
+                let mut component = vec![];
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                component.reverse();
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
+
+/// Function `reachable` that returns the states that can be reached from some states
+///
+/// The states are returned in breadth-first order, starting with `from`.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{model, reachable, EnumerableModel, HashedDiscreteModel};
+///
+/// let model = model! {
+///     s0 -> s1;
+///     s1 -> s1;
+///     s2 -> s0;
+///     init s0;
+/// };
+///
+/// assert_eq!(reachable(&model, model.initial_states()), vec![&"s0", &"s1"]);
+/// ```
+pub fn reachable<'a, S: PartialEq, A>(model: &'a dyn Model<S, A>, from: &'a [S]) -> Vec<&'a S> {
+    Graph::explore(model, from.iter().collect()).states
+}
+
+/// Function `shortest_path` that returns a path with the fewest transitions from a state to another
+///
+/// The path starts with `from` and ends with `to`. Returns `None` if `to` cannot be reached from `from`.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{model, shortest_path, HashedDiscreteModel};
+///
+/// let model = model! {
+///     s0 -> s1, s2;
+///     s1 -> s3;
+///     s2 -> s1;
+///     s3 -> s3;
+/// };
+///
+/// assert_eq!(shortest_path(&model, &"s0", &"s3"), Some(vec![&"s0", &"s1", &"s3"]));
+/// assert_eq!(shortest_path(&model, &"s3", &"s0"), None);
+/// ```
+pub fn shortest_path<'a, S: PartialEq, A>(
+    model: &'a dyn Model<S, A>,
+    from: &'a S,
+    to: &S,
+) -> Option<Vec<&'a S>> {
+    let graph = Graph::explore(model, vec![from]);
+    let target = graph.index(to)?;
+    let (_, parents) = graph.distances(0);
+
+    let mut path = vec![graph.states[target]];
+    let mut current = target;
+    while let Some(parent) = parents[current] {
+        path.push(graph.states[parent]);
+        current = parent;
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Function `strongly_connected_components` that splits the states reachable from some states into strongly connected components
+///
+/// Two states are in the same component if each can be reached from the other. The components are found with Tarjan's algorithm
+/// and are returned in reverse topological order: no component has a transition to a component that comes after it.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{model, strongly_connected_components, EnumerableModel, HashedDiscreteModel};
+///
+/// let model = model! {
+///     s0 -> s1;
+///     s1 -> s0, s2;
+///     s2 -> s2;
+///     init s0;
+/// };
+///
+/// let components = strongly_connected_components(&model, model.initial_states());
+/// assert_eq!(components, vec![vec![&"s2"], vec![&"s0", &"s1"]]);
+/// ```
+pub fn strongly_connected_components<'a, S: PartialEq, A>(
+    model: &'a dyn Model<S, A>,
+    from: &'a [S],
+) -> Vec<Vec<&'a S>> {
+    let graph = Graph::explore(model, from.iter().collect());
+    graph
+        .components_within(&vec![true; graph.states.len()])
+        .into_iter()
+        .map(|component| component.into_iter().map(|i| graph.states[i]).collect())
+        .collect()
+}
+
+/// Function `bottom_strongly_connected_components` that returns the strongly connected components without transitions leaving them
+///
+/// Only the states reachable from `from` are considered. Every one of them can reach a bottom component,
+/// and a path that enters a bottom component never leaves it.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{bottom_strongly_connected_components, model, EnumerableModel, HashedDiscreteModel};
+///
+/// let model = model! {
+///     s0 -> s0, s1, s2;
+///     s1 -> s1;
+///     s2 -> s3;
+///     s3 -> s2;
+///     init s0;
+/// };
+///
+/// let bottoms = bottom_strongly_connected_components(&model, model.initial_states());
+/// assert_eq!(bottoms, vec![vec![&"s1"], vec![&"s2", &"s3"]]);
+/// ```
+pub fn bottom_strongly_connected_components<'a, S: PartialEq, A>(
+    model: &'a dyn Model<S, A>,
+    from: &'a [S],
+) -> Vec<Vec<&'a S>> {
+    let graph = Graph::explore(model, from.iter().collect());
+    let components = graph.components_within(&vec![true; graph.states.len()]);
+
+    let mut component_of = vec![0; graph.states.len()];
+    for (c, component) in components.iter().enumerate() {
+        for &i in component.iter() {
+            component_of[i] = c;
+        }
+    }
+
+    components
+        .iter()
+        .enumerate()
+        .filter(|(c, component)| {
+            component
+                .iter()
+                .all(|&i| graph.successors[i].iter().all(|&j| component_of[j] == *c))
+        })
+        .map(|(_, component)| component.iter().map(|&i| graph.states[i]).collect())
+        .collect()
+}
+
+/// # `PredecessorIndex`
+/// The reverse transitions of the states reachable from some states, built by [`predecessor_index`].
+pub struct PredecessorIndex<'a, S> {
+    graph: Graph<'a, S>,
+}
+
+impl<'a, S: PartialEq> PredecessorIndex<'a, S> {
+    /// # `predecessors`
+    /// Returns the states with a transition to `state`, in the order they were explored. Returns an empty `Vec` if `state` was not reached
+    pub fn predecessors(&self, state: &S) -> Vec<&'a S> {
+        match self.graph.index(state) {
+            Some(i) => self.graph.predecessors[i]
+                .iter()
+                .map(|&j| self.graph.states[j])
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// # `states`
+    /// Returns the states in the index, i.e. those reachable from the states the index was built from
+    pub fn states(&self) -> &Vec<&'a S> {
+        &self.graph.states
+    }
+}
+
+/// Function `predecessor_index` that explores the states reachable from some states and indexes their predecessors
+///
+/// ## Examples
+/// ```
+/// use ceetle::{model, predecessor_index, EnumerableModel, HashedDiscreteModel};
+///
+/// let model = model! {
+///     s0 -> s1, s2;
+///     s1 -> s2;
+///     s2 -> s2;
+///     init s0;
+/// };
+///
+/// let index = predecessor_index(&model, model.initial_states());
+/// assert_eq!(index.predecessors(&"s2"), vec![&"s0", &"s1", &"s2"]);
+/// assert!(index.predecessors(&"s0").is_empty());
+/// ```
+pub fn predecessor_index<'a, S: PartialEq, A>(
+    model: &'a dyn Model<S, A>,
+    from: &'a [S],
+) -> PredecessorIndex<'a, S> {
+    PredecessorIndex {
+        graph: Graph::explore(model, from.iter().collect()),
+    }
+}
+
+/// Function `diameter` that returns the largest number of transitions needed to go from a state to another state reachable from it
+///
+/// Only the states reachable from `from` are considered. Every state that can reach another one can do so with at most this many transitions,
+/// so e.g. `EF` properties only need to look this far ahead.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{diameter, model, EnumerableModel, HashedDiscreteModel};
+///
+/// let model = model! {
+///     s0 -> s1;
+///     s1 -> s2;
+///     s2 -> s0;
+///     init s0;
+/// };
+///
+/// assert_eq!(diameter(&model, model.initial_states()), 2);
+/// ```
+pub fn diameter<S: PartialEq, A>(model: &dyn Model<S, A>, from: &[S]) -> usize {
+    let graph = Graph::explore(model, from.iter().collect());
+    (0..graph.states.len())
+        .flat_map(|i| graph.distances(i).0.into_iter().flatten())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Function `recurrence_diameter` that returns the number of transitions of the longest path from `from` that visits no state twice
+///
+/// This is an upper bound of the `diameter` and is used as a completeness threshold in bounded model checking.
+/// Finding the longest such path takes exponential time in the worst case, so it should only be used on small models.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{model, recurrence_diameter, EnumerableModel, HashedDiscreteModel};
+///
+/// let model = model! {
+///     s0 -> s1, s2;
+///     s1 -> s2;
+///     s2 -> s0;
+///     init s0;
+/// };
+///
+/// assert_eq!(recurrence_diameter(&model, model.initial_states()), 2);
+/// ```
+pub fn recurrence_diameter<S: PartialEq, A>(model: &dyn Model<S, A>, from: &[S]) -> usize {
+    let graph = Graph::explore(model, from.iter().collect());
+    let mut longest = 0;
+
+    for start in 0..graph.states.len() {
+        if !from.iter().any(|s| s == graph.states[start]) {
+            continue;
+        }
+
+        // Depth-first search over simple paths, with the next successor to try for each state on the path
+        let mut on_path = vec![false; graph.states.len()];
+        let mut path = vec![(start, 0)];
+        on_path[start] = true;
+
+        while let Some((v, i)) = path.last().copied() {
+            longest = longest.max(path.len() - 1);
+            match graph.successors[v].get(i) {
+                Some(&w) => {
+                    path.last_mut().unwrap().1 += 1;
+                    if !on_path[w] {
+                        on_path[w] = true;
+                        path.push((w, 0));
+                    }
+                }
+                None => {
+                    on_path[v] = false;
+                    path.pop();
+                }
+            }
+        }
+    }
+
+    longest
+}
@@ -0,0 +1,141 @@
+use std::ops;
+
+use super::CTLFormula;
+
+// ================== CONSTRUCTORS ==================
+// Shorthands for building `CTLFormula`s at runtime without boxing by hand.
+// For formulas known at compile time the `ctl` macro is usually more readable.
+
+/// # `atom`
+/// Returns the formula `Atom(value)`
+///
+/// ## Examples
+/// ```
+/// use ceetle::builder::{atom, ag, af};
+/// use ceetle::CTLFormula;
+///
+/// let f = ag(atom("req").implies(af(atom("ack"))));
+/// assert_eq!(f.to_string(), "AG((req→AF(ack)))");
+/// ```
+pub fn atom<T: PartialEq>(value: T) -> CTLFormula<T> {
+    CTLFormula::Atom(value)
+}
+
+/// # `not`
+/// Returns the formula `¬f`
+pub fn not<T: PartialEq>(formula: CTLFormula<T>) -> CTLFormula<T> {
+    CTLFormula::Not(Box::new(formula))
+}
+
+/// # `and`
+/// Returns the formula `f∧g`
+pub fn and<T: PartialEq>(formula: CTLFormula<T>, other: CTLFormula<T>) -> CTLFormula<T> {
+    CTLFormula::And(Box::new(formula), Box::new(other))
+}
+
+/// # `or`
+/// Returns the formula `f∨g`
+pub fn or<T: PartialEq>(formula: CTLFormula<T>, other: CTLFormula<T>) -> CTLFormula<T> {
+    CTLFormula::Or(Box::new(formula), Box::new(other))
+}
+
+/// # `imply`
+/// Returns the formula `f→g`
+pub fn imply<T: PartialEq>(formula: CTLFormula<T>, other: CTLFormula<T>) -> CTLFormula<T> {
+    CTLFormula::Imply(Box::new(formula), Box::new(other))
+}
+
+/// # `ag`
+/// Returns the formula `AG(f)`
+pub fn ag<T: PartialEq>(formula: CTLFormula<T>) -> CTLFormula<T> {
+    CTLFormula::AG(Box::new(formula))
+}
+
+/// # `af`
+/// Returns the formula `AF(f)`
+pub fn af<T: PartialEq>(formula: CTLFormula<T>) -> CTLFormula<T> {
+    CTLFormula::AF(Box::new(formula))
+}
+
+/// # `ax`
+/// Returns the formula `AX(f)`
+pub fn ax<T: PartialEq>(formula: CTLFormula<T>) -> CTLFormula<T> {
+    CTLFormula::AX(Box::new(formula))
+}
+
+/// # `au`
+/// Returns the formula `A[f U g]`
+pub fn au<T: PartialEq>(formula: CTLFormula<T>, until: CTLFormula<T>) -> CTLFormula<T> {
+    CTLFormula::AU(Box::new(formula), Box::new(until))
+}
+
+/// # `eg`
+/// Returns the formula `EG(f)`
+pub fn eg<T: PartialEq>(formula: CTLFormula<T>) -> CTLFormula<T> {
+    CTLFormula::EG(Box::new(formula))
+}
+
+/// # `ef`
+/// Returns the formula `EF(f)`
+pub fn ef<T: PartialEq>(formula: CTLFormula<T>) -> CTLFormula<T> {
+    CTLFormula::EF(Box::new(formula))
+}
+
+/// # `ex`
+/// Returns the formula `EX(f)`
+pub fn ex<T: PartialEq>(formula: CTLFormula<T>) -> CTLFormula<T> {
+    CTLFormula::EX(Box::new(formula))
+}
+
+/// # `eu`
+/// Returns the formula `E[f U g]`
+pub fn eu<T: PartialEq>(formula: CTLFormula<T>, until: CTLFormula<T>) -> CTLFormula<T> {
+    CTLFormula::EU(Box::new(formula), Box::new(until))
+}
+
+impl<T: PartialEq> CTLFormula<T> {
+    /// # `and`
+    /// Returns the formula `self∧other`
+    pub fn and(self, other: CTLFormula<T>) -> Self {
+        and(self, other)
+    }
+
+    /// # `or`
+    /// Returns the formula `self∨other`
+    pub fn or(self, other: CTLFormula<T>) -> Self {
+        or(self, other)
+    }
+
+    /// # `implies`
+    /// Returns the formula `self→other`
+    pub fn implies(self, other: CTLFormula<T>) -> Self {
+        imply(self, other)
+    }
+}
+
+// ================== OPERATORS ==================
+// `f & g`, `f | g` and `!f` build conjunctions, disjunctions and negations
+
+impl<T: PartialEq> ops::BitAnd for CTLFormula<T> {
+    type Output = CTLFormula<T>;
+
+    fn bitand(self, other: Self) -> Self::Output {
+        and(self, other)
+    }
+}
+
+impl<T: PartialEq> ops::BitOr for CTLFormula<T> {
+    type Output = CTLFormula<T>;
+
+    fn bitor(self, other: Self) -> Self::Output {
+        or(self, other)
+    }
+}
+
+impl<T: PartialEq> ops::Not for CTLFormula<T> {
+    type Output = CTLFormula<T>;
+
+    fn not(self) -> Self::Output {
+        not(self)
+    }
+}
@@ -0,0 +1,73 @@
+use std::{
+    cell::{Cell, OnceCell, RefCell},
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+};
+
+const CHUNKS: usize = usize::BITS as usize;
+
+/// # `Cache`
+/// An append-only map from keys to values that are computed the first time they are needed, used by the models that build their
+/// transitions lazily to hand out references to the successors of a state from `&self`.
+///
+/// The values are stored in chunks of doubling sizes, so value `i` is in chunk `⌊log2(i + 1)⌋` at offset `i + 1 - 2^chunk`.
+/// Chunks are allocated once and never moved nor freed before the cache, so references to the values stay valid while values are added.
+pub(crate) struct Cache<K, V> {
+    index: RefCell<HashMap<K, usize>>,
+    chunks: [OnceCell<Box<[OnceCell<V>]>>; CHUNKS],
+    len: Cell<usize>,
+}
+
+impl<K: Clone + Eq + Hash, V> Cache<K, V> {
+    pub(crate) fn new() -> Self {
+        Self {
+            index: RefCell::new(HashMap::new()),
+            chunks: std::array::from_fn(|_| OnceCell::new()),
+            len: Cell::new(0),
+        }
+    }
+
+    /// # `get_or_insert_with`
+    /// Returns the value of `key`, computing it with `f` if the cache does not have it yet.
+    /// `f` may use the cache itself, since no borrow of the cache is held while it runs.
+    pub(crate) fn get_or_insert_with(&self, key: &K, f: impl FnOnce() -> V) -> &V {
+        if let Some(value) = self.get(key) {
+            return value;
+        }
+
+        let value = f();
+        // `f` may have added the key through the cache, in which case the first value is kept
+        if let Some(existing) = self.get(key) {
+            return existing;
+        }
+
+        let i = self.len.get();
+        self.len.set(i + 1);
+        let slot = self.slot(i);
+        let _ = slot.set(value);
+        self.index.borrow_mut().insert(key.clone(), i);
+        slot.get().unwrap()
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        let i = *self.index.borrow().get(key)?;
+        self.slot(i).get()
+    }
+
+    fn slot(&self, i: usize) -> &OnceCell<V> {
+        let chunk = (usize::BITS - 1 - (i + 1).leading_zeros()) as usize;
+        let slots = self.chunks[chunk]
+            .get_or_init(|| (0..1usize << chunk).map(|_| OnceCell::new()).collect());
+        &slots[i + 1 - (1 << chunk)]
+    }
+}
+
+impl<K: Clone + Eq + Hash + fmt::Debug, V: fmt::Debug> fmt::Debug for Cache<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let index = self.index.borrow();
+        f.debug_map()
+            .entries(index.iter().map(|(key, i)| (key, self.slot(*i).get())))
+            .finish()
+    }
+}
@@ -0,0 +1,402 @@
+use std::{fmt, hash::Hash};
+
+use super::{cache::Cache, Model};
+
+/// # `ComponentAtom`
+/// An atom of one component of a `Composition`, written `component.atom`, e.g. `proc1.crit`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ComponentAtom<A> {
+    /// The name of the component
+    pub component: String,
+    /// The atom in the model of the component
+    pub atom: A,
+}
+
+impl<A> ComponentAtom<A> {
+    /// # `new`
+    /// Returns the atom `atom` of the component named `component`
+    pub fn new(component: &str, atom: A) -> Self {
+        Self {
+            component: component.to_string(),
+            atom,
+        }
+    }
+}
+
+impl<A: fmt::Display> fmt::Display for ComponentAtom<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.component, self.atom)
+    }
+}
+
+/// Trait for models whose transitions are labelled by actions
+///
+/// The `labelled_transitions` function returns the transitions of a state together with their actions, and the `alphabet` function
+/// returns all actions of the model. The targets of the labelled transitions must be the states returned by `transitions`.
+/// Components of a `Composition::synchronized` take the actions they share together.
+pub trait LabelledModel<S, A, L>: Model<S, A> {
+    fn labelled_transitions(&self, state: &S) -> Vec<(&L, &S)>;
+    fn alphabet(&self) -> Vec<&L>;
+}
+
+/// A `Vector`-based model of a Finite State Automaton with actions on its transitions
+///
+/// The `LabelledDiscreteModel` is like the `VecDiscreteModel`, except that every transition has an action of type `L`.
+/// Lookup is `O(n)`, where `n` is the number of states.
+#[derive(Debug, Clone)]
+pub struct LabelledDiscreteModel<S, A, L> {
+    #[allow(clippy::type_complexity)]
+    states: Vec<(S, Vec<A>, Vec<(L, S)>)>,
+    targets: Vec<Vec<S>>,
+}
+
+impl<S: PartialEq + Clone, A: PartialEq, L: PartialEq> LabelledDiscreteModel<S, A, L> {
+    /// # `new`
+    /// Returns a new `LabelledDiscreteModel` with a structure given as a `Vec` of tuples `(S, Vec<A>, Vec<(L, S)>)`, where
+    /// - The first value is the state label
+    /// - The second value is a `Vec` of atoms that hold in that state
+    /// - The last value is a `Vec` of the actions of the state and the states they lead to
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::LabelledDiscreteModel;
+    ///
+    /// let model = LabelledDiscreteModel::new(vec![
+    ///     ("idle", vec![],       vec![("enter", "crit")]),
+    ///     ("crit", vec!["crit"], vec![("leave", "idle")]),
+    /// ]);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn new(states: Vec<(S, Vec<A>, Vec<(L, S)>)>) -> Self {
+        let targets = states
+            .iter()
+            .map(|(_, _, nexts)| nexts.iter().map(|(_, next)| next.clone()).collect())
+            .collect();
+        Self { states, targets }
+    }
+
+    fn find(&self, state: &S) -> usize {
+        match self.states.iter().position(|s| s.0 == *state) {
+            Some(i) => i,
+            None => panic!("State is not in model!"),
+        }
+    }
+}
+
+impl<S: PartialEq + Clone, A: PartialEq, L: PartialEq> Model<S, A>
+    for LabelledDiscreteModel<S, A, L>
+{
+    /// # `state_has`
+    /// Takes a state and an atom and returns true if the atom holds in that state. This operation is `O(n)`, where `n` is the number of states.
+    fn state_has(&self, state: &S, atom: &A) -> bool {
+        self.states[self.find(state)].1.contains(atom)
+    }
+
+    /// # `transitions`
+    /// Takes a state and returns a `&Vec` of the states that the given state can transition to. This operation is `O(n)`, where `n` is the number of states.
+    fn transitions(&self, state: &S) -> &Vec<S> {
+        &self.targets[self.find(state)]
+    }
+}
+
+impl<S: PartialEq + Clone, A: PartialEq, L: PartialEq> LabelledModel<S, A, L>
+    for LabelledDiscreteModel<S, A, L>
+{
+    fn labelled_transitions(&self, state: &S) -> Vec<(&L, &S)> {
+        self.states[self.find(state)]
+            .2
+            .iter()
+            .map(|(action, next)| (action, next))
+            .collect()
+    }
+
+    fn alphabet(&self) -> Vec<&L> {
+        let mut actions = vec![];
+        for (action, _) in self.states.iter().flat_map(|s| s.2.iter()) {
+            if !actions.contains(&action) {
+                actions.push(action);
+            }
+        }
+        actions
+    }
+}
+
+type Step<'a, S> = Box<dyn Fn(&[S]) -> Vec<Vec<S>> + 'a>;
+type Local<'a, S> = Box<dyn Fn(usize, &S) -> Option<Vec<S>> + 'a>;
+
+/// # `Composition`
+/// A model of several components running in parallel, built by `Composition::synchronous`, `Composition::interleaving` or `Composition::synchronized`.
+///
+/// A state of the composition is a `Vec` with a state of each component, in the order the components were given.
+/// Its atoms are `ComponentAtom`s: `ComponentAtom::new("proc1", "crit")` holds in the states where the atom `"crit"` holds in the state of the component named `proc1`.
+/// Atoms of unknown components never hold.
+///
+/// Like the `StateMachineModel`, the successors of a state are computed the first time they are needed and then cached,
+/// so only the part of the product that the verification looks at is built.
+pub struct Composition<'a, S, A> {
+    pub(crate) names: Vec<String>,
+    pub(crate) components: Vec<&'a dyn Model<S, A>>,
+    step: Step<'a, S>,
+    // The successors of a component in a local state if all its transitions there are independent of the other components
+    pub(crate) local: Local<'a, S>,
+    successors: Cache<Vec<S>, Vec<Vec<S>>>,
+}
+
+impl<'a, S: Clone + Eq + Hash + 'a, A: 'a> Composition<'a, S, A> {
+    fn new(
+        names: Vec<String>,
+        components: Vec<&'a dyn Model<S, A>>,
+        step: Step<'a, S>,
+        local: Local<'a, S>,
+    ) -> Self {
+        Self {
+            names,
+            components,
+            step,
+            local,
+            successors: Cache::new(),
+        }
+    }
+
+    /// # `synchronous`
+    /// Returns the synchronous product of the components, where every component takes a transition at the same time.
+    /// A state of the product has no transitions if a component has none.
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::builder::{and, atom, ag, ef, not};
+    /// use ceetle::{model, verify, ComponentAtom, Composition, HashedDiscreteModel};
+    ///
+    /// let bit = model! {
+    ///     zero -> one;
+    ///     one [on] -> zero;
+    /// };
+    ///
+    /// let pair = Composition::synchronous(vec![("low", &bit), ("high", &bit)]);
+    /// let on = |name| atom(ComponentAtom::new(name, "on"));
+    /// let both = and(on("low"), on("high"));
+    /// assert!(verify(&pair, &vec!["zero", "one"], &ag(not(both.clone()))));
+    /// assert!(verify(&pair, &vec!["zero", "zero"], &ef(both)));
+    /// ```
+    pub fn synchronous(components: Vec<(&str, &'a dyn Model<S, A>)>) -> Self {
+        let (names, components) = split(components);
+        let models = components.clone();
+        Self::new(
+            names,
+            components,
+            Box::new(move |state| {
+                product(
+                    models
+                        .iter()
+                        .zip(state)
+                        .map(|(model, local)| model.transitions(local).iter().collect())
+                        .collect(),
+                )
+            }),
+            // Every component takes part in every transition
+            Box::new(|_, _| None),
+        )
+    }
+
+    /// # `interleaving`
+    /// Returns the asynchronous product of the components, where exactly one component takes a transition at a time.
+    /// A state of the product has no transitions if no component has any.
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::builder::{and, atom, ag, not};
+    /// use ceetle::{model, verify, ComponentAtom, Composition, HashedDiscreteModel};
+    ///
+    /// let process = model! {
+    ///     idle -> crit;
+    ///     crit [crit] -> idle;
+    /// };
+    ///
+    /// let system = Composition::interleaving(vec![("proc1", &process), ("proc2", &process)]);
+    /// let crit = |name| atom(ComponentAtom::new(name, "crit"));
+    /// let mutex = ag(not(and(crit("proc1"), crit("proc2"))));
+    /// assert!(!verify(&system, &vec!["idle", "idle"], &mutex));
+    /// ```
+    pub fn interleaving(components: Vec<(&str, &'a dyn Model<S, A>)>) -> Self {
+        let (names, components) = split(components);
+        let models = components.clone();
+        let locals = components.clone();
+        Self::new(
+            names,
+            components,
+            Box::new(move |state| {
+                let mut nexts = vec![];
+                for (i, model) in models.iter().enumerate() {
+                    for next in model.transitions(&state[i]) {
+                        let mut successor = state.to_vec();
+                        successor[i] = next.clone();
+                        if !nexts.contains(&successor) {
+                            nexts.push(successor);
+                        }
+                    }
+                }
+                nexts
+            }),
+            Box::new(move |i, local| Some(locals[i].transitions(local).clone())),
+        )
+    }
+
+    /// # `synchronized`
+    /// Returns the product of the components where an action that is in the alphabet of several components can only be taken
+    /// by all of them together, and the other actions are interleaved.
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::builder::{and, atom, ag, not};
+    /// use ceetle::{verify, ComponentAtom, Composition, LabelledDiscreteModel};
+    ///
+    /// let process = |enter, leave| LabelledDiscreteModel::new(vec![
+    ///     ("idle", vec![],       vec![(enter, "crit")]),
+    ///     ("crit", vec!["crit"], vec![(leave, "idle")]),
+    /// ]);
+    /// let (proc1, proc2) = (process("enter1", "leave1"), process("enter2", "leave2"));
+    /// let lock = LabelledDiscreteModel::new(vec![
+    ///     ("free", vec![], vec![("enter1", "held"), ("enter2", "held")]),
+    ///     ("held", vec![], vec![("leave1", "free"), ("leave2", "free")]),
+    /// ]);
+    ///
+    /// let system = Composition::synchronized(vec![("proc1", &proc1), ("proc2", &proc2), ("lock", &lock)]);
+    /// let crit = |name| atom(ComponentAtom::new(name, "crit"));
+    /// let mutex = ag(not(and(crit("proc1"), crit("proc2"))));
+    /// assert!(verify(&system, &vec!["idle", "idle", "free"], &mutex));
+    /// ```
+    pub fn synchronized<L: PartialEq + 'a>(
+        components: Vec<(&str, &'a dyn LabelledModel<S, A, L>)>,
+    ) -> Self {
+        let models: Vec<&'a dyn LabelledModel<S, A, L>> = components.iter().map(|c| c.1).collect();
+        let alphabets: Vec<Vec<&'a L>> = models.iter().map(|model| model.alphabet()).collect();
+        let mut actions: Vec<&'a L> = vec![];
+        for action in alphabets.iter().flatten() {
+            if !actions.contains(action) {
+                actions.push(action);
+            }
+        }
+
+        // An action is only independent of the other components if it is in the alphabet of no other component
+        let private: Vec<Vec<&'a L>> = alphabets
+            .iter()
+            .enumerate()
+            .map(|(i, alphabet)| {
+                alphabet
+                    .iter()
+                    .copied()
+                    .filter(|action| {
+                        alphabets
+                            .iter()
+                            .enumerate()
+                            .all(|(j, other)| j == i || !other.contains(action))
+                    })
+                    .collect()
+            })
+            .collect();
+        let locals = models.clone();
+
+        let (names, components) = split(
+            components
+                .into_iter()
+                .map(|(name, model)| (name, model as &'a dyn Model<S, A>))
+                .collect(),
+        );
+        Self::new(
+            names,
+            components,
+            Box::new(move |state| {
+                let mut nexts = vec![];
+                for action in actions.iter() {
+                    // The targets of each component, which stays in its state if the action is not in its alphabet
+                    let choices: Vec<Vec<&S>> = models
+                        .iter()
+                        .zip(alphabets.iter())
+                        .zip(state)
+                        .map(|((model, alphabet), local)| {
+                            if alphabet.contains(action) {
+                                model
+                                    .labelled_transitions(local)
+                                    .into_iter()
+                                    .filter(|(a, _)| a == action)
+                                    .map(|(_, next)| next)
+                                    .collect()
+                            } else {
+                                vec![local]
+                            }
+                        })
+                        .collect();
+                    for successor in product(choices) {
+                        if !nexts.contains(&successor) {
+                            nexts.push(successor);
+                        }
+                    }
+                }
+                nexts
+            }),
+            Box::new(move |i, local| {
+                let transitions = locals[i].labelled_transitions(local);
+                if transitions
+                    .iter()
+                    .all(|(action, _)| private[i].contains(action))
+                {
+                    Some(
+                        transitions
+                            .into_iter()
+                            .map(|(_, next)| next.clone())
+                            .collect(),
+                    )
+                } else {
+                    None
+                }
+            }),
+        )
+    }
+
+    /// # `components`
+    /// Returns the names of the components, in the order of the states of the composition
+    pub fn components(&self) -> Vec<&str> {
+        self.names.iter().map(|name| name.as_str()).collect()
+    }
+}
+
+impl<S: Clone + Eq + Hash, A> Model<Vec<S>, ComponentAtom<A>> for Composition<'_, S, A> {
+    fn state_has(&self, state: &Vec<S>, atom: &ComponentAtom<A>) -> bool {
+        match self.names.iter().position(|name| *name == atom.component) {
+            Some(i) => self.components[i].state_has(&state[i], &atom.atom),
+            None => false,
+        }
+    }
+
+    fn transitions(&self, state: &Vec<S>) -> &Vec<Vec<S>> {
+        self.successors
+            .get_or_insert_with(state, || (self.step)(state))
+    }
+}
+
+fn split<'a, S, A>(
+    components: Vec<(&str, &'a dyn Model<S, A>)>,
+) -> (Vec<String>, Vec<&'a dyn Model<S, A>>) {
+    components
+        .into_iter()
+        .map(|(name, model)| (name.to_string(), model))
+        .unzip()
+}
+
+/// Every way of picking one state from each `Vec`, in lexicographic order
+fn product<S: Clone>(choices: Vec<Vec<&S>>) -> Vec<Vec<S>> {
+    let mut result = vec![vec![]];
+    for options in choices {
+        result = result
+            .into_iter()
+            .flat_map(|prefix: Vec<S>| {
+                options.iter().map(move |option| {
+                    let mut state = prefix.clone();
+                    state.push((*option).clone());
+                    state
+                })
+            })
+            .collect();
+    }
+    result
+}
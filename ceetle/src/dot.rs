@@ -0,0 +1,774 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt::{self, Display},
+};
+
+use super::{
+    analysis::Graph,
+    verifier::{depth, satisfying},
+    CTLFormula, EnumerableModel, HashedDiscreteModel,
+};
+
+/// Function that assigns a state to a named cluster
+type ClusterFn<'a, S> = Box<dyn Fn(&S) -> Option<String> + 'a>;
+
+/// Options for exporting a model to Graphviz DOT with [`to_dot`]
+///
+/// By default the graph is called `model`, the atoms of each state are shown below its name and the states are not colored.
+pub struct DotOptions<'a, S, A: PartialEq> {
+    name: String,
+    show_labels: bool,
+    formula: Option<&'a CTLFormula<A>>,
+    path: Vec<S>,
+    cluster: Option<ClusterFn<'a, S>>,
+}
+
+impl<'a, S, A: PartialEq> Default for DotOptions<'a, S, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, S, A: PartialEq> DotOptions<'a, S, A> {
+    /// # `new`
+    /// Returns the default options
+    pub fn new() -> Self {
+        Self {
+            name: "model".to_string(),
+            show_labels: true,
+            formula: None,
+            path: Vec::new(),
+            cluster: None,
+        }
+    }
+
+    /// # `with_name`
+    /// Sets the name of the graph
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// # `with_labels`
+    /// Sets whether the atoms of each state are shown
+    pub fn with_labels(mut self, show_labels: bool) -> Self {
+        self.show_labels = show_labels;
+        self
+    }
+
+    /// # `with_formula`
+    /// Colors the states that satisfy the formula green and the states that do not red
+    pub fn with_formula(mut self, formula: &'a CTLFormula<A>) -> Self {
+        self.formula = Some(formula);
+        self
+    }
+
+    /// # `with_path`
+    /// Highlights the given path, for example a witness or a counterexample, by drawing its states and transitions in bold blue
+    pub fn with_path(mut self, path: Vec<S>) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// # `with_clusters`
+    /// Groups states into clusters. The function takes a state and returns the name of its cluster, or `None` if the state is not in a cluster.
+    pub fn with_clusters(mut self, cluster: impl Fn(&S) -> Option<String> + 'a) -> Self {
+        self.cluster = Some(Box::new(cluster));
+        self
+    }
+}
+
+/// Function `to_dot` that exports a model to Graphviz DOT
+///
+/// Every state becomes a node named after the state and every transition becomes an edge. The atoms of a state are written
+/// to its `atoms` attribute, so [`from_dot`] imports the same model back as long as no atom contains a comma or whitespace.
+/// Initial states are marked by an arrow coming from a point named `__initial0`, `__initial1`, etc., with more leading underscores if a state name starts with `__initial`.
+/// The nodes are sorted by name, so exporting the same model always gives the same output.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ctl, to_dot, CTLFormula, DotOptions, VecDiscreteModel};
+///
+/// let model = VecDiscreteModel::new(vec![
+///     ("s0", vec!["p"], vec!["s1"]),
+///     ("s1", vec!["q"], vec!["s0"]),
+/// ])
+/// .with_initial_states(vec!["s0"]);
+///
+/// let formula = ctl!(Atom("p"));
+/// let dot = to_dot(&model, &DotOptions::new().with_formula(&formula).with_labels(false));
+/// assert!(dot.contains("\"s0\" [label=\"s0\", atoms=\"p\", style=filled, fillcolor=palegreen];"));
+/// assert!(dot.contains("\"s1\" -> \"s0\";"));
+/// ```
+pub fn to_dot<S: Display + PartialEq, A: Display + PartialEq>(
+    model: &dyn EnumerableModel<S, A>,
+    options: &DotOptions<S, A>,
+) -> String {
+    let mut states = model.states();
+    states.sort_by_cached_key(|s| s.to_string());
+
+    // The formula is checked in all states at once rather than in each node
+    let holding: Vec<&S> = match options.formula {
+        Some(formula) => {
+            let graph = Graph::explore_up_to(model, model.states(), depth(formula));
+            let holds = satisfying(model, &graph, formula);
+            (0..graph.starts)
+                .filter(|&i| holds[i])
+                .map(|i| graph.states[i])
+                .collect()
+        }
+        None => vec![],
+    };
+
+    let mut out = format!("digraph {} {{\n", quote(&options.name));
+
+    // Nodes, grouped by cluster
+    let mut clusters: BTreeMap<Option<String>, Vec<&S>> = BTreeMap::new();
+    for state in states.iter() {
+        let cluster = options.cluster.as_ref().and_then(|f| f(state));
+        clusters.entry(cluster).or_default().push(state);
+    }
+
+    for (cluster, members) in clusters.iter() {
+        let indent = match cluster {
+            Some(name) => {
+                out.push_str(&format!(
+                    "    subgraph {} {{\n",
+                    quote(&format!("cluster_{name}"))
+                ));
+                out.push_str(&format!("        label={};\n", quote(name)));
+                "        "
+            }
+            None => "    ",
+        };
+
+        for state in members {
+            let holds = options.formula.map(|_| holding.contains(state));
+            out.push_str(&format!("{indent}{}\n", node(model, state, holds, options)));
+        }
+
+        if cluster.is_some() {
+            out.push_str("    }\n");
+        }
+    }
+
+    // Arrows into the initial states, from points whose names start with a prefix that no state name starts with
+    let mut prefix = "__initial".to_string();
+    while states.iter().any(|s| s.to_string().starts_with(&prefix)) {
+        prefix.insert(0, '_');
+    }
+    for (i, state) in model.initial_states().iter().enumerate() {
+        let point = quote(&format!("{prefix}{i}"));
+        out.push_str(&format!("    {point} [shape=point];\n"));
+        out.push_str(&format!("    {point} -> {};\n", quote(&state.to_string())));
+    }
+
+    // Transitions
+    for state in states.iter() {
+        for next in model.transitions(state).iter() {
+            let on_path = options
+                .path
+                .windows(2)
+                .any(|step| step[0] == **state && step[1] == *next);
+            let attributes = if on_path {
+                " [color=blue, penwidth=2]"
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "    {} -> {}{attributes};\n",
+                quote(&state.to_string()),
+                quote(&next.to_string())
+            ));
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+fn node<S: Display + PartialEq, A: Display + PartialEq>(
+    model: &dyn EnumerableModel<S, A>,
+    state: &S,
+    holds: Option<bool>,
+    options: &DotOptions<S, A>,
+) -> String {
+    let name = state.to_string();
+    let atoms: Vec<String> = model.labels(state).iter().map(|a| a.to_string()).collect();
+    let mut label = name.clone();
+    if options.show_labels {
+        label = format!("{label}\n{{{}}}", atoms.join(", "));
+    }
+
+    // The atoms are also written to their own attribute, which `from_dot` reads instead of the label
+    let mut attributes = vec![
+        format!("label={}", quote(&label)),
+        format!("atoms={}", quote(&atoms.join(", "))),
+    ];
+    if let Some(holds) = holds {
+        let color = if holds { "palegreen" } else { "lightpink" };
+        attributes.push("style=filled".to_string());
+        attributes.push(format!("fillcolor={color}"));
+    }
+    if options.path.contains(state) {
+        attributes.push("color=blue".to_string());
+        attributes.push("penwidth=2".to_string());
+    }
+
+    format!("{} [{}];", quote(&name), attributes.join(", "))
+}
+
+/// Quotes a string as a DOT identifier
+fn quote(text: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Error returned when a DOT file cannot be imported with [`from_dot`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotError {
+    /// Line of the error, starting at 1
+    pub line: usize,
+    /// Column of the error, starting at 1
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl Error for DotError {}
+
+/// Options for importing a model from Graphviz DOT with [`from_dot_with`]
+///
+/// By default the atoms of a state are read from its `atoms` attribute, or from its `label` attribute if it has no `atoms`,
+/// and a state is initial if its `initial` attribute is `true`, `yes` or `1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotImportOptions {
+    atom_attributes: Vec<String>,
+    initial_attribute: String,
+}
+
+impl Default for DotImportOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DotImportOptions {
+    /// # `new`
+    /// Returns the default options
+    pub fn new() -> Self {
+        Self {
+            atom_attributes: vec!["atoms".to_string(), "label".to_string()],
+            initial_attribute: "initial".to_string(),
+        }
+    }
+
+    /// # `with_atom_attributes`
+    /// Sets the attributes that the atoms of a state are read from. The first attribute that a node has is used.
+    pub fn with_atom_attributes(mut self, attributes: &[&str]) -> Self {
+        self.atom_attributes = attributes.iter().map(|a| a.to_string()).collect();
+        self
+    }
+
+    /// # `with_initial_attribute`
+    /// Sets the attribute that marks a state as initial
+    pub fn with_initial_attribute(mut self, attribute: &str) -> Self {
+        self.initial_attribute = attribute.to_string();
+        self
+    }
+}
+
+/// Function `from_dot` that imports a model from a Graphviz DOT graph with the default [`DotImportOptions`]
+///
+/// Every node becomes a state and every edge becomes a transition, edges of an undirected `graph` become transitions in both directions.
+/// The atoms of a state are the words of its `atoms` or `label` attribute, separated by commas or whitespace, and `\n` in a quoted string is a line break.
+/// A model exported with [`to_dot`] is imported back as it was.
+/// A state is initial if its `initial` attribute is set to `true`, or if it has an edge coming from a node with `shape=point`, as in
+/// diagrams drawn with an arrow pointing to the initial state. Such point nodes are not part of the model.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ctl, from_dot, verify, CTLFormula, EnumerableModel};
+///
+/// let model = from_dot(r#"
+///     digraph {
+///         s0 [label="p,q", initial=true];
+///         s1 [atoms="p"];
+///         s0 -> s1 -> s0;
+///     }
+/// "#).unwrap();
+///
+/// assert_eq!(model.initial_states(), &vec!["s0".to_string()]);
+/// assert!(verify(&model, &"s0".to_string(), &ctl!(AG(Atom("p".to_string())))));
+/// ```
+pub fn from_dot(input: &str) -> Result<HashedDiscreteModel<String, String>, DotError> {
+    from_dot_with(input, &DotImportOptions::new())
+}
+
+/// Function `from_dot_with` that imports a model from a Graphviz DOT graph, see [`from_dot`]
+pub fn from_dot_with(
+    input: &str,
+    options: &DotImportOptions,
+) -> Result<HashedDiscreteModel<String, String>, DotError> {
+    let mut parser = DotParser {
+        tokens: tokenize_dot(input)?,
+        current: 0,
+        directed: true,
+        nodes: Vec::new(),
+        attributes: HashMap::new(),
+        edges: Vec::new(),
+        subgraphs: Vec::new(),
+    };
+    parser.graph()?;
+
+    let is_point = |node: &String| {
+        parser
+            .attributes
+            .get(node)
+            .and_then(|attributes| attributes.get("shape"))
+            .is_some_and(|shape| shape == "point")
+    };
+
+    let mut states: HashMap<String, (Vec<String>, Vec<String>)> = HashMap::new();
+    let mut initial: Vec<String> = Vec::new();
+
+    for node in parser.nodes.iter().filter(|node| !is_point(node)) {
+        let attributes = parser.attributes.get(node);
+        let get = |name: &str| attributes.and_then(|attributes| attributes.get(name));
+
+        let atoms = options
+            .atom_attributes
+            .iter()
+            .find_map(|name| get(name))
+            .map(|value| {
+                value
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|atom| !atom.is_empty())
+                    .map(|atom| atom.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if let Some(value) = get(&options.initial_attribute) {
+            if ["true", "yes", "1"].contains(&value.to_lowercase().as_str()) {
+                initial.push(node.clone());
+            }
+        }
+
+        states.insert(node.clone(), (atoms, Vec::new()));
+    }
+
+    for (from, to) in parser.edges.iter() {
+        match (is_point(from), is_point(to)) {
+            (true, false) if !initial.contains(to) => initial.push(to.clone()),
+            (false, false) => {
+                let transitions = &mut states.get_mut(from).unwrap().1;
+                if !transitions.contains(to) {
+                    transitions.push(to.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(HashedDiscreteModel::new(states).with_initial_states(initial))
+}
+
+/// Line and column of a token
+type Position = (usize, usize);
+
+#[derive(Debug, Clone, PartialEq)]
+enum DotToken {
+    Id(String),
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Equals,
+    Semicolon,
+    Comma,
+    Edge,
+    End,
+}
+
+impl fmt::Display for DotToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "`{id}`"),
+            Self::LeftBrace => write!(f, "`{{`"),
+            Self::RightBrace => write!(f, "`}}`"),
+            Self::LeftBracket => write!(f, "`[`"),
+            Self::RightBracket => write!(f, "`]`"),
+            Self::Equals => write!(f, "`=`"),
+            Self::Semicolon => write!(f, "`;`"),
+            Self::Comma => write!(f, "`,`"),
+            Self::Edge => write!(f, "edge operator"),
+            Self::End => write!(f, "end of input"),
+        }
+    }
+}
+
+fn tokenize_dot(input: &str) -> Result<Vec<(Position, DotToken)>, DotError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let (mut line, mut column) = (1, 1);
+    let mut i = 0;
+
+    // Advances past `count` characters while keeping track of the line and column
+    let advance = |i: &mut usize, line: &mut usize, column: &mut usize, count: usize| {
+        for _ in 0..count {
+            if chars.get(*i) == Some(&'\n') {
+                *line += 1;
+                *column = 1;
+            } else {
+                *column += 1;
+            }
+            *i += 1;
+        }
+    };
+    let error = |line: usize, column: usize, message: &str| DotError {
+        line,
+        column,
+        message: message.to_string(),
+    };
+
+    while i < chars.len() {
+        let start = (line, column);
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        let token = match c {
+            c if c.is_whitespace() => {
+                advance(&mut i, &mut line, &mut column, 1);
+                continue;
+            }
+            // Comments and preprocessor lines
+            '/' if next == Some('/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    advance(&mut i, &mut line, &mut column, 1);
+                }
+                continue;
+            }
+            '#' if column == 1 => {
+                while i < chars.len() && chars[i] != '\n' {
+                    advance(&mut i, &mut line, &mut column, 1);
+                }
+                continue;
+            }
+            '/' if next == Some('*') => {
+                advance(&mut i, &mut line, &mut column, 2);
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    advance(&mut i, &mut line, &mut column, 1);
+                }
+                if i >= chars.len() {
+                    return Err(error(start.0, start.1, "Unterminated comment"));
+                }
+                advance(&mut i, &mut line, &mut column, 2);
+                continue;
+            }
+            '{' => DotToken::LeftBrace,
+            '}' => DotToken::RightBrace,
+            '[' => DotToken::LeftBracket,
+            ']' => DotToken::RightBracket,
+            '=' => DotToken::Equals,
+            ';' => DotToken::Semicolon,
+            ',' => DotToken::Comma,
+            '-' if next == Some('>') || next == Some('-') => {
+                advance(&mut i, &mut line, &mut column, 2);
+                tokens.push((start, DotToken::Edge));
+                continue;
+            }
+            '"' => {
+                let mut id = String::new();
+                advance(&mut i, &mut line, &mut column, 1);
+                loop {
+                    match chars.get(i) {
+                        None => return Err(error(start.0, start.1, "Unterminated string")),
+                        Some('"') => break,
+                        Some('\\') if chars.get(i + 1) == Some(&'"') => {
+                            id.push('"');
+                            advance(&mut i, &mut line, &mut column, 2);
+                        }
+                        // Escapes written by `to_dot`
+                        Some('\\') if chars.get(i + 1) == Some(&'\\') => {
+                            id.push('\\');
+                            advance(&mut i, &mut line, &mut column, 2);
+                        }
+                        Some('\\') if chars.get(i + 1) == Some(&'n') => {
+                            id.push('\n');
+                            advance(&mut i, &mut line, &mut column, 2);
+                        }
+                        Some('\\') if chars.get(i + 1) == Some(&'\n') => {
+                            advance(&mut i, &mut line, &mut column, 2);
+                        }
+                        Some(&c) => {
+                            id.push(c);
+                            advance(&mut i, &mut line, &mut column, 1);
+                        }
+                    }
+                }
+                DotToken::Id(id)
+            }
+            '<' => return Err(error(start.0, start.1, "HTML strings are not supported")),
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut id = String::new();
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric()
+                        || chars[i] == '_'
+                        || chars[i] == '.'
+                        || chars[i] == '-')
+                {
+                    if chars[i] == '-'
+                        && !id.is_empty()
+                        && matches!(chars.get(i + 1), Some('>') | Some('-'))
+                    {
+                        break;
+                    }
+                    id.push(chars[i]);
+                    advance(&mut i, &mut line, &mut column, 1);
+                }
+                tokens.push((start, DotToken::Id(id)));
+                continue;
+            }
+            c => {
+                return Err(error(
+                    start.0,
+                    start.1,
+                    &format!("Unexpected character `{c}`"),
+                ))
+            }
+        };
+
+        advance(&mut i, &mut line, &mut column, 1);
+        tokens.push((start, token));
+    }
+
+    tokens.push(((line, column), DotToken::End));
+    Ok(tokens)
+}
+
+struct DotParser {
+    tokens: Vec<(Position, DotToken)>,
+    current: usize,
+    directed: bool,
+    nodes: Vec<String>,
+    attributes: HashMap<String, HashMap<String, String>>,
+    edges: Vec<(String, String)>,
+    // The nodes mentioned so far in each subgraph being parsed, from the outermost
+    subgraphs: Vec<Vec<String>>,
+}
+
+impl DotParser {
+    fn peek(&self) -> &DotToken {
+        &self.tokens[self.current].1
+    }
+
+    fn next(&mut self) -> DotToken {
+        let token = self.tokens[self.current].1.clone();
+        if token != DotToken::End {
+            self.current += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, DotError> {
+        let (line, column) = self.tokens[self.current].0;
+        Err(DotError {
+            line,
+            column,
+            message,
+        })
+    }
+
+    fn expect(&mut self, expected: DotToken) -> Result<(), DotError> {
+        if *self.peek() != expected {
+            return self.error(format!("Expected {expected} but found {}", self.peek()));
+        }
+        self.next();
+        Ok(())
+    }
+
+    fn is_keyword(token: &DotToken, keyword: &str) -> bool {
+        matches!(token, DotToken::Id(id) if id.eq_ignore_ascii_case(keyword))
+    }
+
+    fn id(&mut self) -> Result<String, DotError> {
+        match self.peek().clone() {
+            DotToken::Id(id) => {
+                self.next();
+                Ok(id)
+            }
+            token => self.error(format!("Expected an identifier but found {token}")),
+        }
+    }
+
+    fn add_node(&mut self, node: &str) {
+        if !self.attributes.contains_key(node) {
+            self.nodes.push(node.to_string());
+            self.attributes.insert(node.to_string(), HashMap::new());
+        }
+        for mentioned in self.subgraphs.iter_mut() {
+            if !mentioned.iter().any(|n| n == node) {
+                mentioned.push(node.to_string());
+            }
+        }
+    }
+
+    // graph := ['strict'] ('graph' | 'digraph') [id] '{' statements '}'
+    fn graph(&mut self) -> Result<(), DotError> {
+        if Self::is_keyword(self.peek(), "strict") {
+            self.next();
+        }
+        if Self::is_keyword(self.peek(), "digraph") {
+            self.directed = true;
+        } else if Self::is_keyword(self.peek(), "graph") {
+            self.directed = false;
+        } else {
+            return self.error(format!(
+                "Expected `digraph` or `graph` but found {}",
+                self.peek()
+            ));
+        }
+        self.next();
+
+        if let DotToken::Id(_) = self.peek() {
+            self.next();
+        }
+        self.expect(DotToken::LeftBrace)?;
+        self.statements()?;
+        self.expect(DotToken::RightBrace)?;
+
+        if *self.peek() != DotToken::End {
+            return self.error(format!("Unexpected {} after graph", self.peek()));
+        }
+        Ok(())
+    }
+
+    fn statements(&mut self) -> Result<(), DotError> {
+        while *self.peek() != DotToken::RightBrace {
+            self.statement()?;
+            if *self.peek() == DotToken::Semicolon {
+                self.next();
+            }
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self) -> Result<(), DotError> {
+        let token = self.peek().clone();
+
+        // Defaults for the graph, nodes or edges do not affect the model
+        if ["graph", "node", "edge"]
+            .iter()
+            .any(|k| Self::is_keyword(&token, k))
+        {
+            self.next();
+            self.attribute_list()?;
+            return Ok(());
+        }
+        if Self::is_keyword(&token, "subgraph") || token == DotToken::LeftBrace {
+            let nodes = self.subgraph()?;
+            return self.edges(nodes);
+        }
+
+        let id = self.id()?;
+        if *self.peek() == DotToken::Equals {
+            // Graph attribute
+            self.next();
+            self.id()?;
+            return Ok(());
+        }
+
+        self.add_node(&id);
+        if *self.peek() == DotToken::Edge {
+            return self.edges(vec![id]);
+        }
+
+        let attributes = self.attribute_list()?;
+        self.attributes.get_mut(&id).unwrap().extend(attributes);
+        Ok(())
+    }
+
+    // edges := ('->' (id | subgraph))* attribute_list, where `from` are the nodes before the first edge operator
+    fn edges(&mut self, from: Vec<String>) -> Result<(), DotError> {
+        let mut from = from;
+        while *self.peek() == DotToken::Edge {
+            self.next();
+            let to = if Self::is_keyword(self.peek(), "subgraph")
+                || *self.peek() == DotToken::LeftBrace
+            {
+                self.subgraph()?
+            } else {
+                let id = self.id()?;
+                self.add_node(&id);
+                vec![id]
+            };
+            for a in from.iter() {
+                for b in to.iter() {
+                    self.edges.push((a.clone(), b.clone()));
+                    if !self.directed {
+                        self.edges.push((b.clone(), a.clone()));
+                    }
+                }
+            }
+            from = to;
+        }
+
+        // Edge attributes do not affect the model
+        self.attribute_list()?;
+        Ok(())
+    }
+
+    // subgraph := ['subgraph' [id]] '{' statements '}', returns every node mentioned in the subgraph, even if it was declared before
+    fn subgraph(&mut self) -> Result<Vec<String>, DotError> {
+        if Self::is_keyword(self.peek(), "subgraph") {
+            self.next();
+            if let DotToken::Id(_) = self.peek() {
+                self.next();
+            }
+        }
+        self.subgraphs.push(Vec::new());
+        self.expect(DotToken::LeftBrace)?;
+        self.statements()?;
+        self.expect(DotToken::RightBrace)?;
+        Ok(self.subgraphs.pop().unwrap())
+    }
+
+    // attribute_list := ('[' [id '=' id ((',' | ';') id '=' id)*] ']')*
+    fn attribute_list(&mut self) -> Result<HashMap<String, String>, DotError> {
+        let mut attributes = HashMap::new();
+        while *self.peek() == DotToken::LeftBracket {
+            self.next();
+            while *self.peek() != DotToken::RightBracket {
+                let name = self.id()?;
+                self.expect(DotToken::Equals)?;
+                let value = self.id()?;
+                attributes.insert(name, value);
+                if matches!(self.peek(), DotToken::Comma | DotToken::Semicolon) {
+                    self.next();
+                }
+            }
+            self.next();
+        }
+        Ok(attributes)
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use super::{analysis::Graph, minimize::same_labels, CTLFormula, EnumerableModel};
+
+/// The states reachable from a state of each of two models, numbered so that the states of the left model come first
+struct Union<'a, A> {
+    labels: Vec<&'a Vec<A>>,
+    successors: Vec<Vec<usize>>,
+    right: usize,
+}
+
+impl<'a, A: PartialEq + Clone> Union<'a, A> {
+    fn new<S: PartialEq, T: PartialEq>(
+        left: &'a dyn EnumerableModel<S, A>,
+        left_state: &'a S,
+        right: &'a dyn EnumerableModel<T, A>,
+        right_state: &'a T,
+    ) -> Self {
+        let left_graph = Graph::explore(left, vec![left_state]);
+        let right_graph = Graph::explore(right, vec![right_state]);
+        let offset = left_graph.states.len();
+
+        let mut labels: Vec<&Vec<A>> = left_graph.states.iter().map(|s| left.labels(s)).collect();
+        labels.extend(right_graph.states.iter().map(|s| right.labels(s)));
+        let mut successors = left_graph.successors;
+        successors.extend(
+            right_graph
+                .successors
+                .into_iter()
+                .map(|nexts| nexts.into_iter().map(|j| j + offset).collect()),
+        );
+
+        Self {
+            labels,
+            successors,
+            right: offset,
+        }
+    }
+
+    /// An atom or a negated atom that holds in `state` but not in `other`, which must have different labels
+    fn literal(&self, state: usize, other: usize) -> CTLFormula<A> {
+        let (labels, other_labels) = (self.labels[state], self.labels[other]);
+        match labels.iter().find(|a| !other_labels.contains(a)) {
+            Some(a) => CTLFormula::Atom(a.clone()),
+            None => {
+                let a = other_labels.iter().find(|a| !labels.contains(a)).unwrap();
+                CTLFormula::Not(Box::new(CTLFormula::Atom(a.clone())))
+            }
+        }
+    }
+}
+
+fn conjunction<A: PartialEq>(formulas: Vec<CTLFormula<A>>) -> CTLFormula<A> {
+    let mut distinct: Vec<CTLFormula<A>> = vec![];
+    for formula in formulas {
+        if !distinct.contains(&formula) {
+            distinct.push(formula);
+        }
+    }
+    distinct
+        .into_iter()
+        .reduce(|a, b| CTLFormula::And(Box::new(a), Box::new(b)))
+        .unwrap_or(CTLFormula::True)
+}
+
+/// Function `bisimilar` that checks whether a state of a model is bisimilar to a state of another model
+///
+/// The two states are bisimilar if they have the same labels and every transition of one can be matched by a transition of the other
+/// to a bisimilar state. Bisimilar states satisfy exactly the same CTL formulas, so `bisimilar` can be used to check that a model
+/// still behaves the same after it has been changed. Only the states reachable from the given states are explored.
+///
+/// Returns `Err` with a formula that holds in `left_state` but not in `right_state` if they are not bisimilar.
+/// The formula only uses atoms, `Not`, `And`, `True` and `EX`, and is built from the round of partition refinement in which the states
+/// were told apart, in the style of Hennessy–Milner logic.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{bisimilar, model, ctl, verify, CTLFormula, HashedDiscreteModel};
+///
+/// let old = model! {
+///     idle [ready] -> busy;
+///     busy -> idle;
+/// };
+/// let new = model! {
+///     idle [ready] -> working;
+///     working -> done;
+///     done -> idle;
+/// };
+///
+/// let formula = bisimilar(&old, &"idle", &new, &"idle").unwrap_err();
+/// assert_eq!(formula, ctl!(EX(EX(Atom("ready")))));
+/// assert!(verify(&old, &"idle", &formula));
+/// assert!(!verify(&new, &"idle", &formula));
+///
+/// assert!(bisimilar(&old, &"idle", &old, &"idle").is_ok());
+/// ```
+pub fn bisimilar<S: PartialEq, T: PartialEq, A: PartialEq + Clone>(
+    left: &dyn EnumerableModel<S, A>,
+    left_state: &S,
+    right: &dyn EnumerableModel<T, A>,
+    right_state: &T,
+) -> Result<(), CTLFormula<A>> {
+    let union = Union::new(left, left_state, right, right_state);
+    let n = union.labels.len();
+
+    // The classes of the states after each round of refinement, starting with the classes of states with the same labels
+    let mut representatives: Vec<&Vec<A>> = vec![];
+    let mut rounds = vec![union
+        .labels
+        .iter()
+        .map(
+            |labels| match representatives.iter().position(|r| same_labels(labels, r)) {
+                Some(b) => b,
+                None => {
+                    representatives.push(labels);
+                    representatives.len() - 1
+                }
+            },
+        )
+        .collect::<Vec<usize>>()];
+    let mut count = representatives.len();
+
+    loop {
+        let blocks = rounds.last().unwrap();
+        let mut numbers = HashMap::new();
+        let refined: Vec<usize> = (0..n)
+            .map(|i| {
+                let mut signature: Vec<usize> =
+                    union.successors[i].iter().map(|&j| blocks[j]).collect();
+                signature.sort_unstable();
+                signature.dedup();
+                let next = numbers.len();
+                *numbers.entry((blocks[i], signature)).or_insert(next)
+            })
+            .collect();
+
+        if numbers.len() == count {
+            break;
+        }
+        count = numbers.len();
+        rounds.push(refined);
+    }
+
+    let blocks = rounds.last().unwrap();
+    if blocks[0] == blocks[union.right] {
+        return Ok(());
+    }
+
+    let mut cache = HashMap::new();
+    Err(distinguish(&union, &rounds, 0, union.right, &mut cache))
+}
+
+/// A formula that holds in `state` but not in `other`, which are in different classes after the last round
+fn distinguish<A: PartialEq + Clone>(
+    union: &Union<A>,
+    rounds: &[Vec<usize>],
+    state: usize,
+    other: usize,
+    cache: &mut HashMap<(usize, usize), CTLFormula<A>>,
+) -> CTLFormula<A> {
+    if let Some(formula) = cache.get(&(state, other)) {
+        return formula.clone();
+    }
+
+    let round = rounds
+        .iter()
+        .position(|blocks| blocks[state] != blocks[other])
+        .unwrap();
+    let formula = if round == 0 {
+        union.literal(state, other)
+    } else {
+        // The states were in the same class in the previous round, so one of them has a transition to a class the other cannot reach
+        let previous = &rounds[round - 1];
+        let unmatched = |from: usize, to: usize| {
+            union.successors[from].iter().copied().find(|&next| {
+                union.successors[to]
+                    .iter()
+                    .all(|&other_next| previous[other_next] != previous[next])
+            })
+        };
+
+        match unmatched(state, other) {
+            Some(next) => {
+                let parts = union.successors[other]
+                    .iter()
+                    .map(|&other_next| distinguish(union, rounds, next, other_next, cache))
+                    .collect();
+                CTLFormula::EX(Box::new(conjunction(parts)))
+            }
+            None => {
+                let next = unmatched(other, state).unwrap();
+                let parts = union.successors[state]
+                    .iter()
+                    .map(|&state_next| distinguish(union, rounds, next, state_next, cache))
+                    .collect();
+                CTLFormula::Not(Box::new(CTLFormula::EX(Box::new(conjunction(parts)))))
+            }
+        }
+    };
+
+    cache.insert((state, other), formula.clone());
+    formula
+}
+
+/// Function `simulates` that checks whether a state of a model simulates a state of another model
+///
+/// `right_state` simulates `left_state` if they have the same labels and every transition of `left_state` can be matched by a transition of
+/// `right_state` to a state that simulates its target. Every behaviour of `left_state` is then also a behaviour of `right_state`,
+/// so e.g. an abstraction of a system should simulate it. Only the states reachable from the given states are explored.
+///
+/// Returns `Err` with a formula that holds in `left_state` but not in `right_state` if `right_state` does not simulate `left_state`.
+/// The formula only uses atoms, negated atoms, `And`, `True` and `EX`.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{simulates, model, ctl, CTLFormula, HashedDiscreteModel};
+///
+/// let concrete = model! {
+///     s0 [idle] -> s1;
+///     s1 [busy] -> s0;
+/// };
+/// let abstraction = model! {
+///     a0 [idle] -> a1, a2;
+///     a1 [busy] -> a0;
+///     a2 [error] -> a2;
+/// };
+///
+/// assert!(simulates(&concrete, &"s0", &abstraction, &"a0").is_ok());
+/// assert_eq!(
+///     simulates(&abstraction, &"a0", &concrete, &"s0"),
+///     Err(ctl!(EX(Atom("error"))))
+/// );
+/// ```
+pub fn simulates<S: PartialEq, T: PartialEq, A: PartialEq + Clone>(
+    left: &dyn EnumerableModel<S, A>,
+    left_state: &S,
+    right: &dyn EnumerableModel<T, A>,
+    right_state: &T,
+) -> Result<(), CTLFormula<A>> {
+    let union = Union::new(left, left_state, right, right_state);
+    let (lefts, rights) = (0..union.right, union.right..union.labels.len());
+
+    // The round in which each pair of a left and a right state was removed from the simulation, indexed by the left state and the right state
+    let mut removed: Vec<Vec<Option<usize>>> = lefts
+        .clone()
+        .map(|s| {
+            rights
+                .clone()
+                .map(|t| (!same_labels(union.labels[s], union.labels[t])).then_some(0))
+                .collect()
+        })
+        .collect();
+
+    let mut round = 0;
+    loop {
+        round += 1;
+        let mut changed = false;
+        for s in lefts.clone() {
+            for t in rights.clone() {
+                if removed[s][t - union.right].is_some() {
+                    continue;
+                }
+                let unmatched = union.successors[s].iter().any(|&next| {
+                    union.successors[t].iter().all(|&other_next| {
+                        removed[next][other_next - union.right].is_some_and(|r| r < round)
+                    })
+                });
+                if unmatched {
+                    removed[s][t - union.right] = Some(round);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    if removed[0][0].is_none() {
+        return Ok(());
+    }
+
+    let mut cache = HashMap::new();
+    Err(unsimulated(&union, &removed, 0, union.right, &mut cache))
+}
+
+/// A formula that holds in the left state `state` but not in the right state `other`, which does not simulate it
+fn unsimulated<A: PartialEq + Clone>(
+    union: &Union<A>,
+    removed: &[Vec<Option<usize>>],
+    state: usize,
+    other: usize,
+    cache: &mut HashMap<(usize, usize), CTLFormula<A>>,
+) -> CTLFormula<A> {
+    if let Some(formula) = cache.get(&(state, other)) {
+        return formula.clone();
+    }
+
+    let round = removed[state][other - union.right].unwrap();
+    let formula = if round == 0 {
+        union.literal(state, other)
+    } else {
+        let next = union.successors[state]
+            .iter()
+            .copied()
+            .find(|&next| {
+                union.successors[other].iter().all(|&other_next| {
+                    removed[next][other_next - union.right].is_some_and(|r| r < round)
+                })
+            })
+            .unwrap();
+        let parts = union.successors[other]
+            .iter()
+            .map(|&other_next| unsimulated(union, removed, next, other_next, cache))
+            .collect();
+        CTLFormula::EX(Box::new(conjunction(parts)))
+    };
+
+    cache.insert((state, other), formula.clone());
+    formula
+}
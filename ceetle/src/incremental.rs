@@ -0,0 +1,413 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use super::{CTLFormula, EnumerableModel};
+
+/// # `ModelDelta`
+/// A set of edits to a model: transitions to add or remove and atoms to add to or remove from the labels of states.
+/// It is applied to an [`IncrementalChecker`] with [`apply`](IncrementalChecker::apply), which applies the removals before the additions.
+#[derive(Debug, Clone)]
+pub struct ModelDelta<S, A> {
+    added_transitions: Vec<(S, S)>,
+    removed_transitions: Vec<(S, S)>,
+    added_labels: Vec<(S, A)>,
+    removed_labels: Vec<(S, A)>,
+}
+
+impl<S, A> ModelDelta<S, A> {
+    /// # `new`
+    /// Returns a delta that does not change anything
+    pub fn new() -> Self {
+        Self {
+            added_transitions: vec![],
+            removed_transitions: vec![],
+            added_labels: vec![],
+            removed_labels: vec![],
+        }
+    }
+
+    /// # `with_added_transition`
+    /// Adds a transition from `from` to `to`
+    pub fn with_added_transition(mut self, from: S, to: S) -> Self {
+        self.added_transitions.push((from, to));
+        self
+    }
+
+    /// # `with_removed_transition`
+    /// Removes the transition from `from` to `to`
+    pub fn with_removed_transition(mut self, from: S, to: S) -> Self {
+        self.removed_transitions.push((from, to));
+        self
+    }
+
+    /// # `with_added_label`
+    /// Makes `atom` hold in `state`
+    pub fn with_added_label(mut self, state: S, atom: A) -> Self {
+        self.added_labels.push((state, atom));
+        self
+    }
+
+    /// # `with_removed_label`
+    /// Makes `atom` not hold in `state`
+    pub fn with_removed_label(mut self, state: S, atom: A) -> Self {
+        self.removed_labels.push((state, atom));
+        self
+    }
+}
+
+impl<S, A> Default for ModelDelta<S, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A subformula in existential normal form, whose subformulas are given by their index in `IncrementalChecker::nodes`
+#[derive(Debug, Clone, PartialEq)]
+enum Node<A> {
+    True,
+    Atom(A),
+    Not(usize),
+    And(usize, usize),
+    EX(usize),
+    EU(usize, usize),
+    EG(usize),
+}
+
+/// # `IncrementalChecker`
+/// A model together with properties to verify on it, whose results are updated when the model is edited.
+///
+/// The checker keeps the set of states where each subformula of the properties holds. Subformulas shared by several properties are only stored once.
+/// When a [`ModelDelta`] is applied, only the sets of the subformulas that the edits can affect are recomputed:
+/// - an atom only where its label changed
+/// - a boolean connective only where one of its subformulas changed
+/// - `EX` only in the states whose transitions changed and in the predecessors of the states where its subformula changed
+/// - `EU` and `EG` only in the states that can reach a state whose transitions changed or where one of their subformulas changed,
+///   since the result in the other states only depends on states and transitions that are left as they were
+///
+/// The properties are rewritten to existential normal form, and the results are the same as those of `verify` on the edited model.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ctl, CTLFormula, IncrementalChecker, ModelDelta, VecDiscreteModel};
+///
+/// let model = VecDiscreteModel::new(vec![
+///     ("idle", vec![],       vec!["busy"]),
+///     ("busy", vec!["busy"], vec!["idle", "error"]),
+///     ("error", vec!["error"], vec!["error"]),
+/// ]);
+///
+/// let mut checker = IncrementalChecker::new(&model)
+///     .with_property("idle", ctl!(AG(Not(Atom("error")))))
+///     .with_property("idle", ctl!(EF(Atom("busy"))));
+/// assert_eq!(checker.verdicts(), vec![false, true]);
+///
+/// let changed = checker.apply(&ModelDelta::new().with_removed_transition("busy", "error"));
+/// assert_eq!(changed, vec![0]);
+/// assert_eq!(checker.verdicts(), vec![true, true]);
+///
+/// let changed = checker.apply(&ModelDelta::new().with_removed_label("busy", "busy"));
+/// assert_eq!(changed, vec![1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IncrementalChecker<S, A> {
+    states: Vec<S>,
+    index: HashMap<S, usize>,
+    labels: Vec<Vec<A>>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+    // The distinct subformulas, each after its own subformulas, with the states where they hold
+    nodes: Vec<(Node<A>, Vec<bool>)>,
+    // The state and the node of each property, with its last result
+    properties: Vec<(usize, usize, bool)>,
+}
+
+impl<S: Eq + Hash + Clone, A: PartialEq + Clone> IncrementalChecker<S, A> {
+    /// # `new`
+    /// Returns a checker for a copy of `model` without any properties
+    pub fn new(model: &dyn EnumerableModel<S, A>) -> Self {
+        let states: Vec<S> = model.states().into_iter().cloned().collect();
+        let index: HashMap<S, usize> = states
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (state.clone(), i))
+            .collect();
+        let labels = states.iter().map(|s| model.labels(s).clone()).collect();
+        let successors: Vec<Vec<usize>> = states
+            .iter()
+            .map(|s| {
+                model
+                    .transitions(s)
+                    .iter()
+                    .map(|next| index[next])
+                    .collect()
+            })
+            .collect();
+        let mut predecessors = vec![vec![]; states.len()];
+        for (i, nexts) in successors.iter().enumerate() {
+            for &j in nexts {
+                predecessors[j].push(i);
+            }
+        }
+
+        Self {
+            states,
+            index,
+            labels,
+            successors,
+            predecessors,
+            nodes: vec![],
+            properties: vec![],
+        }
+    }
+
+    /// # `with_property`
+    /// Adds the property that `formula` holds in `state`, and verifies it
+    pub fn with_property(mut self, state: S, formula: CTLFormula<A>) -> Self {
+        let state = self.find(&state);
+        let node = self.node(&formula.to_enf());
+        let holds = self.nodes[node].1[state];
+        self.properties.push((state, node, holds));
+        self
+    }
+
+    /// # `verdicts`
+    /// Returns whether each property holds, in the order they were added
+    pub fn verdicts(&self) -> Vec<bool> {
+        self.properties.iter().map(|(_, _, holds)| *holds).collect()
+    }
+
+    /// # `apply`
+    /// Edits the model by `delta` and updates the results of the properties.
+    /// Returns the indices of the properties whose result changed, in the order they were added.
+    ///
+    /// Panics if `delta` refers to a state that is not in the model.
+    pub fn apply(&mut self, delta: &ModelDelta<S, A>) -> Vec<usize> {
+        let mut relabelled: Vec<(usize, &A)> = vec![];
+        for (state, atom) in delta.removed_labels.iter() {
+            let i = self.find(state);
+            if self.labels[i].contains(atom) {
+                self.labels[i].retain(|a| a != atom);
+                relabelled.push((i, atom));
+            }
+        }
+        for (state, atom) in delta.added_labels.iter() {
+            let i = self.find(state);
+            if !self.labels[i].contains(atom) {
+                self.labels[i].push(atom.clone());
+                relabelled.push((i, atom));
+            }
+        }
+
+        let mut rewired: Vec<usize> = vec![];
+        for (from, to) in delta.removed_transitions.iter() {
+            let (i, j) = (self.find(from), self.find(to));
+            if self.successors[i].contains(&j) {
+                self.successors[i].retain(|&next| next != j);
+                self.predecessors[j].retain(|&previous| previous != i);
+                rewired.push(i);
+            }
+        }
+        for (from, to) in delta.added_transitions.iter() {
+            let (i, j) = (self.find(from), self.find(to));
+            if !self.successors[i].contains(&j) {
+                self.successors[i].push(j);
+                self.predecessors[j].push(i);
+                rewired.push(i);
+            }
+        }
+
+        // The states where the set of each node changed
+        let mut changes: Vec<Vec<usize>> = vec![];
+        for n in 0..self.nodes.len() {
+            // The states where the node may have changed
+            let candidates: Vec<usize> = match &self.nodes[n].0 {
+                Node::True => vec![],
+                Node::Atom(atom) => relabelled
+                    .iter()
+                    .filter(|(_, a)| *a == atom)
+                    .map(|(i, _)| *i)
+                    .collect(),
+                Node::Not(f) => changes[*f].clone(),
+                Node::And(f, g) => changes[*f].iter().chain(&changes[*g]).copied().collect(),
+                Node::EX(f) => changes[*f]
+                    .iter()
+                    .flat_map(|&j| self.predecessors[j].iter().copied())
+                    .chain(rewired.iter().copied())
+                    .collect(),
+                Node::EU(f, g) => {
+                    self.reaching(changes[*f].iter().chain(&changes[*g]).chain(&rewired))
+                }
+                Node::EG(f) => self.reaching(changes[*f].iter().chain(&rewired)),
+            };
+
+            if candidates.is_empty() {
+                changes.push(vec![]);
+                continue;
+            }
+
+            // The set is taken out of the node while it is updated, so only the candidates are copied
+            let before: Vec<bool> = candidates.iter().map(|&i| self.nodes[n].1[i]).collect();
+            let mut holds = std::mem::take(&mut self.nodes[n].1);
+            self.update(&self.nodes[n].0, &candidates, &mut holds);
+            let mut changed: Vec<usize> = candidates
+                .into_iter()
+                .zip(before)
+                .filter(|&(i, before)| holds[i] != before)
+                .map(|(i, _)| i)
+                .collect();
+            changed.sort_unstable();
+            changed.dedup();
+            self.nodes[n].1 = holds;
+            changes.push(changed);
+        }
+
+        let mut changed = vec![];
+        for (p, (state, node, holds)) in self.properties.iter_mut().enumerate() {
+            let now = self.nodes[*node].1[*state];
+            if now != *holds {
+                *holds = now;
+                changed.push(p);
+            }
+        }
+        changed
+    }
+
+    fn find(&self, state: &S) -> usize {
+        match self.index.get(state) {
+            Some(&i) => i,
+            None => panic!("State is not in model!"),
+        }
+    }
+
+    /// The index of the node of a formula in existential normal form, adding it and its subformulas if they are new
+    fn node(&mut self, formula: &CTLFormula<A>) -> usize {
+        let children: Vec<usize> = formula
+            .children()
+            .into_iter()
+            .map(|f| self.node(f))
+            .collect();
+        let node = match &formula {
+            CTLFormula::True => Node::True,
+            CTLFormula::Atom(atom) => Node::Atom(atom.clone()),
+            CTLFormula::Not(_) => Node::Not(children[0]),
+            CTLFormula::And(_, _) => Node::And(children[0], children[1]),
+            CTLFormula::EX(_) => Node::EX(children[0]),
+            CTLFormula::EU(_, _) => Node::EU(children[0], children[1]),
+            CTLFormula::EG(_) => Node::EG(children[0]),
+            _ => unreachable!("The formula is not in existential normal form!"),
+        };
+
+        if let Some(n) = self.nodes.iter().position(|(other, _)| *other == node) {
+            return n;
+        }
+        let holds = self.evaluate(&node);
+        self.nodes.push((node, holds));
+        self.nodes.len() - 1
+    }
+
+    /// The states that can reach one of `seeds`, including them
+    fn reaching<'s>(&self, seeds: impl Iterator<Item = &'s usize>) -> Vec<usize> {
+        let mut visited = HashSet::new();
+        let mut stack: Vec<usize> = seeds.copied().filter(|&i| visited.insert(i)).collect();
+        let mut region = stack.clone();
+        while let Some(j) = stack.pop() {
+            for &i in self.predecessors[j].iter() {
+                if visited.insert(i) {
+                    stack.push(i);
+                    region.push(i);
+                }
+            }
+        }
+        region
+    }
+
+    /// Whether a node holds in a state, from the sets of its subformulas. Only for nodes that are not fixpoints
+    fn holds(&self, node: &Node<A>, state: usize) -> bool {
+        let set = |n: &usize| &self.nodes[*n].1;
+        match node {
+            Node::True => true,
+            Node::Atom(atom) => self.labels[state].contains(atom),
+            Node::Not(f) => !set(f)[state],
+            Node::And(f, g) => set(f)[state] && set(g)[state],
+            Node::EX(f) => self.successors[state].iter().any(|&j| set(f)[j]),
+            Node::EU(..) | Node::EG(..) => {
+                unreachable!("Fixpoints are computed in several states at once!")
+            }
+        }
+    }
+
+    /// The states where a node holds, from the sets of its subformulas
+    fn evaluate(&self, node: &Node<A>) -> Vec<bool> {
+        let n = self.states.len();
+        let mut holds = vec![false; n];
+        self.update(node, &(0..n).collect::<Vec<usize>>(), &mut holds);
+        holds
+    }
+
+    /// Recomputes where a node holds in the states of `region`, and keeps `holds` as it is in the other states.
+    /// For `EU` and `EG`, every state that can reach a state of `region` must be in `region`.
+    fn update(&self, node: &Node<A>, region: &[usize], holds: &mut [bool]) {
+        match node {
+            Node::EU(f, g) => {
+                let (f, g) = (&self.nodes[*f].1, &self.nodes[*g].1);
+                let inside: HashSet<usize> = region.iter().copied().collect();
+                for &i in region {
+                    holds[i] = g[i];
+                }
+                // Backwards search through the states of the region where `f` holds, from those where `g` holds
+                // and from the states outside the region where `E[f U g]` holds
+                let mut stack: Vec<usize> = region
+                    .iter()
+                    .flat_map(|&i| std::iter::once(i).chain(self.successors[i].iter().copied()))
+                    .filter(|&j| holds[j])
+                    .collect();
+                while let Some(j) = stack.pop() {
+                    for &i in self.predecessors[j].iter() {
+                        if inside.contains(&i) && !holds[i] && f[i] {
+                            holds[i] = true;
+                            stack.push(i);
+                        }
+                    }
+                }
+            }
+            Node::EG(f) => {
+                let f = &self.nodes[*f].1;
+                for &i in region {
+                    holds[i] = f[i];
+                }
+                // Remove the states of the region where `f` holds that have transitions but none to a state that is left, until none are removed
+                let mut count: HashMap<usize, usize> = region
+                    .iter()
+                    .map(|&i| (i, self.successors[i].iter().filter(|&&j| holds[j]).count()))
+                    .collect();
+                let mut stack: Vec<usize> = region
+                    .iter()
+                    .copied()
+                    .filter(|&i| holds[i] && count[&i] == 0 && !self.successors[i].is_empty())
+                    .collect();
+                for &i in stack.iter() {
+                    holds[i] = false;
+                }
+                while let Some(j) = stack.pop() {
+                    for &i in self.predecessors[j].iter() {
+                        let Some(count) = count.get_mut(&i) else {
+                            continue;
+                        };
+                        *count -= 1;
+                        if holds[i] && *count == 0 {
+                            holds[i] = false;
+                            stack.push(i);
+                        }
+                    }
+                }
+            }
+            _ => {
+                for &i in region {
+                    holds[i] = self.holds(node, i);
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,84 @@
 mod model;
 pub use model::*;
 
+mod model_builder;
+pub use model_builder::*;
+
+mod cache;
+
+mod state_machine;
+pub use state_machine::*;
+
+mod composition;
+pub use composition::*;
+
+mod predicate;
+pub use predicate::*;
+
+mod reduction;
+pub use reduction::*;
+
+mod symmetry;
+pub use symmetry::*;
+
 mod tl_syntax;
 pub use tl_syntax::*;
 
+/// Functions such as `atom`, `ag` and `not` that build `CTLFormula`s, kept out of the crate root so that they do not clash with names in user code
+pub mod builder;
+
+mod printer;
+pub use printer::*;
+
+mod parser;
+pub use parser::*;
+
+mod analysis;
+pub use analysis::*;
+
 mod verifier;
 pub use verifier::*;
 
+mod modal;
+pub use modal::*;
+
+mod incremental;
+pub use incremental::*;
+
+mod witness;
+pub use witness::*;
+
+mod validate;
+pub use validate::*;
+
+mod minimize;
+pub use minimize::*;
+
+mod equivalence;
+pub use equivalence::*;
+
+mod transform;
+
+mod dot;
+pub use dot::*;
+
+mod rewrite;
+pub use rewrite::*;
+
+mod smv;
+pub use smv::*;
+
+mod transition_system;
+pub use transition_system::*;
+
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "serde")]
+pub use serialization::*;
+
 pub use ceetle_macros::*;
+// The macros refer to the items of this crate as `::ceetle::..`, which must also resolve in this crate
+extern crate self as ceetle;
 
 #[cfg(test)]
 mod tests;
\ No newline at end of file
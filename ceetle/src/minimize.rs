@@ -0,0 +1,469 @@
+use std::collections::HashMap;
+
+use super::{
+    analysis::{components, Graph},
+    EnumerableModel, HashedDiscreteModel,
+};
+
+/// # `Quotient`
+/// A model whose states are classes of equivalent states of another model, built by [`bisimulation_quotient`] or [`stuttering_bisimulation_quotient`].
+///
+/// The states of `model` are the indices of `classes`, and each class holds the states of the original model it stands for.
+/// The labels of a class are those of its states, and its initial states are the classes of the initial states of the original model.
+#[derive(Debug, Clone)]
+pub struct Quotient<S, A> {
+    /// The reduced model
+    pub model: HashedDiscreteModel<usize, A>,
+    /// The states of the original model in each class, in the order they were listed by the original model
+    pub classes: Vec<Vec<S>>,
+}
+
+impl<S: PartialEq, A> Quotient<S, A> {
+    /// # `class_of`
+    /// Returns the state of the quotient model that stands for `state`, or `None` if `state` is not in the original model
+    pub fn class_of(&self, state: &S) -> Option<usize> {
+        self.classes.iter().position(|class| class.contains(state))
+    }
+}
+
+/// Function `bisimulation_quotient` that merges the states of a model that are strongly bisimilar
+///
+/// Two states are bisimilar if they have the same labels and every transition of one can be matched by a transition of the other
+/// to a bisimilar state. The largest bisimulation is computed with the partition refinement algorithm of Paige and Tarjan,
+/// in `O(m log n)` time for `n` states and `m` transitions.
+///
+/// Bisimilar states satisfy the same CTL formulas, so for every state `s` of `model` and every formula `f`,
+/// `verify(model, &s, &f)` is the same as `verify(&quotient.model, &quotient.class_of(&s).unwrap(), &f)`.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{bisimulation_quotient, ctl, verify, CTLFormula, EnumerableModel, VecDiscreteModel};
+///
+/// let model = VecDiscreteModel::new(vec![
+///     ("s0", vec!["idle"], vec!["s1", "s2"]),
+///     ("s1", vec!["busy"], vec!["s0"]),
+///     ("s2", vec!["busy"], vec!["s0"]),
+/// ]).with_initial_states(vec!["s0"]);
+///
+/// let quotient = bisimulation_quotient(&model);
+/// assert_eq!(quotient.classes, vec![vec!["s0"], vec!["s1", "s2"]]);
+/// assert_eq!(quotient.model.initial_states(), &vec![0]);
+///
+/// let formula = ctl!(AG(Imply(Atom("busy"), AX(Atom("idle")))));
+/// assert!(verify(&quotient.model, &0, &formula));
+/// ```
+pub fn bisimulation_quotient<S: PartialEq + Clone, A: PartialEq + Clone>(
+    model: &dyn EnumerableModel<S, A>,
+) -> Quotient<S, A> {
+    let graph = Graph::explore(model, model.states());
+    let initial = label_blocks(model, &graph.states);
+    let blocks = coarsest_bisimulation(&graph.successors, &graph.predecessors, &initial);
+    quotient(model, &graph, &blocks, |_, _| true)
+}
+
+/// Function `stuttering_bisimulation_quotient` that merges the states of a model that are divergence-sensitive stuttering bisimilar
+///
+/// Stuttering bisimulation is coarser than strong bisimulation: it ignores how many transitions a path takes without changing its labels,
+/// as long as it eventually does change them. Whether a path can stay with the same labels forever is not ignored,
+/// which makes the equivalence divergence-sensitive. The classes are computed by signature refinement.
+///
+/// Stuttering bisimilar states satisfy the same CTL formulas without `EX` and `AX`, so for every state `s` of `model`
+/// and every such formula `f`, `verify(model, &s, &f)` is the same as `verify(&quotient.model, &quotient.class_of(&s).unwrap(), &f)`.
+/// A class has a transition to itself if its states can stay in it forever.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{stuttering_bisimulation_quotient, ctl, verify, CTLFormula, VecDiscreteModel};
+///
+/// let model = VecDiscreteModel::new(vec![
+///     ("s0", vec!["idle"], vec!["s1"]),
+///     ("s1", vec!["idle"], vec!["s2"]),
+///     ("s2", vec!["busy"], vec!["s0"]),
+/// ]).with_initial_states(vec!["s0"]);
+///
+/// let quotient = stuttering_bisimulation_quotient(&model);
+/// assert_eq!(quotient.classes, vec![vec!["s0", "s1"], vec!["s2"]]);
+///
+/// let formula = ctl!(AG(AF(Atom("busy"))));
+/// assert!(verify(&quotient.model, &0, &formula));
+/// ```
+pub fn stuttering_bisimulation_quotient<S: PartialEq + Clone, A: PartialEq + Clone>(
+    model: &dyn EnumerableModel<S, A>,
+) -> Quotient<S, A> {
+    let graph = Graph::explore(model, model.states());
+    let initial = label_blocks(model, &graph.states);
+    let (blocks, divergent) = coarsest_stuttering_bisimulation(&graph.successors, &initial);
+    // Transitions within a class are only kept, as a self-loop, for classes whose states can stay in them forever
+    quotient(model, &graph, &blocks, |i, j| {
+        blocks[i] != blocks[j] || divergent[i]
+    })
+}
+
+fn quotient<S: PartialEq + Clone, A: PartialEq + Clone>(
+    model: &dyn EnumerableModel<S, A>,
+    graph: &Graph<S>,
+    blocks: &[usize],
+    keep: impl Fn(usize, usize) -> bool,
+) -> Quotient<S, A> {
+    let count = blocks.iter().max().map_or(0, |b| b + 1);
+    let mut classes: Vec<Vec<S>> = vec![vec![]; count];
+    let mut transitions: Vec<Vec<usize>> = vec![vec![]; count];
+
+    for (i, state) in graph.states.iter().enumerate() {
+        classes[blocks[i]].push((*state).clone());
+        for &j in graph.successors[i].iter() {
+            if keep(i, j) && !transitions[blocks[i]].contains(&blocks[j]) {
+                transitions[blocks[i]].push(blocks[j]);
+            }
+        }
+    }
+
+    let states = transitions
+        .into_iter()
+        .enumerate()
+        .map(|(b, nexts)| (b, (model.labels(&classes[b][0]).clone(), nexts)))
+        .collect();
+
+    let mut initial = vec![];
+    for state in model.initial_states() {
+        if let Some(b) = graph.index(state).map(|i| blocks[i]) {
+            if !initial.contains(&b) {
+                initial.push(b);
+            }
+        }
+    }
+
+    Quotient {
+        model: HashedDiscreteModel::new(states).with_initial_states(initial),
+        classes,
+    }
+}
+
+/// Numbers the states by their labels, so that states with the same labels, in any order, get the same number
+pub(crate) fn label_blocks<S, A: PartialEq>(
+    model: &dyn EnumerableModel<S, A>,
+    states: &[&S],
+) -> Vec<usize> {
+    let mut representatives: Vec<&Vec<A>> = vec![];
+    states
+        .iter()
+        .map(|state| {
+            let labels = model.labels(state);
+            match representatives
+                .iter()
+                .position(|other| same_labels(labels, other))
+            {
+                Some(b) => b,
+                None => {
+                    representatives.push(labels);
+                    representatives.len() - 1
+                }
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn same_labels<A: PartialEq>(left: &[A], right: &[A]) -> bool {
+    left.iter().all(|a| right.contains(a)) && right.iter().all(|a| left.contains(a))
+}
+
+/// A partition of the states `0..n` into blocks that can be split in time proportional to the number of states moved
+struct Partition {
+    block_of: Vec<usize>,
+    blocks: Vec<Vec<usize>>,
+    position: Vec<usize>,
+}
+
+impl Partition {
+    fn new(initial: &[usize]) -> Self {
+        let mut partition = Partition {
+            block_of: initial.to_vec(),
+            blocks: vec![vec![]; initial.iter().max().map_or(0, |b| b + 1)],
+            position: vec![0; initial.len()],
+        };
+        for (x, &b) in initial.iter().enumerate() {
+            partition.position[x] = partition.blocks[b].len();
+            partition.blocks[b].push(x);
+        }
+        partition
+    }
+
+    /// Moves the `marked` states of each block that also has unmarked states to a new block.
+    /// Returns the pairs of split blocks and the new blocks made from them
+    fn split(&mut self, marked: &[usize]) -> Vec<(usize, usize)> {
+        let mut touched: Vec<usize> = vec![];
+        let mut moved: HashMap<usize, Vec<usize>> = HashMap::new();
+        for &x in marked {
+            let b = self.block_of[x];
+            moved.entry(b).or_insert_with(|| {
+                touched.push(b);
+                vec![]
+            });
+            moved.get_mut(&b).unwrap().push(x);
+        }
+
+        let mut splits = vec![];
+        for b in touched {
+            let states = &moved[&b];
+            if states.len() == self.blocks[b].len() {
+                continue;
+            }
+            let new = self.blocks.len();
+            self.blocks.push(vec![]);
+            for &x in states {
+                let last = *self.blocks[b].last().unwrap();
+                self.position[last] = self.position[x];
+                self.blocks[b].swap_remove(self.position[x]);
+                self.position[x] = self.blocks[new].len();
+                self.blocks[new].push(x);
+                self.block_of[x] = new;
+            }
+            splits.push((b, new));
+        }
+        splits
+    }
+
+    /// Returns the block of each state, with the blocks numbered in the order their first state appears
+    fn numbering(&self) -> Vec<usize> {
+        let mut numbers = vec![usize::MAX; self.blocks.len()];
+        let mut next = 0;
+        self.block_of
+            .iter()
+            .map(|&b| {
+                if numbers[b] == usize::MAX {
+                    numbers[b] = next;
+                    next += 1;
+                }
+                numbers[b]
+            })
+            .collect()
+    }
+}
+
+/// The coarsest strong bisimulation refining `initial`, computed with the Paige–Tarjan algorithm.
+/// The successors must not contain duplicates. Returns the class of each state, numbered in the order their first state appears
+pub(crate) fn coarsest_bisimulation(
+    successors: &[Vec<usize>],
+    predecessors: &[Vec<usize>],
+    initial: &[usize],
+) -> Vec<usize> {
+    // ============================================================
+    //                        Algorithm
+    // The blocks of the partition are grouped in compound blocks, and
+    // the partition is kept stable with respect to every compound block:
+    // each state of a block has either some or no transitions into it.
+    // While a compound block S holds more than one block, its smaller
+    // block B is taken out of it, and every block is split by whether
+    // its states have transitions into B and whether they have
+    // transitions into S \ B. The latter is found by counting, for each
+    // state, its transitions into each compound block
+    // ============================================================
+    let mut edge_from = vec![];
+    let mut edges_into: Vec<Vec<usize>> = vec![vec![]; successors.len()];
+    for (y, froms) in predecessors.iter().enumerate() {
+        for &x in froms {
+            edges_into[y].push(edge_from.len());
+            edge_from.push(x);
+        }
+    }
+    // Each edge points to the counter of transitions from its source into the compound block of its target
+    let mut counts: Vec<usize> = successors.iter().map(|nexts| nexts.len()).collect();
+    let mut edge_count: Vec<usize> = edge_from.clone();
+
+    let mut partition = Partition::new(initial);
+    let mut compound_of = vec![0; partition.blocks.len()];
+    let mut compounds = vec![(0..partition.blocks.len()).collect::<Vec<_>>()];
+    let mut pending = vec![false];
+    let mut worklist = vec![];
+
+    let refine = |partition: &mut Partition,
+                  compound_of: &mut Vec<usize>,
+                  compounds: &mut Vec<Vec<usize>>,
+                  pending: &mut Vec<bool>,
+                  worklist: &mut Vec<usize>,
+                  marked: &[usize]| {
+        for (old, new) in partition.split(marked) {
+            let c = compound_of[old];
+            compound_of.push(c);
+            compounds[c].push(new);
+            if !pending[c] {
+                pending[c] = true;
+                worklist.push(c);
+            }
+        }
+    };
+
+    // The whole state space is the only compound block, so the partition must first be stable with respect to it
+    let has_successors: Vec<usize> = (0..successors.len())
+        .filter(|&x| !successors[x].is_empty())
+        .collect();
+    refine(
+        &mut partition,
+        &mut compound_of,
+        &mut compounds,
+        &mut pending,
+        &mut worklist,
+        &has_successors,
+    );
+    if compounds[0].len() > 1 && !pending[0] {
+        pending[0] = true;
+        worklist.push(0);
+    }
+
+    while let Some(c) = worklist.pop() {
+        pending[c] = false;
+        if compounds[c].len() < 2 {
+            continue;
+        }
+
+        let (first, second) = (compounds[c][0], compounds[c][1]);
+        let b = if partition.blocks[first].len() <= partition.blocks[second].len() {
+            first
+        } else {
+            second
+        };
+        compounds[c].retain(|&other| other != b);
+        compound_of[b] = compounds.len();
+        compounds.push(vec![b]);
+        pending.push(false);
+        if compounds[c].len() > 1 {
+            pending[c] = true;
+            worklist.push(c);
+        }
+
+        // The number of transitions from each state into B, with one of those transitions
+        let members = partition.blocks[b].clone();
+        let mut into_b: HashMap<usize, (usize, usize)> = HashMap::new();
+        let mut pre_b = vec![];
+        for &y in members.iter() {
+            for &e in edges_into[y].iter() {
+                let x = edge_from[e];
+                let entry = into_b.entry(x).or_insert_with(|| {
+                    pre_b.push(x);
+                    (0, e)
+                });
+                entry.0 += 1;
+            }
+        }
+
+        // States whose transitions into S all go into B
+        let only_b: Vec<usize> = pre_b
+            .iter()
+            .copied()
+            .filter(|x| {
+                let (count, e) = into_b[x];
+                counts[edge_count[e]] == count
+            })
+            .collect();
+
+        refine(
+            &mut partition,
+            &mut compound_of,
+            &mut compounds,
+            &mut pending,
+            &mut worklist,
+            &pre_b,
+        );
+        refine(
+            &mut partition,
+            &mut compound_of,
+            &mut compounds,
+            &mut pending,
+            &mut worklist,
+            &only_b,
+        );
+
+        let mut cells = HashMap::new();
+        for &x in pre_b.iter() {
+            cells.insert(x, counts.len());
+            counts.push(into_b[&x].0);
+        }
+        for &y in members.iter() {
+            for &e in edges_into[y].iter() {
+                counts[edge_count[e]] -= 1;
+                edge_count[e] = cells[&edge_from[e]];
+            }
+        }
+    }
+
+    partition.numbering()
+}
+
+/// The coarsest divergence-sensitive stuttering bisimulation refining `initial`, computed by signature refinement.
+/// Returns the class of each state, numbered in the order their first state appears, and whether each state can stay in its class forever
+pub(crate) fn coarsest_stuttering_bisimulation(
+    successors: &[Vec<usize>],
+    initial: &[usize],
+) -> (Vec<usize>, Vec<bool>) {
+    // ============================================================
+    //                        Algorithm
+    // A transition is inert if it stays in the same class. The
+    // signature of a state is the set of other classes it can reach
+    // after any number of inert transitions, and whether it can take
+    // inert transitions forever or until a state without transitions.
+    // Classes are split by signature until no class is split. The
+    // signatures are computed on the strongly connected components of
+    // the inert transitions, whose states all have the same signature
+    // ============================================================
+    let n = successors.len();
+    let mut blocks = initial.to_vec();
+    let mut count = blocks.iter().max().map_or(0, |b| b + 1);
+
+    loop {
+        let inert: Vec<Vec<usize>> = successors
+            .iter()
+            .enumerate()
+            .map(|(i, nexts)| {
+                nexts
+                    .iter()
+                    .copied()
+                    .filter(|&j| blocks[j] == blocks[i])
+                    .collect()
+            })
+            .collect();
+
+        let mut divergent = vec![false; n];
+        let mut signatures: Vec<Vec<usize>> = vec![vec![]; n];
+        let mut component_of = vec![0; n];
+        // The components are in reverse topological order, so those reached by inert transitions are done first
+        for (c, component) in components(&inert, &vec![true; n]).into_iter().enumerate() {
+            for &i in component.iter() {
+                component_of[i] = c;
+            }
+            let mut diverges = component.len() > 1 || inert[component[0]].contains(&component[0]);
+            let mut signature = vec![];
+            for &i in component.iter() {
+                diverges |= successors[i].is_empty();
+                for &j in successors[i].iter() {
+                    if blocks[j] != blocks[i] {
+                        signature.push(blocks[j]);
+                    } else if component_of[j] != c {
+                        diverges |= divergent[j];
+                        signature.extend(signatures[j].iter().copied());
+                    }
+                }
+            }
+            signature.sort_unstable();
+            signature.dedup();
+            for &i in component.iter() {
+                divergent[i] = diverges;
+                signatures[i] = signature.clone();
+            }
+        }
+
+        let mut numbers = HashMap::new();
+        let refined: Vec<usize> = (0..n)
+            .map(|i| {
+                let key = (blocks[i], divergent[i], std::mem::take(&mut signatures[i]));
+                let next = numbers.len();
+                *numbers.entry(key).or_insert(next)
+            })
+            .collect();
+
+        blocks = refined;
+        if numbers.len() == count {
+            return (blocks, divergent);
+        }
+        count = numbers.len();
+    }
+}
@@ -0,0 +1,442 @@
+use std::fmt;
+
+use super::CTLFormula;
+
+/// The result of verifying a formula on a `ModalModel`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Truth {
+    /// The formula holds in every model that refines the modal model
+    True,
+    /// The formula holds in no model that refines the modal model
+    False,
+    /// The modal model does not tell yet whether the formula holds
+    Unknown,
+}
+
+impl From<bool> for Truth {
+    fn from(value: bool) -> Self {
+        if value {
+            Self::True
+        } else {
+            Self::False
+        }
+    }
+}
+
+impl fmt::Display for Truth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::True => write!(f, "true"),
+            Self::False => write!(f, "false"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// A part of a `ModalModel` that is not decided yet, returned by [`ModalModel::refinement`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Refinement<S, A> {
+    /// Whether the atom holds in the state
+    Atom(S, A),
+    /// Whether the `may` transition between the two states exists
+    Transition(S, S),
+}
+
+/// # `ModalModel`
+/// A partial model, where some transitions may or may not exist and some atoms may or may not hold.
+///
+/// A state has `must` transitions, which exist in the system, and `may` transitions, which might exist. Each atom of a state holds,
+/// does not hold or is unknown. The modal model stands for all the models that decide every `may` transition and every unknown atom.
+/// [`check`](ModalModel::check) returns `Truth::True` or `Truth::False` only if the formula has that value in all of them, and `Truth::Unknown` otherwise.
+///
+/// Like for the `verify` function, `EX` does not hold and `AX` holds in a state without transitions, and `EG` holds in such a state if its formula does.
+/// Lookup is `O(n)`, where `n` is the number of states.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ctl, CTLFormula, ModalModel, Refinement, Truth};
+///
+/// let model = ModalModel::new(vec![
+///     ("idle", vec![],        vec!["busy"]),
+///     ("busy", vec!["busy"],  vec!["idle"]),
+///     ("error", vec!["error"], vec!["error"]),
+/// ])
+/// .with_may_transition("busy", "error")
+/// .with_unknown_atom("idle", "busy");
+///
+/// assert_eq!(model.check(&"idle", &ctl!(EF(Atom("busy")))), Truth::True);
+/// assert_eq!(model.check(&"idle", &ctl!(AG(Not(Atom("error"))))), Truth::Unknown);
+/// assert_eq!(model.refinement(&"idle", &ctl!(AG(Not(Atom("error"))))), Some(Refinement::Transition("busy", "error")));
+///
+/// let model = model.refine(&Refinement::Transition("busy", "error"), false);
+/// assert_eq!(model.check(&"idle", &ctl!(AG(Not(Atom("error"))))), Truth::True);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ModalModel<S, A> {
+    states: Vec<(S, Vec<A>, Vec<S>)>,
+    unknown: Vec<Vec<A>>,
+    // The transitions that may exist, which are not `must` transitions
+    may: Vec<Vec<S>>,
+}
+
+impl<S: PartialEq + Clone, A: PartialEq + Clone> ModalModel<S, A> {
+    /// # `new`
+    /// Returns a new `ModalModel` with a structure given as a `Vec` of tuples `(S, Vec<A>, Vec<S>)`, like a `VecDiscreteModel`, where
+    /// - The first value is the state label
+    /// - The second value is a `Vec` of atoms that hold in that state. The other atoms do not hold
+    /// - The last value is a `Vec` of the states that the state has a `must` transition to
+    pub fn new(states: Vec<(S, Vec<A>, Vec<S>)>) -> Self {
+        let n = states.len();
+        Self {
+            states,
+            unknown: vec![vec![]; n],
+            may: vec![vec![]; n],
+        }
+    }
+
+    /// # `with_may_transition`
+    /// Adds a transition from `from` to `to` that may or may not exist, unless there is already a transition between them
+    pub fn with_may_transition(mut self, from: S, to: S) -> Self {
+        self.find(&to);
+        let i = self.find(&from);
+        if !self.states[i].2.contains(&to) && !self.may[i].contains(&to) {
+            self.may[i].push(to);
+        }
+        self
+    }
+
+    /// # `with_unknown_atom`
+    /// Makes it unknown whether `atom` holds in `state`
+    pub fn with_unknown_atom(mut self, state: S, atom: A) -> Self {
+        let i = self.find(&state);
+        self.states[i].1.retain(|a| *a != atom);
+        if !self.unknown[i].contains(&atom) {
+            self.unknown[i].push(atom);
+        }
+        self
+    }
+
+    /// # `refine`
+    /// Decides a part of the model: the atom of a `Refinement::Atom` holds if `value` is true and does not hold otherwise,
+    /// and the transition of a `Refinement::Transition` becomes a `must` transition if `value` is true and is removed otherwise
+    pub fn refine(mut self, refinement: &Refinement<S, A>, value: bool) -> Self {
+        match refinement {
+            Refinement::Atom(state, atom) => {
+                let i = self.find(state);
+                self.unknown[i].retain(|a| a != atom);
+                if value && !self.states[i].1.contains(atom) {
+                    self.states[i].1.push(atom.clone());
+                }
+            }
+            Refinement::Transition(from, to) => {
+                let i = self.find(from);
+                if self.may[i].contains(to) {
+                    self.may[i].retain(|s| s != to);
+                    if value {
+                        self.states[i].2.push(to.clone());
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// # `check`
+    /// Returns whether `formula` holds in `state` in all the models that refine this model, in none of them, or is unknown.
+    ///
+    /// The formula is evaluated twice: pessimistically, with only the `must` transitions for existential operators and unknown atoms as false,
+    /// and optimistically, the other way around. Negations swap the two. Pessimistically, `EG(f)` also holds in a state without `must` transitions
+    /// whose `may` transitions all lead to states where it holds, since every refinement keeps none or some of them. This is sound, but may return `Truth::Unknown`
+    /// for a formula that has the same value in every refinement, e.g. `p ∨ ¬p` for an unknown `p`.
+    pub fn check(&self, state: &S, formula: &CTLFormula<A>) -> Truth {
+        let checker = Checker::new(self);
+        let formula = formula.clone().to_enf();
+        let (definite, possible) = checker.evaluate(&formula);
+        let i = self.find(state);
+        if definite[i] {
+            Truth::True
+        } else if possible[i] {
+            Truth::Unknown
+        } else {
+            Truth::False
+        }
+    }
+
+    /// # `refinement`
+    /// Returns an unknown atom or a `may` transition that `formula` is `Truth::Unknown` in `state` because of,
+    /// or `None` if the formula is `Truth::True` or `Truth::False`.
+    ///
+    /// Deciding the returned part with `refine` is a step towards knowing whether the formula holds, though it may take several steps.
+    pub fn refinement(&self, state: &S, formula: &CTLFormula<A>) -> Option<Refinement<S, A>> {
+        let checker = Checker::new(self);
+        let formula = formula.clone().to_enf();
+        let i = self.find(state);
+        if checker.is_unknown(&formula, i) {
+            Some(checker.cause(&formula, i))
+        } else {
+            None
+        }
+    }
+
+    /// # `resolve`
+    /// Refines the model until it is known whether `formula` holds in `state`, and returns the result and the refined model.
+    ///
+    /// Each time the result is `Truth::Unknown`, `decide` is called with the `refinement` to decide, and returns whether the atom holds or the transition exists.
+    /// Only the parts of the model that the result depends on are decided.
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::{ctl, CTLFormula, ModalModel, Refinement};
+    ///
+    /// let model = ModalModel::new(vec![
+    ///     ("s0", vec![], vec![]),
+    ///     ("s1", vec!["goal"], vec![]),
+    ///     ("s2", vec![], vec![]),
+    /// ])
+    /// .with_may_transition("s0", "s1")
+    /// .with_may_transition("s0", "s2")
+    /// .with_unknown_atom("s2", "goal");
+    ///
+    /// let mut asked = vec![];
+    /// let (holds, _) = model.resolve(&"s0", &ctl!(EX(Atom("goal"))), |refinement| {
+    ///     asked.push(refinement.clone());
+    ///     false
+    /// });
+    /// assert!(!holds);
+    /// assert_eq!(asked, vec![Refinement::Transition("s0", "s1"), Refinement::Atom("s2", "goal")]);
+    /// ```
+    pub fn resolve(
+        &self,
+        state: &S,
+        formula: &CTLFormula<A>,
+        mut decide: impl FnMut(&Refinement<S, A>) -> bool,
+    ) -> (bool, Self) {
+        let mut model = self.clone();
+        loop {
+            match model.check(state, formula) {
+                Truth::True => return (true, model),
+                Truth::False => return (false, model),
+                Truth::Unknown => {
+                    let refinement = model.refinement(state, formula).unwrap();
+                    let value = decide(&refinement);
+                    model = model.refine(&refinement, value);
+                }
+            }
+        }
+    }
+
+    fn find(&self, state: &S) -> usize {
+        match self.states.iter().position(|s| s.0 == *state) {
+            Some(i) => i,
+            None => panic!("State is not in model!"),
+        }
+    }
+}
+
+/// The two evaluations of formulas in existential normal form on the states of a `ModalModel`, numbered in the order of the model
+struct Checker<'m, S, A> {
+    model: &'m ModalModel<S, A>,
+    must: Vec<Vec<usize>>,
+    // The `must` and the `may` transitions together
+    may: Vec<Vec<usize>>,
+}
+
+impl<'m, S: PartialEq + Clone, A: PartialEq + Clone> Checker<'m, S, A> {
+    fn new(model: &'m ModalModel<S, A>) -> Self {
+        let must: Vec<Vec<usize>> = model
+            .states
+            .iter()
+            .map(|(_, _, nexts)| nexts.iter().map(|s| model.find(s)).collect())
+            .collect();
+        let may = must
+            .iter()
+            .zip(model.may.iter())
+            .map(|(nexts, mays)| {
+                let mut nexts = nexts.clone();
+                nexts.extend(mays.iter().map(|s| model.find(s)));
+                nexts
+            })
+            .collect();
+        Self { model, must, may }
+    }
+
+    /// The states where the formula holds in every refinement, and those where it holds in some refinement
+    fn evaluate(&self, formula: &CTLFormula<A>) -> (Vec<bool>, Vec<bool>) {
+        let n = self.model.states.len();
+        match formula {
+            CTLFormula::True => (vec![true; n], vec![true; n]),
+            CTLFormula::False => (vec![false; n], vec![false; n]),
+            CTLFormula::Atom(atom) => {
+                let definite: Vec<bool> = self
+                    .model
+                    .states
+                    .iter()
+                    .map(|(_, labels, _)| labels.contains(atom))
+                    .collect();
+                let possible = (0..n)
+                    .map(|i| definite[i] || self.model.unknown[i].contains(atom))
+                    .collect();
+                (definite, possible)
+            }
+            CTLFormula::Not(f) => {
+                let (definite, possible) = self.evaluate(f);
+                (negate(possible), negate(definite))
+            }
+            CTLFormula::And(f, g) => {
+                let ((d1, p1), (d2, p2)) = (self.evaluate(f), self.evaluate(g));
+                (zip(&d1, &d2, |a, b| a && b), zip(&p1, &p2, |a, b| a && b))
+            }
+            CTLFormula::Or(f, g) => {
+                let ((d1, p1), (d2, p2)) = (self.evaluate(f), self.evaluate(g));
+                (zip(&d1, &d2, |a, b| a || b), zip(&p1, &p2, |a, b| a || b))
+            }
+            CTLFormula::EX(f) => {
+                let (definite, possible) = self.evaluate(f);
+                (
+                    (0..n)
+                        .map(|i| self.must[i].iter().any(|&j| definite[j]))
+                        .collect(),
+                    (0..n)
+                        .map(|i| self.may[i].iter().any(|&j| possible[j]))
+                        .collect(),
+                )
+            }
+            CTLFormula::EU(f, g) => {
+                let ((d1, p1), (d2, p2)) = (self.evaluate(f), self.evaluate(g));
+                (until(&d1, &d2, &self.must).0, until(&p1, &p2, &self.may).0)
+            }
+            CTLFormula::EG(f) => {
+                let (definite, possible) = self.evaluate(f);
+                // In every refinement, a state keeps its `must` transitions, and a state without them either has no transitions
+                // or only some of its `may` transitions. A state might have no transitions if it has no `must` transitions.
+                (
+                    globally(&definite, |i, holds| {
+                        self.must[i].iter().any(|&j| holds[j])
+                            || (self.must[i].is_empty() && self.may[i].iter().all(|&j| holds[j]))
+                    }),
+                    globally(&possible, |i, holds| {
+                        self.must[i].is_empty() || self.may[i].iter().any(|&j| holds[j])
+                    }),
+                )
+            }
+            _ => unreachable!("The formula is not in existential normal form!"),
+        }
+    }
+
+    fn is_unknown(&self, formula: &CTLFormula<A>, state: usize) -> bool {
+        let (definite, possible) = self.evaluate(formula);
+        !definite[state] && possible[state]
+    }
+
+    /// An unknown atom or a `may` transition that the formula is unknown in `state` because of
+    fn cause(&self, formula: &CTLFormula<A>, state: usize) -> Refinement<S, A> {
+        let states = &self.model.states;
+        let transition =
+            |to: usize| Refinement::Transition(states[state].0.clone(), states[to].0.clone());
+
+        match formula {
+            CTLFormula::Atom(atom) => Refinement::Atom(states[state].0.clone(), atom.clone()),
+            CTLFormula::Not(f) => self.cause(f, state),
+            CTLFormula::And(f, g) | CTLFormula::Or(f, g) => {
+                if self.is_unknown(f, state) {
+                    self.cause(f, state)
+                } else {
+                    self.cause(g, state)
+                }
+            }
+            CTLFormula::EX(f) => {
+                let (definite, possible) = self.evaluate(f);
+                let next = *self.may[state].iter().find(|&&j| possible[j]).unwrap();
+                // If `f` surely holds in the target, the transition is a `may` transition, since `EX(f)` would hold otherwise
+                if definite[next] {
+                    transition(next)
+                } else {
+                    self.cause(f, next)
+                }
+            }
+            CTLFormula::EU(f, g) => {
+                if self.is_unknown(g, state) {
+                    return self.cause(g, state);
+                }
+                if self.is_unknown(f, state) {
+                    return self.cause(f, state);
+                }
+                // Both surely hold and do not hold respectively, so `state` reaches `g` in fewer steps through some `may` transition
+                let ((_, p1), (_, p2)) = (self.evaluate(f), self.evaluate(g));
+                let rounds = until(&p1, &p2, &self.may).1;
+                let next = *self.may[state]
+                    .iter()
+                    .find(|&&j| rounds[j].is_some_and(|r| Some(r) < rounds[state]))
+                    .unwrap();
+                if self.must[state].contains(&next) {
+                    self.cause(formula, next)
+                } else {
+                    transition(next)
+                }
+            }
+            CTLFormula::EG(f) => {
+                if self.is_unknown(f, state) {
+                    return self.cause(f, state);
+                }
+                if self.must[state].is_empty() {
+                    // The state surely has `f`, so `EG(f)` would hold if the state had no transitions out of the pessimistic set
+                    let definite = self.evaluate(formula).0;
+                    return transition(*self.may[state].iter().find(|&&j| !definite[j]).unwrap());
+                }
+                // The `must` successors in the optimistic set are unknown too, and following them cannot loop,
+                // since a loop of `must` transitions where `f` surely holds would be in the pessimistic set
+                let possible = self.evaluate(formula).1;
+                let next = *self.may[state].iter().find(|&&j| possible[j]).unwrap();
+                if self.must[state].contains(&next) {
+                    self.cause(formula, next)
+                } else {
+                    transition(next)
+                }
+            }
+            _ => unreachable!("The formula is not unknown!"),
+        }
+    }
+}
+
+fn negate(values: Vec<bool>) -> Vec<bool> {
+    values.into_iter().map(|v| !v).collect()
+}
+
+fn zip(first: &[bool], second: &[bool], op: impl Fn(bool, bool) -> bool) -> Vec<bool> {
+    first.iter().zip(second).map(|(&a, &b)| op(a, b)).collect()
+}
+
+/// The least fixpoint of `E[f U g]` over `successors`, with the round in which each state was added
+fn until(f: &[bool], g: &[bool], successors: &[Vec<usize>]) -> (Vec<bool>, Vec<Option<usize>>) {
+    let mut rounds: Vec<Option<usize>> = g.iter().map(|&holds| holds.then_some(0)).collect();
+    let mut round = 0;
+    loop {
+        round += 1;
+        let added: Vec<usize> = (0..f.len())
+            .filter(|&i| {
+                rounds[i].is_none() && f[i] && successors[i].iter().any(|&j| rounds[j].is_some())
+            })
+            .collect();
+        if added.is_empty() {
+            break;
+        }
+        for i in added {
+            rounds[i] = Some(round);
+        }
+    }
+    (rounds.iter().map(Option::is_some).collect(), rounds)
+}
+
+/// The greatest fixpoint of `EG(f)`, where a state where `f` holds stays in the set while `continues` holds for it and the set
+fn globally(f: &[bool], continues: impl Fn(usize, &[bool]) -> bool) -> Vec<bool> {
+    let mut holds = f.to_vec();
+    loop {
+        let next: Vec<bool> = (0..f.len())
+            .map(|i| holds[i] && continues(i, &holds))
+            .collect();
+        if next == holds {
+            return holds;
+        }
+        holds = next;
+    }
+}
@@ -16,12 +16,24 @@ pub trait Model<S, A> {
     fn transitions(&self, state: &S) -> &Vec<S>;
 }
 
+/// Trait for models whose states can be listed
+///
+/// An `EnumerableModel` extends `Model` with functions to list all states of the model, the atoms that hold in each state and the initial states.
+/// Functions that need to look at the whole model, such as exporting it or analysing its structure, take an `EnumerableModel`.
+pub trait EnumerableModel<S, A>: Model<S, A> {
+    fn states(&self) -> Vec<&S>;
+    fn labels(&self, state: &S) -> &Vec<A>;
+    fn initial_states(&self) -> &Vec<S>;
+}
+
 /// A `Vector`-based model of a Finite State Automaton
 /// 
 /// The `VecDiscreteModel` is identical to a finite-state automaton. It uses a `Vec` to store each state together with its atoms and transitions. 
 /// Therefore, lookup is `O(n)`, where `n` is the number of states.
+#[derive(Debug, Clone)]
 pub struct VecDiscreteModel<S, A> {
-    states: Vec<(S, Vec<A>, Vec<S>)>,
+    pub(crate) states: Vec<(S, Vec<A>, Vec<S>)>,
+    pub(crate) initial: Vec<S>,
 }
 
 impl<S: PartialEq, A: PartialEq> VecDiscreteModel<S, A> {
@@ -43,8 +55,16 @@ impl<S: PartialEq, A: PartialEq> VecDiscreteModel<S, A> {
     pub fn new(states: Vec<(S, Vec<A>, Vec<S>)>) -> Self {
         Self {
             states,
+            initial: Vec::new(),
         }
     }
+
+    /// # `with_initial_states`
+    /// Sets the initial states of the model. Models have no initial states unless given by this function.
+    pub fn with_initial_states(mut self, initial: Vec<S>) -> Self {
+        self.initial = initial;
+        self
+    }
 }
 
 impl<S: PartialEq, A: PartialEq> Model<S, A> for VecDiscreteModel<S, A> {
@@ -54,7 +74,7 @@ impl<S: PartialEq, A: PartialEq> Model<S, A> for VecDiscreteModel<S, A> {
     fn state_has(&self, state: &S, atom: &A) -> bool {
         for s in self.states.iter() {
             if s.0 == *state {
-                return s.1.contains(&atom);
+                return s.1.contains(atom);
             }
         }
         panic!("State is not in model!");
@@ -72,12 +92,39 @@ impl<S: PartialEq, A: PartialEq> Model<S, A> for VecDiscreteModel<S, A> {
     }
 }
 
+impl<S: PartialEq, A: PartialEq> EnumerableModel<S, A> for VecDiscreteModel<S, A> {
+    /// # `states`
+    /// Returns the states of the model in the order they were given
+    fn states(&self) -> Vec<&S> {
+        self.states.iter().map(|s| &s.0).collect()
+    }
+
+    /// # `labels`
+    /// Takes a state and returns a `&Vec` of the atoms that hold in that state. This operation is `O(n)`, where `n` is the number of states.
+    fn labels(&self, state: &S) -> &Vec<A> {
+        for s in self.states.iter() {
+            if s.0 == *state {
+                return &s.1;
+            }
+        }
+        panic!("State is not in model!");
+    }
+
+    /// # `initial_states`
+    /// Returns the initial states of the model, set by `with_initial_states`
+    fn initial_states(&self) -> &Vec<S> {
+        &self.initial
+    }
+}
+
 /// A `HashMap`-based model for a Finite State Automaton
 /// 
 /// The `HashedDiscreteModel` is identical to a finite-state automaton. It uses a `HashMap` to store each state together with its atoms and transitions. 
 /// Therefore, lookup is `O(1)`.
+#[derive(Debug, Clone)]
 pub struct HashedDiscreteModel<S, A> {
-    states: HashMap<S, (Vec<A>, Vec<S>)>,
+    pub(crate) states: HashMap<S, (Vec<A>, Vec<S>)>,
+    pub(crate) initial: Vec<S>,
 }
 
 impl<S: PartialEq, A: PartialEq> HashedDiscreteModel<S, A> {
@@ -102,9 +149,17 @@ impl<S: PartialEq, A: PartialEq> HashedDiscreteModel<S, A> {
     /// ```
     pub fn new(states: HashMap<S, (Vec<A>, Vec<S>)>) -> Self {
         Self {
-            states
+            states,
+            initial: Vec::new(),
         }
     }
+
+    /// # `with_initial_states`
+    /// Sets the initial states of the model. Models have no initial states unless given by this function.
+    pub fn with_initial_states(mut self, initial: Vec<S>) -> Self {
+        self.initial = initial;
+        self
+    }
 }
 
 impl<S: Eq + Hash, A: PartialEq> Model<S, A> for HashedDiscreteModel<S, A> {
@@ -112,8 +167,8 @@ impl<S: Eq + Hash, A: PartialEq> Model<S, A> for HashedDiscreteModel<S, A> {
     /// # `state_has`
     /// Takes a state and an atom and returns true if the atom holds in that state. This operation is `O(1)`.
     fn state_has(&self, state: &S, atom: &A) -> bool {
-        if let Some(atoms) = self.states.get(&state) {
-            return atoms.0.contains(&atom);
+        if let Some(atoms) = self.states.get(state) {
+            return atoms.0.contains(atom);
         }
         panic!("State is not in model!");
     }
@@ -121,10 +176,32 @@ impl<S: Eq + Hash, A: PartialEq> Model<S, A> for HashedDiscreteModel<S, A> {
     /// # `transitions`
     /// Takes a state and returns a `&Vec` of the states that the given state can transition to. This operation is `O(1)`.
     fn transitions(&self, state: &S) -> &Vec<S> {
-        if let Some(nexts) = self.states.get(&state) {
-            return &(*nexts).1;
+        if let Some(nexts) = self.states.get(state) {
+            return &nexts.1;
         }
         panic!("State is not in model!");
     }
 }
 
+impl<S: Eq + Hash, A: PartialEq> EnumerableModel<S, A> for HashedDiscreteModel<S, A> {
+    /// # `states`
+    /// Returns the states of the model in an unspecified order
+    fn states(&self) -> Vec<&S> {
+        self.states.keys().collect()
+    }
+
+    /// # `labels`
+    /// Takes a state and returns a `&Vec` of the atoms that hold in that state. This operation is `O(1)`.
+    fn labels(&self, state: &S) -> &Vec<A> {
+        if let Some(atoms) = self.states.get(state) {
+            return &atoms.0;
+        }
+        panic!("State is not in model!");
+    }
+
+    /// # `initial_states`
+    /// Returns the initial states of the model, set by `with_initial_states`
+    fn initial_states(&self) -> &Vec<S> {
+        &self.initial
+    }
+}
@@ -0,0 +1,377 @@
+use std::{collections::HashMap, error::Error, fmt, hash::Hash};
+
+use super::{HashedDiscreteModel, VecDiscreteModel};
+
+/// Error returned when building or changing a model would make it malformed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelError<S> {
+    /// The state is already in the model
+    DuplicateState(S),
+    /// The state is not in the model
+    UnknownState(S),
+    /// There is no transition between the two states
+    UnknownTransition(S, S),
+}
+
+impl<S: fmt::Debug> fmt::Display for ModelError<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateState(state) => write!(f, "State {state:?} is already in the model"),
+            Self::UnknownState(state) => write!(f, "State {state:?} is not in the model"),
+            Self::UnknownTransition(from, to) => {
+                write!(f, "There is no transition from {from:?} to {to:?}")
+            }
+        }
+    }
+}
+
+impl<S: fmt::Debug> Error for ModelError<S> {}
+
+/// # `ModelBuilder`
+/// Builds a `HashedDiscreteModel` or a `VecDiscreteModel` one state and one transition at a time.
+/// States, transitions and initial states can be given in any order, they are only checked when the model is built.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ModelBuilder, ModelError, Model};
+///
+/// let model = ModelBuilder::new()
+///     .with_transition("s0", "s1")
+///     .with_state("s0", vec!["p"])
+///     .with_state("s1", vec!["q"])
+///     .with_transition("s1", "s1")
+///     .with_initial("s0")
+///     .build()
+///     .unwrap();
+/// assert_eq!(model.transitions(&"s0"), &vec!["s1"]);
+///
+/// let error = ModelBuilder::<_, &str>::new().with_transition("s0", "s1").build().unwrap_err();
+/// assert_eq!(error, ModelError::UnknownState("s0"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ModelBuilder<S, A> {
+    states: Vec<(S, Vec<A>)>,
+    transitions: Vec<(S, S)>,
+    initial: Vec<S>,
+}
+
+impl<S: PartialEq + Clone, A: PartialEq> ModelBuilder<S, A> {
+    /// # `new`
+    /// Returns a builder of a model with no states
+    pub fn new() -> Self {
+        Self {
+            states: Vec::new(),
+            transitions: Vec::new(),
+            initial: Vec::new(),
+        }
+    }
+
+    /// # `with_state`
+    /// Adds a state where the given atoms hold
+    pub fn with_state(mut self, state: S, labels: Vec<A>) -> Self {
+        self.states.push((state, labels));
+        self
+    }
+
+    /// # `with_transition`
+    /// Adds a transition from `from` to `to`. Adding the same transition twice adds it once
+    pub fn with_transition(mut self, from: S, to: S) -> Self {
+        self.transitions.push((from, to));
+        self
+    }
+
+    /// # `with_initial`
+    /// Marks a state as initial
+    pub fn with_initial(mut self, state: S) -> Self {
+        self.initial.push(state);
+        self
+    }
+
+    /// # `build_vec`
+    /// Returns the `VecDiscreteModel` with the states in the order they were added.
+    /// Fails if a state is added twice or if a transition or an initial state refers to a state that is not added.
+    pub fn build_vec(self) -> Result<VecDiscreteModel<S, A>, ModelError<S>> {
+        let mut states: Vec<(S, Vec<A>, Vec<S>)> = Vec::new();
+        for (state, labels) in self.states {
+            if states.iter().any(|s| s.0 == state) {
+                return Err(ModelError::DuplicateState(state));
+            }
+            states.push((state, labels, Vec::new()));
+        }
+
+        for (from, to) in self.transitions {
+            if !states.iter().any(|s| s.0 == from) {
+                return Err(ModelError::UnknownState(from));
+            }
+            if !states.iter().any(|s| s.0 == to) {
+                return Err(ModelError::UnknownState(to));
+            }
+            let entry = states.iter_mut().find(|s| s.0 == from).unwrap();
+            if !entry.2.contains(&to) {
+                entry.2.push(to);
+            }
+        }
+
+        if let Some(state) = self
+            .initial
+            .iter()
+            .find(|state| !states.iter().any(|s| s.0 == **state))
+        {
+            return Err(ModelError::UnknownState(state.clone()));
+        }
+
+        Ok(VecDiscreteModel::new(states).with_initial_states(self.initial))
+    }
+}
+
+impl<S: Eq + Hash + Clone, A: PartialEq> ModelBuilder<S, A> {
+    /// # `build`
+    /// Returns the `HashedDiscreteModel`.
+    /// Fails if a state is added twice or if a transition or an initial state refers to a state that is not added.
+    pub fn build(self) -> Result<HashedDiscreteModel<S, A>, ModelError<S>> {
+        let mut states = HashMap::new();
+        for (state, labels) in self.states {
+            if states.contains_key(&state) {
+                return Err(ModelError::DuplicateState(state));
+            }
+            states.insert(state, (labels, Vec::new()));
+        }
+
+        let mut model = HashedDiscreteModel::new(states);
+        for (from, to) in self.transitions {
+            model.add_transition(&from, to)?;
+        }
+        for state in self.initial.iter() {
+            if !model.states.contains_key(state) {
+                return Err(ModelError::UnknownState(state.clone()));
+            }
+        }
+
+        Ok(model.with_initial_states(self.initial))
+    }
+}
+
+impl<S: PartialEq + Clone, A: PartialEq> Default for ModelBuilder<S, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ================== MUTATIONS ==================
+// Changes to existing models. Every change is checked, so a model that only refers to its own states keeps doing so.
+
+impl<S: Eq + Hash + Clone, A: PartialEq> HashedDiscreteModel<S, A> {
+    /// # `add_state`
+    /// Adds a state without transitions where the given atoms hold. Fails if the state is already in the model
+    pub fn add_state(&mut self, state: S, labels: Vec<A>) -> Result<(), ModelError<S>> {
+        if self.states.contains_key(&state) {
+            return Err(ModelError::DuplicateState(state));
+        }
+        self.states.insert(state, (labels, Vec::new()));
+        Ok(())
+    }
+
+    /// # `add_transition`
+    /// Adds a transition from `from` to `to`, unless it is already in the model. Fails if either state is not in the model
+    pub fn add_transition(&mut self, from: &S, to: S) -> Result<(), ModelError<S>> {
+        if !self.states.contains_key(from) {
+            return Err(ModelError::UnknownState(from.clone()));
+        }
+        if !self.states.contains_key(&to) {
+            return Err(ModelError::UnknownState(to));
+        }
+        let (_, nexts) = self.states.get_mut(from).unwrap();
+        if !nexts.contains(&to) {
+            nexts.push(to);
+        }
+        Ok(())
+    }
+
+    /// # `remove_transition`
+    /// Removes the transition from `from` to `to`. Fails if there is no such transition
+    pub fn remove_transition(&mut self, from: &S, to: &S) -> Result<(), ModelError<S>> {
+        let nexts = match self.states.get_mut(from) {
+            Some((_, nexts)) if nexts.contains(to) => nexts,
+            _ => return Err(ModelError::UnknownTransition(from.clone(), to.clone())),
+        };
+        nexts.retain(|next| next != to);
+        Ok(())
+    }
+
+    /// # `remove_state`
+    /// Removes a state together with its transitions and the transitions leading to it, and returns the atoms that held in it.
+    /// The state is no longer initial. Fails if the state is not in the model
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::{model, HashedDiscreteModel, Model, EnumerableModel};
+    ///
+    /// let mut model = model! {
+    ///     s0 [p] -> s0, s1;
+    ///     s1 [q] -> s0;
+    ///     init s0, s1;
+    /// };
+    ///
+    /// assert_eq!(model.remove_state(&"s1"), Ok(vec!["q"]));
+    /// assert_eq!(model.transitions(&"s0"), &vec!["s0"]);
+    /// assert_eq!(model.initial_states(), &vec!["s0"]);
+    /// ```
+    pub fn remove_state(&mut self, state: &S) -> Result<Vec<A>, ModelError<S>> {
+        let Some((labels, _)) = self.states.remove(state) else {
+            return Err(ModelError::UnknownState(state.clone()));
+        };
+        for (_, nexts) in self.states.values_mut() {
+            nexts.retain(|next| next != state);
+        }
+        self.initial.retain(|initial| initial != state);
+        Ok(labels)
+    }
+
+    /// # `set_labels`
+    /// Replaces the atoms that hold in a state and returns the previous ones. Fails if the state is not in the model
+    pub fn set_labels(&mut self, state: &S, labels: Vec<A>) -> Result<Vec<A>, ModelError<S>> {
+        match self.states.get_mut(state) {
+            Some((old, _)) => Ok(std::mem::replace(old, labels)),
+            None => Err(ModelError::UnknownState(state.clone())),
+        }
+    }
+
+    /// # `merge`
+    /// Adds the states, transitions and initial states of `other` to the model.
+    /// A state in both models keeps its atoms and transitions and gets those of `other` as well.
+    /// Fails without changing the model if a transition of `other` leads to a state in neither model
+    pub fn merge(&mut self, other: HashedDiscreteModel<S, A>) -> Result<(), ModelError<S>> {
+        for (_, nexts) in other.states.values() {
+            if let Some(next) = nexts
+                .iter()
+                .find(|next| !self.states.contains_key(next) && !other.states.contains_key(next))
+            {
+                return Err(ModelError::UnknownState(next.clone()));
+            }
+        }
+
+        for (state, (labels, nexts)) in other.states {
+            let (old_labels, old_nexts) =
+                self.states.entry(state).or_insert((Vec::new(), Vec::new()));
+            for label in labels {
+                if !old_labels.contains(&label) {
+                    old_labels.push(label);
+                }
+            }
+            for next in nexts {
+                if !old_nexts.contains(&next) {
+                    old_nexts.push(next);
+                }
+            }
+        }
+        for state in other.initial {
+            if !self.initial.contains(&state) {
+                self.initial.push(state);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: PartialEq + Clone, A: PartialEq> VecDiscreteModel<S, A> {
+    fn position(&self, state: &S) -> Result<usize, ModelError<S>> {
+        self.states
+            .iter()
+            .position(|s| s.0 == *state)
+            .ok_or_else(|| ModelError::UnknownState(state.clone()))
+    }
+
+    /// # `add_state`
+    /// Adds a state without transitions where the given atoms hold, after the other states. Fails if the state is already in the model
+    pub fn add_state(&mut self, state: S, labels: Vec<A>) -> Result<(), ModelError<S>> {
+        if self.position(&state).is_ok() {
+            return Err(ModelError::DuplicateState(state));
+        }
+        self.states.push((state, labels, Vec::new()));
+        Ok(())
+    }
+
+    /// # `add_transition`
+    /// Adds a transition from `from` to `to`, unless it is already in the model. Fails if either state is not in the model
+    pub fn add_transition(&mut self, from: &S, to: S) -> Result<(), ModelError<S>> {
+        let i = self.position(from)?;
+        self.position(&to)?;
+        if !self.states[i].2.contains(&to) {
+            self.states[i].2.push(to);
+        }
+        Ok(())
+    }
+
+    /// # `remove_transition`
+    /// Removes the transition from `from` to `to`. Fails if there is no such transition
+    pub fn remove_transition(&mut self, from: &S, to: &S) -> Result<(), ModelError<S>> {
+        match self.position(from) {
+            Ok(i) if self.states[i].2.contains(to) => {
+                self.states[i].2.retain(|next| next != to);
+                Ok(())
+            }
+            _ => Err(ModelError::UnknownTransition(from.clone(), to.clone())),
+        }
+    }
+
+    /// # `remove_state`
+    /// Removes a state together with its transitions and the transitions leading to it, and returns the atoms that held in it.
+    /// The state is no longer initial. Fails if the state is not in the model
+    pub fn remove_state(&mut self, state: &S) -> Result<Vec<A>, ModelError<S>> {
+        let i = self.position(state)?;
+        let (_, labels, _) = self.states.remove(i);
+        for (_, _, nexts) in self.states.iter_mut() {
+            nexts.retain(|next| next != state);
+        }
+        self.initial.retain(|initial| initial != state);
+        Ok(labels)
+    }
+
+    /// # `set_labels`
+    /// Replaces the atoms that hold in a state and returns the previous ones. Fails if the state is not in the model
+    pub fn set_labels(&mut self, state: &S, labels: Vec<A>) -> Result<Vec<A>, ModelError<S>> {
+        let i = self.position(state)?;
+        Ok(std::mem::replace(&mut self.states[i].1, labels))
+    }
+
+    /// # `merge`
+    /// Adds the states, transitions and initial states of `other` to the model, with the new states after the other states.
+    /// A state in both models keeps its atoms and transitions and gets those of `other` as well.
+    /// Fails without changing the model if a transition of `other` leads to a state in neither model
+    pub fn merge(&mut self, other: VecDiscreteModel<S, A>) -> Result<(), ModelError<S>> {
+        for (_, _, nexts) in other.states.iter() {
+            if let Some(next) = nexts.iter().find(|next| {
+                self.position(next).is_err() && !other.states.iter().any(|s| s.0 == **next)
+            }) {
+                return Err(ModelError::UnknownState(next.clone()));
+            }
+        }
+
+        for (state, labels, nexts) in other.states {
+            let i = match self.position(&state) {
+                Ok(i) => i,
+                Err(_) => {
+                    self.states.push((state, Vec::new(), Vec::new()));
+                    self.states.len() - 1
+                }
+            };
+            let (_, old_labels, old_nexts) = &mut self.states[i];
+            for label in labels {
+                if !old_labels.contains(&label) {
+                    old_labels.push(label);
+                }
+            }
+            for next in nexts {
+                if !old_nexts.contains(&next) {
+                    old_nexts.push(next);
+                }
+            }
+        }
+        for state in other.initial {
+            if !self.initial.contains(&state) {
+                self.initial.push(state);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,297 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use super::{printer::is_atom_char, CTLFormula};
+
+/// Error returned when a formula in text syntax cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Character offset in the input where the error was found
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Quoted(String),
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+    Top,
+    Bottom,
+    Not,
+    And,
+    Or,
+    Imply,
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "`{name}`"),
+            Self::Quoted(name) => write!(f, "`\"{name}\"`"),
+            Self::LeftParen => write!(f, "`(`"),
+            Self::RightParen => write!(f, "`)`"),
+            Self::LeftBracket => write!(f, "`[`"),
+            Self::RightBracket => write!(f, "`]`"),
+            Self::Top => write!(f, "`true`"),
+            Self::Bottom => write!(f, "`false`"),
+            Self::Not => write!(f, "`!`"),
+            Self::And => write!(f, "`&`"),
+            Self::Or => write!(f, "`|`"),
+            Self::Imply => write!(f, "`->`"),
+            Self::End => write!(f, "end of input"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let start = i;
+        let token = match chars[i] {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            '⊤' => Token::Top,
+            '⊥' => Token::Bottom,
+            '!' | '¬' | '~' => Token::Not,
+            '∧' => Token::And,
+            '∨' => Token::Or,
+            '→' => Token::Imply,
+            '&' => {
+                if chars.get(i + 1) == Some(&'&') {
+                    i += 1;
+                }
+                Token::And
+            }
+            '|' => {
+                if chars.get(i + 1) == Some(&'|') {
+                    i += 1;
+                }
+                Token::Or
+            }
+            '-' | '=' if chars.get(i + 1) == Some(&'>') => {
+                i += 1;
+                Token::Imply
+            }
+            '"' => {
+                let mut name = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => {
+                            return Err(ParseError {
+                                position: start,
+                                message: "Unterminated quoted atom".to_string(),
+                            })
+                        }
+                        Some('"') => break,
+                        Some('\\') if i + 1 < chars.len() => {
+                            name.push(chars[i + 1]);
+                            i += 2;
+                        }
+                        Some(&c) => {
+                            name.push(c);
+                            i += 1;
+                        }
+                    }
+                }
+                Token::Quoted(name)
+            }
+            c if is_atom_char(c) => {
+                while i + 1 < chars.len() && is_atom_char(chars[i + 1]) {
+                    i += 1;
+                }
+                let name: String = chars[start..=i].iter().collect();
+                match name.as_str() {
+                    "true" => Token::Top,
+                    "false" => Token::Bottom,
+                    _ => Token::Name(name),
+                }
+            }
+            c => {
+                return Err(ParseError {
+                    position: start,
+                    message: format!("Unexpected character `{c}`"),
+                })
+            }
+        };
+        tokens.push((start, token));
+        i += 1;
+    }
+
+    tokens.push((chars.len(), Token::End));
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    current: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.current].1
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.current].1.clone();
+        if token != Token::End {
+            self.current += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, ParseError> {
+        Err(ParseError {
+            position: self.tokens[self.current].0,
+            message,
+        })
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        if *self.peek() != expected {
+            return self.error(format!("Expected {expected} but found {}", self.peek()));
+        }
+        self.next();
+        Ok(())
+    }
+
+    // imply := or ('->' imply)?
+    fn imply(&mut self) -> Result<CTLFormula<String>, ParseError> {
+        let left = self.or()?;
+        if *self.peek() == Token::Imply {
+            self.next();
+            let right = self.imply()?;
+            return Ok(CTLFormula::Imply(Box::new(left), Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    // or := and ('|' and)*
+    fn or(&mut self) -> Result<CTLFormula<String>, ParseError> {
+        let mut left = self.and()?;
+        while *self.peek() == Token::Or {
+            self.next();
+            let right = self.and()?;
+            left = CTLFormula::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    // and := unary ('&' unary)*
+    fn and(&mut self) -> Result<CTLFormula<String>, ParseError> {
+        let mut left = self.unary()?;
+        while *self.peek() == Token::And {
+            self.next();
+            let right = self.unary()?;
+            left = CTLFormula::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    // unary := '!' unary | ('AG' | 'AF' | 'AX' | 'EG' | 'EF' | 'EX') unary
+    //        | ('A' | 'E') '[' imply 'U' imply ']' | '(' imply ')' | 'true' | 'false' | atom
+    fn unary(&mut self) -> Result<CTLFormula<String>, ParseError> {
+        match self.next() {
+            Token::Not => Ok(CTLFormula::Not(Box::new(self.unary()?))),
+            Token::Top => Ok(CTLFormula::True),
+            Token::Bottom => Ok(CTLFormula::False),
+            Token::Quoted(name) => Ok(CTLFormula::Atom(name)),
+            Token::LeftParen => {
+                let inner = self.imply()?;
+                self.expect(Token::RightParen)?;
+                Ok(inner)
+            }
+            Token::Name(name) => match name.as_str() {
+                "AG" => Ok(CTLFormula::AG(Box::new(self.unary()?))),
+                "AF" => Ok(CTLFormula::AF(Box::new(self.unary()?))),
+                "AX" => Ok(CTLFormula::AX(Box::new(self.unary()?))),
+                "EG" => Ok(CTLFormula::EG(Box::new(self.unary()?))),
+                "EF" => Ok(CTLFormula::EF(Box::new(self.unary()?))),
+                "EX" => Ok(CTLFormula::EX(Box::new(self.unary()?))),
+                "A" | "E" => {
+                    self.expect(Token::LeftBracket)?;
+                    let formula = self.imply()?;
+                    self.expect(Token::Name("U".to_string()))?;
+                    let until = self.imply()?;
+                    self.expect(Token::RightBracket)?;
+                    if name == "A" {
+                        Ok(CTLFormula::AU(Box::new(formula), Box::new(until)))
+                    } else {
+                        Ok(CTLFormula::EU(Box::new(formula), Box::new(until)))
+                    }
+                }
+                "U" => {
+                    self.current -= 1;
+                    self.error("Unexpected `U` outside of `A[..]` or `E[..]`".to_string())
+                }
+                _ => Ok(CTLFormula::Atom(name)),
+            },
+            token => {
+                if token != Token::End {
+                    self.current -= 1;
+                }
+                self.error(format!("Expected a formula but found {token}"))
+            }
+        }
+    }
+}
+
+/// Function `parse_formula` that parses a CTL formula written in text syntax
+///
+/// The syntax accepts both the ASCII and the Unicode notation printed by [`CTLFormula::display`], as well as the output of the `Display` implementation of `CTLFormula`:
+/// - `true`, `false`, `⊤` and `⊥` for the constants
+/// - `!f`, `~f` or `¬f` for negation, `f & g`, `f && g` or `f ∧ g` for conjunction, `f | g`, `f || g` or `f ∨ g` for disjunction and `f -> g`, `f => g` or `f → g` for implication
+/// - `AG f`, `AF f`, `AX f`, `EG f`, `EF f`, `EX f`, `A[f U g]` and `E[f U g]` for the temporal operators
+/// - Atoms are names made of letters, digits, `_` and `.`, or any text in double quotes, e.g. `"x > 3"`
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ctl, parse_formula, CTLFormula};
+///
+/// let f = parse_formula("AG(req -> AF ack) & E[!err U done]").unwrap();
+/// assert_eq!(f, ctl!(And(
+///     AG(Imply(Atom("req".to_string()), AF(Atom("ack".to_string())))),
+///     EU(Not(Atom("err".to_string())), Atom("done".to_string()))
+/// )));
+/// ```
+pub fn parse_formula(input: &str) -> Result<CTLFormula<String>, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        current: 0,
+    };
+
+    let formula = parser.imply()?;
+    if *parser.peek() != Token::End {
+        return parser.error(format!("Unexpected {} after formula", parser.peek()));
+    }
+    Ok(formula)
+}
+
+impl FromStr for CTLFormula<String> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_formula(s)
+    }
+}
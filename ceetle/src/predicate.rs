@@ -0,0 +1,141 @@
+use std::{fmt, rc::Rc};
+
+use super::Model;
+
+/// # `Predicate`
+/// An atom that holds in the states for which a function returns true, so that formulas can talk about any data of the states.
+///
+/// Predicates are compared by identity: a predicate is only equal to itself and its clones, even if another predicate has the same name.
+/// The name is only used to print formulas. Use predicates as atoms on a [`PredicateModel`].
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ctl, CTLFormula, Predicate};
+///
+/// let even = Predicate::new("even", |n: &u32| n % 2 == 0);
+/// assert!(even.holds(&4));
+/// assert_eq!(ctl!(AG(Atom(even))).to_string(), "AG(even)");
+/// ```
+#[derive(Clone)]
+pub struct Predicate<S> {
+    name: String,
+    test: Rc<dyn Fn(&S) -> bool>,
+}
+
+impl<S> Predicate<S> {
+    /// # `new`
+    /// Returns a predicate named `name` that holds in the states where `test` returns true
+    pub fn new(name: &str, test: impl Fn(&S) -> bool + 'static) -> Self {
+        Self {
+            name: name.to_string(),
+            test: Rc::new(test),
+        }
+    }
+
+    /// # `name`
+    /// Returns the name of the predicate
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// # `holds`
+    /// Returns true if the predicate holds in `state`
+    pub fn holds(&self, state: &S) -> bool {
+        (self.test)(state)
+    }
+}
+
+impl<S> PartialEq for Predicate<S> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.test, &other.test)
+    }
+}
+
+impl<S> fmt::Debug for Predicate<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Predicate").field(&self.name).finish()
+    }
+}
+
+impl<S> fmt::Display for Predicate<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// # `PredicateModel`
+/// A model with the transitions of another model whose atoms are evaluated on the states themselves instead of being looked up in labels.
+///
+/// The atoms of a `PredicateModel` are either
+/// - `Predicate`s, which hold where their function returns true
+/// - names of the predicates added with `with_predicate`, as `&str`. Names of no predicate never hold
+///
+/// The atoms of the wrapped model are not used.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ctl, verify, CTLFormula, Predicate, PredicateModel, VecDiscreteModel};
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct Counter { value: u32, limit: u32 }
+///
+/// let (c0, c1, c2) = (Counter { value: 0, limit: 2 }, Counter { value: 1, limit: 2 }, Counter { value: 2, limit: 2 });
+/// let counter: VecDiscreteModel<Counter, ()> = VecDiscreteModel::new(vec![
+///     (c0.clone(), vec![], vec![c1.clone()]),
+///     (c1.clone(), vec![], vec![c2.clone()]),
+///     (c2.clone(), vec![], vec![c0.clone()]),
+/// ]);
+///
+/// let model = PredicateModel::new(&counter)
+///     .with_predicate("at_limit", |s: &Counter| s.value == s.limit);
+/// assert!(verify(&model, &c0, &ctl!(AF(Atom("at_limit")))));
+///
+/// let even = Predicate::new("even", |s: &Counter| s.value % 2 == 0);
+/// assert!(verify(&model, &c0, &ctl!(AG(Imply(Not(Atom(even.clone())), AX(Atom(even)))))));
+/// ```
+pub struct PredicateModel<'a, S, A> {
+    model: &'a dyn Model<S, A>,
+    predicates: Vec<Predicate<S>>,
+}
+
+impl<'a, S, A> PredicateModel<'a, S, A> {
+    /// # `new`
+    /// Returns a model with the transitions of `model` and no named predicates
+    pub fn new(model: &'a dyn Model<S, A>) -> Self {
+        Self {
+            model,
+            predicates: vec![],
+        }
+    }
+
+    /// # `with_predicate`
+    /// Adds a predicate that can be used as the atom `name`. A later predicate with the same name replaces the earlier one
+    pub fn with_predicate(mut self, name: &str, test: impl Fn(&S) -> bool + 'static) -> Self {
+        self.predicates.retain(|predicate| predicate.name != name);
+        self.predicates.push(Predicate::new(name, test));
+        self
+    }
+}
+
+impl<S, A> Model<S, Predicate<S>> for PredicateModel<'_, S, A> {
+    fn state_has(&self, state: &S, atom: &Predicate<S>) -> bool {
+        atom.holds(state)
+    }
+
+    fn transitions(&self, state: &S) -> &Vec<S> {
+        self.model.transitions(state)
+    }
+}
+
+impl<S, A> Model<S, &str> for PredicateModel<'_, S, A> {
+    fn state_has(&self, state: &S, atom: &&str) -> bool {
+        self.predicates
+            .iter()
+            .find(|predicate| predicate.name == *atom)
+            .is_some_and(|predicate| predicate.holds(state))
+    }
+
+    fn transitions(&self, state: &S) -> &Vec<S> {
+        self.model.transitions(state)
+    }
+}
@@ -0,0 +1,235 @@
+use std::fmt;
+
+use super::CTLFormula;
+
+/// Notation used when printing a `CTLFormula` with [`CTLFormula::display`]
+///
+/// All notations only print the parentheses that are needed given the precedence of the operators.
+/// From the loosest to the tightest binding, the operators are `→` (right associative), `∨`, `∧` and finally the unary operators `¬`, `AG`, `AF`, `AX`, `EG`, `EF` and `EX`.
+/// The until operators are always printed with brackets, `A[f U g]` and `E[f U g]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Notation {
+    /// Plain ASCII, e.g. `AG(p -> AF !q)`. Parses back to the same formula with [`parse_formula`](crate::parse_formula)
+    Ascii,
+    /// Unicode symbols, e.g. `AG(p → AF ¬q)`. Parses back to the same formula with [`parse_formula`](crate::parse_formula)
+    Unicode,
+    /// LaTeX math mode, e.g. `\mathbf{AG}\left(\mathit{p} \rightarrow \mathbf{AF}\,\lnot \mathit{q}\right)`
+    Latex,
+}
+
+struct Symbols {
+    top: &'static str,
+    bottom: &'static str,
+    not: &'static str,
+    and: &'static str,
+    or: &'static str,
+    imply: &'static str,
+}
+
+impl Notation {
+    fn symbols(&self) -> Symbols {
+        match self {
+            Self::Ascii => Symbols {
+                top: "true",
+                bottom: "false",
+                not: "!",
+                and: " & ",
+                or: " | ",
+                imply: " -> ",
+            },
+            Self::Unicode => Symbols {
+                top: "⊤",
+                bottom: "⊥",
+                not: "¬",
+                and: " ∧ ",
+                or: " ∨ ",
+                imply: " → ",
+            },
+            Self::Latex => Symbols {
+                top: "\\top",
+                bottom: "\\bot",
+                not: "\\lnot ",
+                and: " \\land ",
+                or: " \\lor ",
+                imply: " \\rightarrow ",
+            },
+        }
+    }
+}
+
+/// Keywords of the text syntax, atoms with these names are quoted when printed
+pub(crate) const KEYWORDS: [&str; 11] = [
+    "true", "false", "AG", "AF", "AX", "EG", "EF", "EX", "A", "E", "U",
+];
+
+/// Returns true if the character can be part of an unquoted atom in the text syntax
+pub(crate) fn is_atom_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+// Binding strength of the operators, a higher value binds tighter
+const IMPLY: u8 = 1;
+const OR: u8 = 2;
+const AND: u8 = 3;
+const UNARY: u8 = 4;
+
+/// Wrapper returned by [`CTLFormula::display`] that prints a formula in a given [`Notation`]
+pub struct FormulaDisplay<'a, T: PartialEq> {
+    formula: &'a CTLFormula<T>,
+    notation: Notation,
+}
+
+impl<T: fmt::Display + PartialEq> CTLFormula<T> {
+    /// # `display`
+    /// Returns a value that prints the formula in the given notation with as few parentheses as possible
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::{ctl, CTLFormula, Notation};
+    ///
+    /// let f = ctl!(AG(Imply(And(Atom("p"), Atom("q")), AF(Not(Atom("r"))))));
+    /// assert_eq!(f.display(Notation::Ascii).to_string(), "AG(p & q -> AF !r)");
+    /// assert_eq!(f.display(Notation::Unicode).to_string(), "AG(p ∧ q → AF ¬r)");
+    /// ```
+    pub fn display(&self, notation: Notation) -> FormulaDisplay<'_, T> {
+        FormulaDisplay {
+            formula: self,
+            notation,
+        }
+    }
+}
+
+impl<T: fmt::Display + PartialEq> fmt::Display for FormulaDisplay<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&print(self.formula, self.notation, 0))
+    }
+}
+
+fn print<T: fmt::Display + PartialEq>(
+    formula: &CTLFormula<T>,
+    notation: Notation,
+    context: u8,
+) -> String {
+    let symbols = notation.symbols();
+
+    let (text, precedence) = match formula {
+        CTLFormula::True => (symbols.top.to_string(), UNARY),
+        CTLFormula::False => (symbols.bottom.to_string(), UNARY),
+        CTLFormula::Atom(atom) => (print_atom(atom, notation), UNARY),
+        CTLFormula::Not(f) => (
+            format!("{}{}", symbols.not, print(f, notation, UNARY)),
+            UNARY,
+        ),
+        CTLFormula::And(f, g) => (
+            format!(
+                "{}{}{}",
+                print(f, notation, AND),
+                symbols.and,
+                print(g, notation, AND + 1)
+            ),
+            AND,
+        ),
+        CTLFormula::Or(f, g) => (
+            format!(
+                "{}{}{}",
+                print(f, notation, OR),
+                symbols.or,
+                print(g, notation, OR + 1)
+            ),
+            OR,
+        ),
+        CTLFormula::Imply(f, g) => (
+            format!(
+                "{}{}{}",
+                print(f, notation, IMPLY + 1),
+                symbols.imply,
+                print(g, notation, IMPLY)
+            ),
+            IMPLY,
+        ),
+        CTLFormula::AG(f) => (print_unary("AG", f, notation), UNARY),
+        CTLFormula::AF(f) => (print_unary("AF", f, notation), UNARY),
+        CTLFormula::AX(f) => (print_unary("AX", f, notation), UNARY),
+        CTLFormula::EG(f) => (print_unary("EG", f, notation), UNARY),
+        CTLFormula::EF(f) => (print_unary("EF", f, notation), UNARY),
+        CTLFormula::EX(f) => (print_unary("EX", f, notation), UNARY),
+        CTLFormula::AU(f, g) => (print_until("A", f, g, notation), UNARY),
+        CTLFormula::EU(f, g) => (print_until("E", f, g, notation), UNARY),
+    };
+
+    if precedence < context {
+        match notation {
+            Notation::Latex => format!("\\left({text}\\right)"),
+            _ => format!("({text})"),
+        }
+    } else {
+        text
+    }
+}
+
+fn print_unary<T: fmt::Display + PartialEq>(
+    operator: &str,
+    formula: &CTLFormula<T>,
+    notation: Notation,
+) -> String {
+    let inner = print(formula, notation, UNARY);
+
+    match notation {
+        Notation::Latex if inner.starts_with("\\left(") => format!("\\mathbf{{{operator}}}{inner}"),
+        Notation::Latex => format!("\\mathbf{{{operator}}}\\,{inner}"),
+        _ if inner.starts_with('(') => format!("{operator}{inner}"),
+        _ => format!("{operator} {inner}"),
+    }
+}
+
+fn print_until<T: fmt::Display + PartialEq>(
+    quantifier: &str,
+    formula: &CTLFormula<T>,
+    until: &CTLFormula<T>,
+    notation: Notation,
+) -> String {
+    let formula = print(formula, notation, 0);
+    let until = print(until, notation, 0);
+
+    match notation {
+        Notation::Latex => {
+            format!("\\mathbf{{{quantifier}}}[{formula} \\mathbin{{\\mathbf{{U}}}} {until}]")
+        }
+        _ => format!("{quantifier}[{formula} U {until}]"),
+    }
+}
+
+fn print_atom<T: fmt::Display>(atom: &T, notation: Notation) -> String {
+    let name = atom.to_string();
+
+    if notation == Notation::Latex {
+        let mut escaped = String::new();
+        for c in name.chars() {
+            match c {
+                '_' | '&' | '%' | '$' | '#' | '{' | '}' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                '\\' => escaped.push_str("\\backslash "),
+                '~' => escaped.push_str("\\sim "),
+                '^' => escaped.push_str("\\hat{}"),
+                c => escaped.push(c),
+            }
+        }
+        return format!("\\mathit{{{escaped}}}");
+    }
+
+    if !name.is_empty() && name.chars().all(is_atom_char) && !KEYWORDS.contains(&name.as_str()) {
+        name
+    } else {
+        let mut quoted = String::from('"');
+        for c in name.chars() {
+            if c == '"' || c == '\\' {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        quoted
+    }
+}
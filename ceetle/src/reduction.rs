@@ -0,0 +1,149 @@
+use std::{cell::RefCell, collections::HashSet, error::Error, fmt, hash::Hash};
+
+use super::{cache::Cache, CTLFormula, ComponentAtom, Composition, Model};
+
+/// Error returned by [`Composition::reduce`] when the formula is not preserved by the reduction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReductionError {
+    /// The formula uses `EX` or `AX`, which count transitions and therefore tell apart paths that the reduction shortens
+    NextOperator,
+}
+
+impl fmt::Display for ReductionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NextOperator => write!(
+                f,
+                "partial order reduction does not preserve formulas that use EX or AX"
+            ),
+        }
+    }
+}
+
+impl Error for ReductionError {}
+
+/// # `ReducedComposition`
+/// A `Composition` where only some of the interleavings of independent transitions are explored, built by [`Composition::reduce`].
+///
+/// The successors of a state are computed the first time they are needed and then cached, like those of the composition.
+pub struct ReducedComposition<'c, 'a, S, A> {
+    composition: &'c Composition<'a, S, A>,
+    // The atoms of the formula for each component, whose values the ample transitions must not change
+    visible: Vec<Vec<A>>,
+    seen: RefCell<HashSet<Vec<S>>>,
+    successors: Cache<Vec<S>, Vec<Vec<S>>>,
+}
+
+impl<'a, S: Clone + Eq + Hash + 'a, A: PartialEq + Clone + 'a> Composition<'a, S, A> {
+    /// # `reduce`
+    /// Returns the composition reduced by partial order reduction for verifying `formula`.
+    ///
+    /// In every state, the reduced composition only takes a single transition of one component if that is enough to preserve `formula`,
+    /// instead of trying every interleaving of the components. This is the ample set method for branching time logics, where such a transition must
+    /// - be the only transition of its component in the state
+    /// - be independent of every other component: in an `interleaving` composition every transition is, and in a `synchronized` composition
+    ///   the actions that are in the alphabet of no other component are. A `synchronous` composition is never reduced
+    /// - not change the value of any atom of `formula`
+    /// - lead to a state that has not been seen yet, so that no cycle is closed with reduced states only
+    ///
+    /// Otherwise all transitions of the state are taken. The reduced composition satisfies the same CTL formulas without `EX` and `AX`
+    /// over the atoms of `formula` as the composition, in every state that it explores. Returns an error if `formula` uses `EX` or `AX`.
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::builder::{ag, atom, ef};
+    /// use ceetle::{model, reachable, verify, ComponentAtom, Composition, HashedDiscreteModel};
+    ///
+    /// let worker = model! {
+    ///     w0 -> w1;
+    ///     w1 -> w2;
+    ///     w2 [done] -> w2;
+    /// };
+    /// let system = Composition::interleaving(vec![("a", &worker), ("b", &worker), ("c", &worker)]);
+    /// let formula = ag(ef(atom(ComponentAtom::new("a", "done"))));
+    ///
+    /// let reduced = system.reduce(&formula).unwrap();
+    /// let start = vec![vec!["w0", "w0", "w0"]];
+    /// assert!(verify(&reduced, &start[0], &formula));
+    /// assert_eq!(reachable(&system, &start).len(), 27);
+    /// assert!(reachable(&reduced, &start).len() < 27);
+    /// ```
+    pub fn reduce(
+        &self,
+        formula: &CTLFormula<ComponentAtom<A>>,
+    ) -> Result<ReducedComposition<'_, 'a, S, A>, ReductionError> {
+        if uses_next(formula) {
+            return Err(ReductionError::NextOperator);
+        }
+
+        let mut visible = vec![vec![]; self.names.len()];
+        for atom in formula.atoms() {
+            if let Some(i) = self.names.iter().position(|name| *name == atom.component) {
+                if !visible[i].contains(&atom.atom) {
+                    visible[i].push(atom.atom.clone());
+                }
+            }
+        }
+
+        Ok(ReducedComposition {
+            composition: self,
+            visible,
+            seen: RefCell::new(HashSet::new()),
+            successors: Cache::new(),
+        })
+    }
+}
+
+fn uses_next<T: PartialEq>(formula: &CTLFormula<T>) -> bool {
+    matches!(formula, CTLFormula::EX(_) | CTLFormula::AX(_))
+        || formula.children().into_iter().any(uses_next)
+}
+
+impl<S: Clone + Eq + Hash, A: PartialEq> ReducedComposition<'_, '_, S, A> {
+    /// The successor through the ample transition of `state`, if it has one
+    fn ample(&self, state: &[S]) -> Option<Vec<S>> {
+        let composition = self.composition;
+        for (i, local) in state.iter().enumerate() {
+            let Some(nexts) = (composition.local)(i, local) else {
+                continue;
+            };
+            let [next] = nexts.as_slice() else {
+                continue;
+            };
+            let model = composition.components[i];
+            let invisible = self.visible[i]
+                .iter()
+                .all(|atom| model.state_has(local, atom) == model.state_has(next, atom));
+            if !invisible {
+                continue;
+            }
+
+            let mut successor = state.to_vec();
+            successor[i] = next.clone();
+            if !self.seen.borrow().contains(&successor) {
+                return Some(successor);
+            }
+        }
+        None
+    }
+}
+
+impl<S: Clone + Eq + Hash, A: PartialEq> Model<Vec<S>, ComponentAtom<A>>
+    for ReducedComposition<'_, '_, S, A>
+{
+    fn state_has(&self, state: &Vec<S>, atom: &ComponentAtom<A>) -> bool {
+        self.composition.state_has(state, atom)
+    }
+
+    fn transitions(&self, state: &Vec<S>) -> &Vec<Vec<S>> {
+        self.successors.get_or_insert_with(state, || {
+            self.seen.borrow_mut().insert(state.clone());
+            let nexts = match self.ample(state) {
+                Some(next) => vec![next],
+                None => self.composition.transitions(state).clone(),
+            };
+            self.seen.borrow_mut().extend(nexts.iter().cloned());
+            nexts
+        })
+    }
+}
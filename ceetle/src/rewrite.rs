@@ -0,0 +1,280 @@
+use std::{collections::HashMap, error::Error, fmt, hash::Hash};
+
+use super::CTLFormula;
+
+/// Atom of a rewrite pattern
+///
+/// A pattern is a `CTLFormula<Pattern<T>>`, where
+/// - `Pattern::Meta(name)` is a metavariable that matches any subformula. A metavariable that occurs more than once must match equal subformulas
+/// - `Pattern::Atom(atom)` only matches `CTLFormula::Atom(atom)`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Pattern<T> {
+    Meta(String),
+    Atom(T),
+}
+
+impl<T> Pattern<T> {
+    /// # `meta`
+    /// Returns a metavariable with the given name
+    pub fn meta(name: &str) -> Self {
+        Self::Meta(name.to_string())
+    }
+}
+
+/// Error returned when registering or applying rewrite rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RewriteError {
+    /// The replacement of the rule uses a metavariable that its pattern does not bind
+    UnboundMetavariable { rule: String, name: String },
+    /// Applying the rules revisited an earlier formula, the rules fired along the cycle are given in order
+    Cycle { rules: Vec<String> },
+    /// The rules were applied more times than the limit of the `Rewriter`
+    StepLimitExceeded { steps: usize },
+}
+
+impl fmt::Display for RewriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnboundMetavariable { rule, name } => {
+                write!(
+                    f,
+                    "metavariable `{name}` is not bound by the pattern of rule `{rule}`"
+                )
+            }
+            Self::Cycle { rules } => {
+                write!(
+                    f,
+                    "rewriting does not terminate, the rules {} form a cycle",
+                    rules.join(" → ")
+                )
+            }
+            Self::StepLimitExceeded { steps } => {
+                write!(f, "rewriting did not reach a fixpoint within {steps} steps")
+            }
+        }
+    }
+}
+
+impl Error for RewriteError {}
+
+/// The result of applying a `Rewriter` to a formula
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rewritten<T: PartialEq> {
+    /// The rewritten formula, to which no rule applies anymore
+    pub formula: CTLFormula<T>,
+    /// The names of the rules that fired, in the order they were applied
+    pub fired: Vec<String>,
+}
+
+struct RewriteRule<T: PartialEq> {
+    name: String,
+    pattern: CTLFormula<Pattern<T>>,
+    replacement: CTLFormula<Pattern<T>>,
+}
+
+/// Rule-based rewriting engine for `CTLFormula`s
+///
+/// A `Rewriter` holds a list of rules `pattern → replacement`, where both sides are formulas over [`Pattern`] atoms.
+/// Rewriting repeatedly replaces the outermost, leftmost subformula matched by a pattern with the instantiated replacement
+/// until no rule applies anymore. Rules are tried in the order they were added.
+///
+/// Rewriting fails with a [`RewriteError`] if it revisits a formula, or if it does not terminate within the step limit.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ctl, CTLFormula, Pattern, Rewriter};
+///
+/// let mut rewriter = Rewriter::new();
+/// rewriter.add_rule(
+///     "response",
+///     ctl!(AG(Imply(Atom(Pattern::meta("p")), AF(Atom(Pattern::meta("q")))))),
+///     ctl!(Atom(Pattern::Atom("responds"))),
+/// ).unwrap();
+///
+/// let result = rewriter.rewrite(ctl!(And(Atom("init"), AG(Imply(Atom("req"), AF(Atom("ack"))))))).unwrap();
+/// assert_eq!(result.formula, ctl!(And(Atom("init"), Atom("responds"))));
+/// assert_eq!(result.fired, vec!["response"]);
+/// ```
+pub struct Rewriter<T: PartialEq> {
+    rules: Vec<RewriteRule<T>>,
+    max_steps: usize,
+}
+
+impl<T: Eq + Hash + Clone> Default for Rewriter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Eq + Hash + Clone> Rewriter<T> {
+    /// # `new`
+    /// Returns a new `Rewriter` without any rules and a limit of 10000 rewrite steps
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            max_steps: 10000,
+        }
+    }
+
+    /// # `with_max_steps`
+    /// Sets the maximum number of rule applications before rewriting is aborted
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// # `add_rule`
+    /// Adds the rule `pattern → replacement` with the given name.
+    /// Returns an error if the replacement uses a metavariable that does not occur in the pattern.
+    pub fn add_rule(
+        &mut self,
+        name: &str,
+        pattern: CTLFormula<Pattern<T>>,
+        replacement: CTLFormula<Pattern<T>>,
+    ) -> Result<&mut Self, RewriteError> {
+        let mut bound = Vec::new();
+        metavariables(&pattern, &mut bound);
+        let mut used = Vec::new();
+        metavariables(&replacement, &mut used);
+
+        if let Some(name_of_unbound) = used.into_iter().find(|var| !bound.contains(var)) {
+            return Err(RewriteError::UnboundMetavariable {
+                rule: name.to_string(),
+                name: name_of_unbound.clone(),
+            });
+        }
+
+        self.rules.push(RewriteRule {
+            name: name.to_string(),
+            pattern,
+            replacement,
+        });
+        Ok(self)
+    }
+
+    /// # `rewrite`
+    /// Applies the rules to the formula until a fixpoint is reached and returns the rewritten formula together with the rules that fired
+    pub fn rewrite(&self, formula: CTLFormula<T>) -> Result<Rewritten<T>, RewriteError> {
+        let mut formula = formula;
+        let mut fired: Vec<String> = Vec::new();
+        // Formulas seen so far, mapped to the number of rules that had fired when they were seen
+        let mut seen: HashMap<CTLFormula<T>, usize> = HashMap::new();
+
+        loop {
+            let Some((next, rule)) = self.rewrite_once(&formula) else {
+                return Ok(Rewritten { formula, fired });
+            };
+
+            if fired.len() == self.max_steps {
+                return Err(RewriteError::StepLimitExceeded {
+                    steps: self.max_steps,
+                });
+            }
+
+            seen.insert(formula, fired.len());
+            fired.push(rule.name.clone());
+
+            if let Some(&start) = seen.get(&next) {
+                return Err(RewriteError::Cycle {
+                    rules: fired.split_off(start),
+                });
+            }
+            formula = next;
+        }
+    }
+
+    /// Rewrites the outermost, leftmost subformula that some rule applies to
+    fn rewrite_once(&self, formula: &CTLFormula<T>) -> Option<(CTLFormula<T>, &RewriteRule<T>)> {
+        for rule in self.rules.iter() {
+            let mut bindings = HashMap::new();
+            if matches(&rule.pattern, formula, &mut bindings) {
+                return Some((instantiate(&rule.replacement, &bindings), rule));
+            }
+        }
+
+        let children = formula.children();
+        for (i, child) in children.iter().enumerate() {
+            if let Some((rewritten, rule)) = self.rewrite_once(child) {
+                let mut new_children: Vec<CTLFormula<T>> =
+                    children.iter().map(|&c| c.clone()).collect();
+                new_children[i] = rewritten;
+                return Some((formula.with_children(new_children), rule));
+            }
+        }
+
+        None
+    }
+}
+
+fn metavariables<'a, T: PartialEq>(
+    pattern: &'a CTLFormula<Pattern<T>>,
+    result: &mut Vec<&'a String>,
+) {
+    if let CTLFormula::Atom(Pattern::Meta(name)) = pattern {
+        result.push(name);
+    }
+    for child in pattern.children() {
+        metavariables(child, result);
+    }
+}
+
+fn matches<'a, T: PartialEq + Clone>(
+    pattern: &'a CTLFormula<Pattern<T>>,
+    formula: &CTLFormula<T>,
+    bindings: &mut HashMap<&'a String, CTLFormula<T>>,
+) -> bool {
+    use CTLFormula::*;
+
+    match (pattern, formula) {
+        (Atom(Pattern::Meta(name)), formula) => match bindings.get(name) {
+            Some(bound) => bound == formula,
+            None => {
+                bindings.insert(name, formula.clone());
+                true
+            }
+        },
+        (Atom(Pattern::Atom(a)), Atom(b)) => a == b,
+        (True, True) | (False, False) => true,
+        (Not(p), Not(f))
+        | (AG(p), AG(f))
+        | (AF(p), AF(f))
+        | (AX(p), AX(f))
+        | (EG(p), EG(f))
+        | (EF(p), EF(f))
+        | (EX(p), EX(f)) => matches(p, f, bindings),
+        (And(p, q), And(f, g))
+        | (Or(p, q), Or(f, g))
+        | (Imply(p, q), Imply(f, g))
+        | (AU(p, q), AU(f, g))
+        | (EU(p, q), EU(f, g)) => matches(p, f, bindings) && matches(q, g, bindings),
+        _ => false,
+    }
+}
+
+fn instantiate<T: PartialEq + Clone>(
+    replacement: &CTLFormula<Pattern<T>>,
+    bindings: &HashMap<&String, CTLFormula<T>>,
+) -> CTLFormula<T> {
+    use CTLFormula::*;
+
+    let inst = |f: &CTLFormula<Pattern<T>>| Box::new(instantiate(f, bindings));
+
+    match replacement {
+        True => True,
+        False => False,
+        Atom(Pattern::Meta(name)) => bindings[name].clone(),
+        Atom(Pattern::Atom(atom)) => Atom(atom.clone()),
+        Not(f) => Not(inst(f)),
+        And(f, g) => And(inst(f), inst(g)),
+        Or(f, g) => Or(inst(f), inst(g)),
+        Imply(f, g) => Imply(inst(f), inst(g)),
+        AG(f) => AG(inst(f)),
+        AF(f) => AF(inst(f)),
+        AX(f) => AX(inst(f)),
+        AU(f, g) => AU(inst(f), inst(g)),
+        EG(f) => EG(inst(f)),
+        EF(f) => EF(inst(f)),
+        EX(f) => EX(inst(f)),
+        EU(f, g) => EU(inst(f), inst(g)),
+    }
+}
@@ -0,0 +1,277 @@
+use std::{collections::HashMap, hash::Hash};
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{CTLFormula, HashedDiscreteModel, VecDiscreteModel};
+
+/// Version of the schema used when serializing `CTLFormula`s
+///
+/// A formula is serialized as a tree of operators tagged by their name, where the `args` are the atom,
+/// the subformula or the list of both subformulas. In JSON, the document of `AG(p→q)` looks like this:
+/// ```json
+/// {
+///     "version": 1,
+///     "formula": {"op": "AG", "args": {"op": "Imply", "args": [{"op": "Atom", "args": "p"}, {"op": "Atom", "args": "q"}]}}
+/// }
+/// ```
+/// Deserializing fails if the `version` of the document is newer than `FORMULA_SCHEMA_VERSION`.
+pub const FORMULA_SCHEMA_VERSION: u32 = 1;
+
+/// Version of the schema used when serializing `VecDiscreteModel`s and `HashedDiscreteModel`s
+///
+/// Both models are serialized as the same document, so a model saved from one type can be loaded as the other.
+/// In JSON, the document of a model looks like this:
+/// ```json
+/// {
+///     "version": 1,
+///     "states": [
+///         {"name": "s0", "labels": ["p"], "transitions": ["s0", "s1"]},
+///         {"name": "s1", "labels": ["p", "q"], "transitions": ["s0"]}
+///     ],
+///     "initial": ["s0"]
+/// }
+/// ```
+/// The states of a `VecDiscreteModel` are kept in order, while the states of a `HashedDiscreteModel` are sorted, so that serializing it always gives the same document.
+/// Deserializing fails if the `version` of the document is newer than `MODEL_SCHEMA_VERSION`.
+pub const MODEL_SCHEMA_VERSION: u32 = 1;
+
+// ================== FORMULAS ==================
+// The tree of a formula is serialized through a mirror of `CTLFormula` so that the version is only written once, at the root.
+
+#[derive(Serialize)]
+struct FormulaDocumentRef<'a, T> {
+    version: u32,
+    formula: FormulaTreeRef<'a, T>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "op", content = "args")]
+enum FormulaTreeRef<'a, T> {
+    True,
+    False,
+    Atom(&'a T),
+    And(Box<Self>, Box<Self>),
+    Or(Box<Self>, Box<Self>),
+    Not(Box<Self>),
+    Imply(Box<Self>, Box<Self>),
+    AG(Box<Self>),
+    AF(Box<Self>),
+    AX(Box<Self>),
+    AU(Box<Self>, Box<Self>),
+    EG(Box<Self>),
+    EF(Box<Self>),
+    EX(Box<Self>),
+    EU(Box<Self>, Box<Self>),
+}
+
+#[derive(Deserialize)]
+struct FormulaDocument<T> {
+    version: u32,
+    formula: FormulaTree<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "op", content = "args")]
+enum FormulaTree<T> {
+    True,
+    False,
+    Atom(T),
+    And(Box<Self>, Box<Self>),
+    Or(Box<Self>, Box<Self>),
+    Not(Box<Self>),
+    Imply(Box<Self>, Box<Self>),
+    AG(Box<Self>),
+    AF(Box<Self>),
+    AX(Box<Self>),
+    AU(Box<Self>, Box<Self>),
+    EG(Box<Self>),
+    EF(Box<Self>),
+    EX(Box<Self>),
+    EU(Box<Self>, Box<Self>),
+}
+
+impl<'a, T: PartialEq> From<&'a CTLFormula<T>> for FormulaTreeRef<'a, T> {
+    fn from(formula: &'a CTLFormula<T>) -> Self {
+        let tree = |f: &'a CTLFormula<T>| Box::new(Self::from(f));
+        match formula {
+            CTLFormula::True => Self::True,
+            CTLFormula::False => Self::False,
+            CTLFormula::Atom(atom) => Self::Atom(atom),
+            CTLFormula::And(f, g) => Self::And(tree(f), tree(g)),
+            CTLFormula::Or(f, g) => Self::Or(tree(f), tree(g)),
+            CTLFormula::Not(f) => Self::Not(tree(f)),
+            CTLFormula::Imply(f, g) => Self::Imply(tree(f), tree(g)),
+            CTLFormula::AG(f) => Self::AG(tree(f)),
+            CTLFormula::AF(f) => Self::AF(tree(f)),
+            CTLFormula::AX(f) => Self::AX(tree(f)),
+            CTLFormula::AU(f, g) => Self::AU(tree(f), tree(g)),
+            CTLFormula::EG(f) => Self::EG(tree(f)),
+            CTLFormula::EF(f) => Self::EF(tree(f)),
+            CTLFormula::EX(f) => Self::EX(tree(f)),
+            CTLFormula::EU(f, g) => Self::EU(tree(f), tree(g)),
+        }
+    }
+}
+
+impl<T: PartialEq> From<FormulaTree<T>> for CTLFormula<T> {
+    fn from(tree: FormulaTree<T>) -> Self {
+        let formula = |t: Box<FormulaTree<T>>| Box::new(Self::from(*t));
+        match tree {
+            FormulaTree::True => Self::True,
+            FormulaTree::False => Self::False,
+            FormulaTree::Atom(atom) => Self::Atom(atom),
+            FormulaTree::And(f, g) => Self::And(formula(f), formula(g)),
+            FormulaTree::Or(f, g) => Self::Or(formula(f), formula(g)),
+            FormulaTree::Not(f) => Self::Not(formula(f)),
+            FormulaTree::Imply(f, g) => Self::Imply(formula(f), formula(g)),
+            FormulaTree::AG(f) => Self::AG(formula(f)),
+            FormulaTree::AF(f) => Self::AF(formula(f)),
+            FormulaTree::AX(f) => Self::AX(formula(f)),
+            FormulaTree::AU(f, g) => Self::AU(formula(f), formula(g)),
+            FormulaTree::EG(f) => Self::EG(formula(f)),
+            FormulaTree::EF(f) => Self::EF(formula(f)),
+            FormulaTree::EX(f) => Self::EX(formula(f)),
+            FormulaTree::EU(f, g) => Self::EU(formula(f), formula(g)),
+        }
+    }
+}
+
+impl<T: Serialize + PartialEq> Serialize for CTLFormula<T> {
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        FormulaDocumentRef {
+            version: FORMULA_SCHEMA_VERSION,
+            formula: FormulaTreeRef::from(self),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de> + PartialEq> Deserialize<'de> for CTLFormula<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let document = FormulaDocument::deserialize(deserializer)?;
+        if document.version > FORMULA_SCHEMA_VERSION {
+            return Err(D::Error::custom(format!(
+                "formula schema version {} is newer than the supported version {FORMULA_SCHEMA_VERSION}",
+                document.version
+            )));
+        }
+        Ok(document.formula.into())
+    }
+}
+
+// ================== MODELS ==================
+
+#[derive(Serialize)]
+struct ModelDocumentRef<'a, S, A> {
+    version: u32,
+    states: Vec<StateDocumentRef<'a, S, A>>,
+    initial: &'a Vec<S>,
+}
+
+#[derive(Serialize)]
+struct StateDocumentRef<'a, S, A> {
+    name: &'a S,
+    labels: &'a Vec<A>,
+    transitions: &'a Vec<S>,
+}
+
+#[derive(Deserialize)]
+struct ModelDocument<S, A> {
+    version: u32,
+    states: Vec<StateDocument<S, A>>,
+    #[serde(default = "Vec::new")]
+    initial: Vec<S>,
+}
+
+#[derive(Deserialize)]
+struct StateDocument<S, A> {
+    name: S,
+    #[serde(default = "Vec::new")]
+    labels: Vec<A>,
+    #[serde(default = "Vec::new")]
+    transitions: Vec<S>,
+}
+
+impl<'de, S: Deserialize<'de>, A: Deserialize<'de>> ModelDocument<S, A> {
+    fn load<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let document = Self::deserialize(deserializer)?;
+        if document.version > MODEL_SCHEMA_VERSION {
+            return Err(D::Error::custom(format!(
+                "model schema version {} is newer than the supported version {MODEL_SCHEMA_VERSION}",
+                document.version
+            )));
+        }
+        Ok(document)
+    }
+}
+
+impl<S: Serialize, A: Serialize> Serialize for VecDiscreteModel<S, A> {
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        ModelDocumentRef {
+            version: MODEL_SCHEMA_VERSION,
+            states: self
+                .states
+                .iter()
+                .map(|(name, labels, transitions)| StateDocumentRef {
+                    name,
+                    labels,
+                    transitions,
+                })
+                .collect(),
+            initial: &self.initial,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, S: Deserialize<'de> + PartialEq, A: Deserialize<'de> + PartialEq> Deserialize<'de>
+    for VecDiscreteModel<S, A>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let document = ModelDocument::load(deserializer)?;
+        let states = document
+            .states
+            .into_iter()
+            .map(|state| (state.name, state.labels, state.transitions))
+            .collect();
+        Ok(VecDiscreteModel::new(states).with_initial_states(document.initial))
+    }
+}
+
+impl<S: Serialize + Ord, A: Serialize> Serialize for HashedDiscreteModel<S, A> {
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        let mut states: Vec<StateDocumentRef<S, A>> = self
+            .states
+            .iter()
+            .map(|(name, (labels, transitions))| StateDocumentRef {
+                name,
+                labels,
+                transitions,
+            })
+            .collect();
+        states.sort_by(|a, b| a.name.cmp(b.name));
+
+        ModelDocumentRef {
+            version: MODEL_SCHEMA_VERSION,
+            states,
+            initial: &self.initial,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, S: Deserialize<'de> + Eq + Hash, A: Deserialize<'de> + PartialEq> Deserialize<'de>
+    for HashedDiscreteModel<S, A>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let document = ModelDocument::load(deserializer)?;
+        let mut states = HashMap::new();
+        for state in document.states {
+            if states.contains_key(&state.name) {
+                return Err(D::Error::custom("duplicate state in model"));
+            }
+            states.insert(state.name, (state.labels, state.transitions));
+        }
+        Ok(HashedDiscreteModel::new(states).with_initial_states(document.initial))
+    }
+}
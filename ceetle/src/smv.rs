@@ -0,0 +1,1308 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    fmt,
+};
+
+use super::{CTLFormula, HashedDiscreteModel};
+
+/// Error returned when an SMV program cannot be parsed or compiled with [`parse_smv`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmvError {
+    /// Line of the error, starting at 1
+    pub line: usize,
+    /// Column of the error, starting at 1
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SmvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl Error for SmvError {}
+
+/// A compiled SMV program, see [`parse_smv`]
+#[derive(Debug, Clone)]
+pub struct SmvProgram {
+    /// The reachable state space of the program, with the initial states of the program as its initial states
+    pub model: HashedDiscreteModel<String, String>,
+    /// The `SPEC` and `CTLSPEC` properties, in the order they are declared
+    pub specs: Vec<CTLFormula<String>>,
+}
+
+/// Function `parse_smv` that compiles a program in a subset of the SMV input language of NuSMV
+///
+/// The supported subset is a single `MODULE main` with the following sections:
+/// - `VAR` declaring variables of type `boolean`, enumerations such as `{idle, busy}` and bounded integers such as `0..7`
+/// - `ASSIGN` with `init(x) := e;` and `next(x) := e;`, where `e` may be a `case ... esac` expression or a set `{a, b}` for nondeterministic choice.
+///   Variables without `init` start with any value and variables without `next` may take any value in the next state
+/// - `DEFINE` declaring named expressions `name := e;`
+/// - `SPEC` and `CTLSPEC` declaring CTL properties
+///
+/// `FAIRNESS` and `JUSTICE` constraints are rejected, since [`verify`](crate::verify) does not restrict paths to fair ones.
+///
+/// Expressions support the boolean operators `!`, `&`, `|`, `xor`, `->` and `<->`, the comparisons `=`, `!=`, `<`, `<=`, `>` and `>=`,
+/// and the arithmetic operators `+`, `-`, `*`, `/` and `mod`. Comments start with `--`.
+/// A name in an expression must be a variable, a `DEFINE` or a value of an enumeration, a `DEFINE` must not depend on itself
+/// nor have the name of a variable or of another `DEFINE`, and arithmetic must not overflow 64-bit integers, otherwise an error is returned.
+/// The operand of `AG`, `AF`, `AX`, `EG`, `EF` and `EX` extends over comparisons, so `AG x = 1` reads as `AG (x = 1)`.
+///
+/// The program is compiled to an explicit model of all states reachable from the initial states.
+/// A state is named by its valuation, e.g. `x=1, ready=TRUE`, and is labelled with
+/// - `x=v` for every variable `x` with value `v`
+/// - the name of every boolean variable and boolean `DEFINE` that is true in the state
+/// - every atom of the properties that is true in the state
+///
+/// The atoms of the properties are their subexpressions that are not built from boolean operators, for example
+/// `AG (x = 1 -> AF ready)` becomes the formula `AG(Atom("x = 1") → AF(Atom("ready")))`.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{parse_smv, verify, EnumerableModel};
+///
+/// let program = parse_smv("
+///     MODULE main
+///     VAR
+///         x : 0..3;
+///     ASSIGN
+///         init(x) := 0;
+///         next(x) := case
+///             x < 3 : x + 1;
+///             TRUE  : 0;
+///         esac;
+///     SPEC AG (x = 3 -> AX x = 0)
+/// ").unwrap();
+///
+/// let initial = &program.model.initial_states()[0];
+/// assert_eq!(initial, "x=0");
+/// assert!(verify(&program.model, initial, &program.specs[0]));
+/// ```
+pub fn parse_smv(input: &str) -> Result<SmvProgram, SmvError> {
+    let mut parser = SmvParser {
+        tokens: tokenize_smv(input)?,
+        current: 0,
+        program: Program::default(),
+        in_property: false,
+    };
+    parser.program()?;
+    parser.program.compile()
+}
+
+// ================== SYNTAX ==================
+
+pub(crate) type Position = (usize, usize);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    Name(String),
+    Int(i64),
+    Symbol(&'static str),
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "`{name}`"),
+            Self::Int(value) => write!(f, "`{value}`"),
+            Self::Symbol(symbol) => write!(f, "`{symbol}`"),
+            Self::End => write!(f, "end of input"),
+        }
+    }
+}
+
+// Longest symbols first, so that e.g. `<->` is not read as `<` followed by `->`
+const SYMBOLS: [&str; 25] = [
+    "<->", ":=", "->", "!=", "<=", ">=", "..", "(", ")", "{", "}", "[", "]", ":", ";", ",", "!",
+    "&", "|", "=", "<", ">", "+", "*", "'",
+];
+
+pub(crate) fn tokenize_smv(input: &str) -> Result<Vec<(Position, Token)>, SmvError> {
+    let mut tokens = Vec::new();
+
+    for (line_index, line) in input.lines().enumerate() {
+        // Comments start with `--` and run to the end of the line
+        let line = match line.find("--") {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let position = (line_index + 1, i + 1);
+            let c = chars[i];
+
+            if c.is_whitespace() {
+                i += 1;
+            } else if c.is_ascii_digit() {
+                let start = i;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let digits: String = chars[start..i].iter().collect();
+                let value = digits.parse().map_err(|_| SmvError {
+                    line: position.0,
+                    column: position.1,
+                    message: format!("Integer `{digits}` is too large"),
+                })?;
+                tokens.push((position, Token::Int(value)));
+            } else if c.is_alphabetic() || c == '_' {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '$' | '#'))
+                {
+                    i += 1;
+                }
+                tokens.push((position, Token::Name(chars[start..i].iter().collect())));
+            } else if c == '-' || c == '/' {
+                // `-` and `/` are only symbols on their own, `--` is a comment and `->` is in SYMBOLS
+                if c == '-' && chars.get(i + 1) == Some(&'>') {
+                    tokens.push((position, Token::Symbol("->")));
+                    i += 2;
+                } else {
+                    tokens.push((position, Token::Symbol(if c == '-' { "-" } else { "/" })));
+                    i += 1;
+                }
+            } else {
+                let rest: String = chars[i..].iter().collect();
+                let Some(symbol) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) else {
+                    return Err(SmvError {
+                        line: position.0,
+                        column: position.1,
+                        message: format!("Unexpected character `{c}`"),
+                    });
+                };
+                tokens.push((position, Token::Symbol(symbol)));
+                i += symbol.chars().count();
+            }
+        }
+    }
+
+    let end = (input.lines().count().max(1), 1);
+    tokens.push((end, Token::End));
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BinaryOp {
+    And,
+    Or,
+    Xor,
+    Implies,
+    Iff,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+}
+
+impl BinaryOp {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Self::And => "&",
+            Self::Or => "|",
+            Self::Xor => "xor",
+            Self::Implies => "->",
+            Self::Iff => "<->",
+            Self::Equal => "=",
+            Self::NotEqual => "!=",
+            Self::Less => "<",
+            Self::LessEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterEqual => ">=",
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+            Self::Modulo => "mod",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Expr {
+    Bool(bool),
+    Int(i64),
+    Name(String),
+    Set(Vec<Expr>),
+    Range(i64, i64),
+    Not(Box<Expr>),
+    Negate(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Case(Vec<(Expr, Expr)>),
+    // Temporal operators, only allowed in properties
+    Temporal(&'static str, Box<Expr>),
+    Until(&'static str, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Collects the names that the expression reads
+    pub(crate) fn names<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Self::Name(name) => names.push(name),
+            Self::Set(elements) => elements.iter().for_each(|e| e.names(names)),
+            Self::Not(e) | Self::Negate(e) | Self::Temporal(_, e) => e.names(names),
+            Self::Binary(_, a, b) | Self::Until(_, a, b) => {
+                a.names(names);
+                b.names(names);
+            }
+            Self::Case(branches) => {
+                for (condition, value) in branches {
+                    condition.names(names);
+                    value.names(names);
+                }
+            }
+            Self::Bool(_) | Self::Int(_) | Self::Range(..) => {}
+        }
+    }
+
+    pub(crate) fn is_temporal(&self) -> bool {
+        match self {
+            Self::Temporal(..) | Self::Until(..) => true,
+            Self::Set(elements) => elements.iter().any(Expr::is_temporal),
+            Self::Not(e) | Self::Negate(e) => e.is_temporal(),
+            Self::Binary(_, a, b) => a.is_temporal() || b.is_temporal(),
+            Self::Case(branches) => branches
+                .iter()
+                .any(|(c, v)| c.is_temporal() || v.is_temporal()),
+            Self::Bool(_) | Self::Int(_) | Self::Name(_) | Self::Range(..) => false,
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Operands that are themselves operators are put in parentheses
+        let operand = |e: &Expr| match e {
+            Expr::Binary(..) => format!("({e})"),
+            _ => e.to_string(),
+        };
+
+        match self {
+            Self::Bool(true) => write!(f, "TRUE"),
+            Self::Bool(false) => write!(f, "FALSE"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Name(name) => write!(f, "{name}"),
+            Self::Set(values) => {
+                let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                write!(f, "{{{}}}", values.join(", "))
+            }
+            Self::Range(low, high) => write!(f, "{low}..{high}"),
+            Self::Not(e) => write!(f, "!{}", operand(e)),
+            Self::Negate(e) => write!(f, "-{}", operand(e)),
+            Self::Binary(op, a, b) => write!(f, "{} {} {}", operand(a), op.symbol(), operand(b)),
+            Self::Case(branches) => {
+                write!(f, "case ")?;
+                for (condition, value) in branches {
+                    write!(f, "{condition} : {value}; ")?;
+                }
+                write!(f, "esac")
+            }
+            Self::Temporal(op, e) => write!(f, "{op} {}", operand(e)),
+            Self::Until(quantifier, a, b) => write!(f, "{quantifier} [{a} U {b}]"),
+        }
+    }
+}
+
+/// The value of a variable of an SMV program or a `TransitionSystem`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    /// A boolean, written `TRUE` or `FALSE`
+    Bool(bool),
+    /// An integer
+    Int(i64),
+    /// A value of an enumeration
+    Symbol(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(true) => write!(f, "TRUE"),
+            Self::Bool(false) => write!(f, "FALSE"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Symbol(symbol) => write!(f, "{symbol}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Type {
+    Boolean,
+    Enumeration(Vec<Value>),
+    Range(i64, i64),
+}
+
+impl Type {
+    fn domain(&self) -> Vec<Value> {
+        match self {
+            Self::Boolean => vec![Value::Bool(false), Value::Bool(true)],
+            Self::Enumeration(values) => values.clone(),
+            Self::Range(low, high) => (*low..=*high).map(Value::Int).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Program {
+    pub(crate) variables: Vec<(String, Type)>,
+    init: HashMap<String, (Position, Expr)>,
+    next: HashMap<String, (Position, Expr)>,
+    defines: HashMap<String, (Position, Expr)>,
+    specs: Vec<(Position, Expr)>,
+    // The subexpressions of the properties with the position where they start, to report errors in atoms where they are
+    spans: Vec<(Position, Expr)>,
+}
+
+pub(crate) struct SmvParser {
+    pub(crate) tokens: Vec<(Position, Token)>,
+    pub(crate) current: usize,
+    pub(crate) program: Program,
+    // Whether the subexpressions being parsed are recorded in `program.spans`
+    pub(crate) in_property: bool,
+}
+
+impl SmvParser {
+    pub(crate) fn peek(&self) -> &Token {
+        &self.tokens[self.current].1
+    }
+
+    fn position(&self) -> Position {
+        self.tokens[self.current].0
+    }
+
+    pub(crate) fn next(&mut self) -> Token {
+        let token = self.tokens[self.current].1.clone();
+        if token != Token::End {
+            self.current += 1;
+        }
+        token
+    }
+
+    pub(crate) fn error<T>(&self, message: String) -> Result<T, SmvError> {
+        let (line, column) = self.position();
+        Err(SmvError {
+            line,
+            column,
+            message,
+        })
+    }
+
+    fn is_name(&self, name: &str) -> bool {
+        matches!(self.peek(), Token::Name(n) if n == name)
+    }
+
+    pub(crate) fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Token::Symbol(s) if *s == symbol)
+    }
+
+    pub(crate) fn expect_symbol(&mut self, symbol: &str) -> Result<(), SmvError> {
+        if !self.is_symbol(symbol) {
+            return self.error(format!("Expected `{symbol}` but found {}", self.peek()));
+        }
+        self.next();
+        Ok(())
+    }
+
+    fn expect_name(&mut self, name: &str) -> Result<(), SmvError> {
+        if !self.is_name(name) {
+            return self.error(format!("Expected `{name}` but found {}", self.peek()));
+        }
+        self.next();
+        Ok(())
+    }
+
+    pub(crate) fn identifier(&mut self) -> Result<String, SmvError> {
+        match self.peek().clone() {
+            Token::Name(name) if !is_keyword(&name) => {
+                self.next();
+                Ok(name)
+            }
+            token => self.error(format!("Expected an identifier but found {token}")),
+        }
+    }
+
+    fn int(&mut self) -> Result<i64, SmvError> {
+        let negative = self.is_symbol("-");
+        if negative {
+            self.next();
+        }
+        match self.peek().clone() {
+            Token::Int(value) => {
+                self.next();
+                Ok(if negative { -value } else { value })
+            }
+            token => self.error(format!("Expected an integer but found {token}")),
+        }
+    }
+
+    fn is_section_start(&self) -> bool {
+        SECTIONS.iter().any(|section| self.is_name(section)) || *self.peek() == Token::End
+    }
+
+    fn program(&mut self) -> Result<(), SmvError> {
+        self.expect_name("MODULE")?;
+        if !self.is_name("main") {
+            return self.error("Only a single `MODULE main` is supported".to_string());
+        }
+        self.next();
+        if self.is_symbol("(") {
+            return self.error("Module parameters are not supported".to_string());
+        }
+
+        loop {
+            let position = self.position();
+            match self.next() {
+                Token::End => return Ok(()),
+                Token::Name(section) => match section.as_str() {
+                    "VAR" => self.variables()?,
+                    "ASSIGN" => self.assignments()?,
+                    "DEFINE" => self.defines()?,
+                    "SPEC" | "CTLSPEC" => {
+                        self.in_property = true;
+                        let spec = self.expression()?;
+                        self.in_property = false;
+                        self.program.specs.push((position, spec));
+                        self.optional_semicolon();
+                    }
+                    "FAIRNESS" | "JUSTICE" => {
+                        self.current -= 1;
+                        return self.error(format!(
+                            "`{section}` constraints are not supported, paths are not restricted to fair ones"
+                        ));
+                    }
+                    "MODULE" => {
+                        self.current -= 1;
+                        return self.error("Only a single `MODULE main` is supported".to_string());
+                    }
+                    _ => {
+                        self.current -= 1;
+                        return self.error(format!("Unsupported section `{section}`"));
+                    }
+                },
+                token => {
+                    self.current -= 1;
+                    return self.error(format!("Expected a section but found {token}"));
+                }
+            }
+        }
+    }
+
+    fn optional_semicolon(&mut self) {
+        if self.is_symbol(";") {
+            self.next();
+        }
+    }
+
+    // variables := (identifier ':' type ';')*
+    fn variables(&mut self) -> Result<(), SmvError> {
+        while !self.is_section_start() {
+            let name = self.identifier()?;
+            if self.program.variables.iter().any(|(n, _)| *n == name) {
+                self.current -= 1;
+                return self.error(format!("Variable `{name}` is declared twice"));
+            }
+            if self.program.defines.contains_key(&name) {
+                self.current -= 1;
+                return self.error(format!("Variable `{name}` is already a `DEFINE`"));
+            }
+            self.expect_symbol(":")?;
+
+            let variable_type = if self.is_name("boolean") {
+                self.next();
+                Type::Boolean
+            } else if self.is_symbol("{") {
+                self.next();
+                let mut values = Vec::new();
+                loop {
+                    match self.next() {
+                        Token::Name(name) if !is_keyword(&name) => values.push(Value::Symbol(name)),
+                        Token::Int(value) => values.push(Value::Int(value)),
+                        token => {
+                            self.current -= 1;
+                            return self.error(format!("Expected a value but found {token}"));
+                        }
+                    }
+                    if self.is_symbol("}") {
+                        self.next();
+                        break;
+                    }
+                    self.expect_symbol(",")?;
+                }
+                Type::Enumeration(values)
+            } else {
+                let low = self.int()?;
+                self.expect_symbol("..")?;
+                let high = self.int()?;
+                if low > high {
+                    return self.error(format!("Empty range `{low}..{high}`"));
+                }
+                Type::Range(low, high)
+            };
+
+            self.expect_symbol(";")?;
+            self.program.variables.push((name, variable_type));
+        }
+        Ok(())
+    }
+
+    // assignments := (('init' | 'next') '(' identifier ')' ':=' expression ';')*
+    fn assignments(&mut self) -> Result<(), SmvError> {
+        while !self.is_section_start() {
+            let position = self.position();
+            let kind = match self.next() {
+                Token::Name(kind) if kind == "init" || kind == "next" => kind,
+                token => {
+                    self.current -= 1;
+                    return self.error(format!(
+                        "Expected `init(..)` or `next(..)` but found {token}, invariant assignments are not supported"
+                    ));
+                }
+            };
+            self.expect_symbol("(")?;
+            let name = self.identifier()?;
+            self.expect_symbol(")")?;
+            self.expect_symbol(":=")?;
+            let value = self.expression()?;
+            self.expect_symbol(";")?;
+
+            let assignments = if kind == "init" {
+                &mut self.program.init
+            } else {
+                &mut self.program.next
+            };
+            if assignments
+                .insert(name.clone(), (position, value))
+                .is_some()
+            {
+                let (line, column) = position;
+                return Err(SmvError {
+                    line,
+                    column,
+                    message: format!("`{kind}({name})` is assigned twice"),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // defines := (identifier ':=' expression ';')*
+    fn defines(&mut self) -> Result<(), SmvError> {
+        while !self.is_section_start() {
+            let position = self.position();
+            let name = self.identifier()?;
+            if self.program.defines.contains_key(&name) {
+                self.current -= 1;
+                return self.error(format!("`{name}` is defined twice"));
+            }
+            if self.program.variables.iter().any(|(n, _)| *n == name) {
+                self.current -= 1;
+                return self.error(format!("`{name}` is already a variable"));
+            }
+            self.expect_symbol(":=")?;
+            let value = self.expression()?;
+            self.expect_symbol(";")?;
+            self.program.defines.insert(name, (position, value));
+        }
+        Ok(())
+    }
+
+    // Binary operators from the loosest to the tightest binding, `->` is right associative
+    pub(crate) fn expression(&mut self) -> Result<Expr, SmvError> {
+        let left = self.binary(0)?;
+        if self.is_symbol("->") {
+            self.next();
+            let right = self.expression()?;
+            return Ok(Expr::Binary(
+                BinaryOp::Implies,
+                Box::new(left),
+                Box::new(right),
+            ));
+        }
+        Ok(left)
+    }
+
+    pub(crate) fn binary(&mut self, level: usize) -> Result<Expr, SmvError> {
+        const LEVELS: [&[(&str, BinaryOp)]; 6] = [
+            &[("<->", BinaryOp::Iff)],
+            &[("|", BinaryOp::Or), ("xor", BinaryOp::Xor)],
+            &[("&", BinaryOp::And)],
+            &[
+                ("=", BinaryOp::Equal),
+                ("!=", BinaryOp::NotEqual),
+                ("<=", BinaryOp::LessEqual),
+                (">=", BinaryOp::GreaterEqual),
+                ("<", BinaryOp::Less),
+                (">", BinaryOp::Greater),
+            ],
+            &[("+", BinaryOp::Add), ("-", BinaryOp::Subtract)],
+            &[
+                ("*", BinaryOp::Multiply),
+                ("/", BinaryOp::Divide),
+                ("mod", BinaryOp::Modulo),
+            ],
+        ];
+
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+
+        let start = self.position();
+        let mut left = self.binary(level + 1)?;
+        loop {
+            let op = LEVELS[level]
+                .iter()
+                .find(|(symbol, _)| self.is_symbol(symbol) || self.is_name(symbol))
+                .map(|(_, op)| *op);
+            let Some(op) = op else {
+                let recorded = self
+                    .program
+                    .spans
+                    .last()
+                    .is_some_and(|(p, e)| *p == start && *e == left);
+                if self.in_property && !recorded {
+                    self.program.spans.push((start, left.clone()));
+                }
+                return Ok(left);
+            };
+            self.next();
+            let right = self.binary(level + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, SmvError> {
+        match self.next() {
+            Token::Int(value) => {
+                if self.is_symbol("..") {
+                    self.next();
+                    let high = self.int()?;
+                    return Ok(Expr::Range(value, high));
+                }
+                Ok(Expr::Int(value))
+            }
+            Token::Symbol("!") => Ok(Expr::Not(Box::new(self.unary()?))),
+            Token::Symbol("-") => Ok(Expr::Negate(Box::new(self.unary()?))),
+            Token::Symbol("(") => {
+                let inner = self.expression()?;
+                self.expect_symbol(")")?;
+                Ok(inner)
+            }
+            Token::Symbol("{") => {
+                let mut values = vec![self.expression()?];
+                while self.is_symbol(",") {
+                    self.next();
+                    values.push(self.expression()?);
+                }
+                self.expect_symbol("}")?;
+                Ok(Expr::Set(values))
+            }
+            Token::Name(name) => match name.as_str() {
+                "TRUE" => Ok(Expr::Bool(true)),
+                "FALSE" => Ok(Expr::Bool(false)),
+                "case" => {
+                    let mut branches = Vec::new();
+                    while !self.is_name("esac") {
+                        let condition = self.expression()?;
+                        self.expect_symbol(":")?;
+                        let value = self.expression()?;
+                        self.expect_symbol(";")?;
+                        branches.push((condition, value));
+                    }
+                    self.next();
+                    Ok(Expr::Case(branches))
+                }
+                "AG" | "AF" | "AX" | "EG" | "EF" | "EX" => {
+                    let op = TEMPORAL.iter().find(|op| **op == name).unwrap();
+                    // The operand extends over comparisons, so `AG x = 1` reads as `AG (x = 1)`
+                    Ok(Expr::Temporal(op, Box::new(self.binary(3)?)))
+                }
+                "A" | "E" => {
+                    let quantifier = if name == "A" { "A" } else { "E" };
+                    self.expect_symbol("[")?;
+                    let formula = self.expression()?;
+                    self.expect_name("U")?;
+                    let until = self.expression()?;
+                    self.expect_symbol("]")?;
+                    Ok(Expr::Until(quantifier, Box::new(formula), Box::new(until)))
+                }
+                "next" | "init" => {
+                    self.current -= 1;
+                    self.error(format!(
+                        "`{name}(..)` is only supported on the left of an assignment"
+                    ))
+                }
+                _ if is_keyword(&name) => {
+                    self.current -= 1;
+                    self.error(format!("Expected an expression but found `{name}`"))
+                }
+                _ => Ok(Expr::Name(name)),
+            },
+            token => {
+                self.current -= 1;
+                self.error(format!("Expected an expression but found {token}"))
+            }
+        }
+    }
+}
+
+const SECTIONS: [&str; 9] = [
+    "MODULE", "VAR", "ASSIGN", "DEFINE", "SPEC", "CTLSPEC", "FAIRNESS", "JUSTICE", "IVAR",
+];
+const TEMPORAL: [&str; 6] = ["AG", "AF", "AX", "EG", "EF", "EX"];
+
+fn is_keyword(name: &str) -> bool {
+    SECTIONS.contains(&name)
+        || TEMPORAL.contains(&name)
+        || [
+            "A", "E", "U", "TRUE", "FALSE", "case", "esac", "init", "next", "boolean", "mod", "xor",
+        ]
+        .contains(&name)
+}
+
+// ================== COMPILATION ==================
+
+/// Valuation of the variables, in the order they are declared. `None` if the variable is not assigned yet
+pub(crate) type Valuation = Vec<Option<Value>>;
+
+impl Program {
+    fn error(position: Position, message: String) -> SmvError {
+        SmvError {
+            line: position.0,
+            column: position.1,
+            message,
+        }
+    }
+
+    pub(crate) fn variable(&self, name: &str) -> Option<usize> {
+        self.variables.iter().position(|(n, _)| n == name)
+    }
+
+    /// Evaluates an expression to a single value. Returns `Ok(None)` if it depends on a variable that is not assigned yet
+    pub(crate) fn evaluate(
+        &self,
+        expr: &Expr,
+        state: &Valuation,
+        position: Position,
+    ) -> Result<Option<Value>, SmvError> {
+        let values = self.evaluate_set(expr, state, position)?;
+        match values {
+            None => Ok(None),
+            Some(values) if values.len() == 1 => Ok(values.into_iter().next()),
+            Some(_) => Err(Self::error(
+                position,
+                format!("`{expr}` must have a single value"),
+            )),
+        }
+    }
+
+    /// Evaluates an expression to the set of values it may take. Returns `Ok(None)` if it depends on a variable that is not assigned yet
+    fn evaluate_set(
+        &self,
+        expr: &Expr,
+        state: &Valuation,
+        position: Position,
+    ) -> Result<Option<Vec<Value>>, SmvError> {
+        let single = |value: Value| Ok(Some(vec![value]));
+
+        match expr {
+            Expr::Bool(value) => single(Value::Bool(*value)),
+            Expr::Int(value) => single(Value::Int(*value)),
+            Expr::Range(low, high) => Ok(Some((*low..=*high).map(Value::Int).collect())),
+            Expr::Name(name) => {
+                if let Some(i) = self.variable(name) {
+                    return Ok(state[i].clone().map(|value| vec![value]));
+                }
+                if let Some((_, define)) = self.defines.get(name) {
+                    return self.evaluate_set(define, state, position);
+                }
+                single(Value::Symbol(name.clone()))
+            }
+            Expr::Set(elements) => {
+                let mut values = Vec::new();
+                for element in elements {
+                    let Some(element_values) = self.evaluate_set(element, state, position)? else {
+                        return Ok(None);
+                    };
+                    for value in element_values {
+                        if !values.contains(&value) {
+                            values.push(value);
+                        }
+                    }
+                }
+                Ok(Some(values))
+            }
+            Expr::Case(branches) => {
+                for (condition, value) in branches {
+                    match self.evaluate(condition, state, position)? {
+                        None => return Ok(None),
+                        Some(Value::Bool(true)) => {
+                            return self.evaluate_set(value, state, position)
+                        }
+                        Some(Value::Bool(false)) => continue,
+                        Some(_) => {
+                            return Err(Self::error(
+                                position,
+                                format!("Condition `{condition}` is not boolean"),
+                            ))
+                        }
+                    }
+                }
+                Err(Self::error(
+                    position,
+                    "No condition of the `case` expression holds".to_string(),
+                ))
+            }
+            Expr::Not(inner) => match self.evaluate(inner, state, position)? {
+                None => Ok(None),
+                Some(Value::Bool(value)) => single(Value::Bool(!value)),
+                Some(_) => Err(Self::error(position, format!("`{inner}` is not boolean"))),
+            },
+            Expr::Negate(inner) => match self.evaluate(inner, state, position)? {
+                None => Ok(None),
+                Some(Value::Int(value)) => match value.checked_neg() {
+                    Some(value) => single(Value::Int(value)),
+                    None => Err(Self::error(
+                        position,
+                        format!("Integer overflow in `{expr}`"),
+                    )),
+                },
+                Some(_) => Err(Self::error(
+                    position,
+                    format!("`{inner}` is not an integer"),
+                )),
+            },
+            Expr::Binary(op, a, b) => {
+                let (Some(a_value), Some(b_value)) = (
+                    self.evaluate(a, state, position)?,
+                    self.evaluate(b, state, position)?,
+                ) else {
+                    return Ok(None);
+                };
+                let value = match (op, a_value, b_value) {
+                    (BinaryOp::Equal, a, b) => Value::Bool(a == b),
+                    (BinaryOp::NotEqual, a, b) => Value::Bool(a != b),
+                    (op, Value::Bool(a), Value::Bool(b)) => Value::Bool(match op {
+                        BinaryOp::And => a && b,
+                        BinaryOp::Or => a || b,
+                        BinaryOp::Xor => a != b,
+                        BinaryOp::Implies => !a || b,
+                        BinaryOp::Iff => a == b,
+                        _ => {
+                            return Err(Self::error(
+                                position,
+                                format!("`{expr}` is not defined for booleans"),
+                            ))
+                        }
+                    }),
+                    (op, Value::Int(a), Value::Int(b)) => {
+                        let result = match op {
+                            BinaryOp::Less => return single(Value::Bool(a < b)),
+                            BinaryOp::LessEqual => return single(Value::Bool(a <= b)),
+                            BinaryOp::Greater => return single(Value::Bool(a > b)),
+                            BinaryOp::GreaterEqual => return single(Value::Bool(a >= b)),
+                            BinaryOp::Divide | BinaryOp::Modulo if b == 0 => {
+                                return Err(Self::error(
+                                    position,
+                                    format!("Division by zero in `{expr}`"),
+                                ))
+                            }
+                            BinaryOp::Add => a.checked_add(b),
+                            BinaryOp::Subtract => a.checked_sub(b),
+                            BinaryOp::Multiply => a.checked_mul(b),
+                            BinaryOp::Divide => a.checked_div(b),
+                            BinaryOp::Modulo => a.checked_rem_euclid(b),
+                            _ => {
+                                return Err(Self::error(
+                                    position,
+                                    format!("`{expr}` is not defined for integers"),
+                                ))
+                            }
+                        };
+                        match result {
+                            Some(value) => Value::Int(value),
+                            None => {
+                                return Err(Self::error(
+                                    position,
+                                    format!("Integer overflow in `{expr}`"),
+                                ))
+                            }
+                        }
+                    }
+                    _ => {
+                        return Err(Self::error(
+                            position,
+                            format!("Operands of `{expr}` have mismatched types"),
+                        ))
+                    }
+                };
+                single(value)
+            }
+            Expr::Temporal(..) | Expr::Until(..) => Err(Self::error(
+                position,
+                format!("Temporal operators are not allowed in `{expr}`"),
+            )),
+        }
+    }
+
+    /// Checks that every name in `expr` is a variable, a `DEFINE` or a value of an enumeration,
+    /// and that a variable of an enumeration is only compared to the values of its own enumeration
+    pub(crate) fn check_names(&self, expr: &Expr, position: Position) -> Result<(), SmvError> {
+        let is_declared =
+            |name: &str| self.variable(name).is_some() || self.defines.contains_key(name);
+        let is_literal = |name: &str| {
+            self.variables.iter().any(|(_, variable_type)| {
+                matches!(variable_type, Type::Enumeration(values) if values.contains(&Value::Symbol(name.to_string())))
+            })
+        };
+
+        match expr {
+            Expr::Name(name) if !is_declared(name) && !is_literal(name) => Err(Self::error(
+                position,
+                format!("`{name}` is not a variable, a `DEFINE` nor a value of an enumeration"),
+            )),
+            Expr::Bool(_) | Expr::Int(_) | Expr::Range(..) | Expr::Name(_) => Ok(()),
+            Expr::Binary(BinaryOp::Equal | BinaryOp::NotEqual, a, b) => {
+                for (variable, literal) in [(a, b), (b, a)] {
+                    let (Expr::Name(variable), Expr::Name(literal)) = (&**variable, &**literal)
+                    else {
+                        continue;
+                    };
+                    let Some(i) = self.variable(variable) else {
+                        continue;
+                    };
+                    if let Type::Enumeration(values) = &self.variables[i].1 {
+                        if !is_declared(literal)
+                            && !values.contains(&Value::Symbol(literal.clone()))
+                        {
+                            return Err(Self::error(
+                                position,
+                                format!("`{literal}` is not a value of `{variable}`"),
+                            ));
+                        }
+                    }
+                }
+                self.check_names(a, position)?;
+                self.check_names(b, position)
+            }
+            Expr::Set(elements) => elements
+                .iter()
+                .try_for_each(|e| self.check_names(e, position)),
+            Expr::Not(e) | Expr::Negate(e) | Expr::Temporal(_, e) => self.check_names(e, position),
+            Expr::Binary(_, a, b) | Expr::Until(_, a, b) => {
+                self.check_names(a, position)?;
+                self.check_names(b, position)
+            }
+            Expr::Case(branches) => branches.iter().try_for_each(|(condition, value)| {
+                self.check_names(condition, position)?;
+                self.check_names(value, position)
+            }),
+        }
+    }
+
+    /// Checks that no `DEFINE` depends on itself, which would make evaluating it loop forever
+    fn check_define_cycles(&self) -> Result<(), SmvError> {
+        // Depth-first search where `finished` are the defines known not to be in a cycle and `path` the defines being visited
+        fn visit<'a>(
+            program: &'a Program,
+            name: &'a str,
+            path: &mut Vec<&'a str>,
+            finished: &mut HashSet<&'a str>,
+        ) -> Result<(), SmvError> {
+            let Some((position, expr)) = program.defines.get(name) else {
+                return Ok(());
+            };
+            if finished.contains(name) {
+                return Ok(());
+            }
+            if let Some(start) = path.iter().position(|n| *n == name) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(name);
+                return Err(Program::error(
+                    *position,
+                    format!(
+                        "`DEFINE {name}` depends on itself through {}",
+                        cycle.join(" -> ")
+                    ),
+                ));
+            }
+
+            path.push(name);
+            let mut names = Vec::new();
+            expr.names(&mut names);
+            for next in names {
+                // Variables hide defines of the same name
+                if program.variable(next).is_none() {
+                    visit(program, next, path, finished)?;
+                }
+            }
+            path.pop();
+            finished.insert(name);
+            Ok(())
+        }
+
+        let mut defines: Vec<&String> = self.defines.keys().collect();
+        defines.sort();
+        let mut finished = HashSet::new();
+        for name in defines {
+            visit(self, name, &mut Vec::new(), &mut finished)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the values a variable may take given its assignment, checking that they are in the domain of the variable
+    pub(crate) fn candidates(
+        &self,
+        variable: usize,
+        assignment: Option<&(Position, Expr)>,
+        state: &Valuation,
+    ) -> Result<Option<Vec<Value>>, SmvError> {
+        let (name, variable_type) = &self.variables[variable];
+        let domain = variable_type.domain();
+        let Some((position, expr)) = assignment else {
+            return Ok(Some(domain));
+        };
+
+        let Some(values) = self.evaluate_set(expr, state, *position)? else {
+            return Ok(None);
+        };
+        if let Some(value) = values.iter().find(|value| !domain.contains(value)) {
+            return Err(Self::error(
+                *position,
+                format!("Value `{value}` is not in the type of `{name}`"),
+            ));
+        }
+        Ok(Some(values))
+    }
+
+    /// Enumerates all valuations where every variable takes one of the values given by `assignments`
+    fn valuations(
+        &self,
+        assignments: &HashMap<String, (Position, Expr)>,
+        current: &Valuation,
+    ) -> Result<Vec<Vec<Value>>, SmvError> {
+        let mut partial: Vec<Valuation> = vec![vec![None; self.variables.len()]];
+
+        for (i, (name, _)) in self.variables.iter().enumerate() {
+            let assignment = assignments.get(name);
+            let mut extended = Vec::new();
+            for valuation in partial {
+                // Initial values may depend on other variables, next values only depend on the current state
+                let scope = if current.is_empty() {
+                    &valuation
+                } else {
+                    current
+                };
+                let values = match self.candidates(i, assignment, scope)? {
+                    Some(values) => values,
+                    None => self.variables[i].1.domain(),
+                };
+                for value in values {
+                    let mut valuation = valuation.clone();
+                    valuation[i] = Some(value);
+                    extended.push(valuation);
+                }
+            }
+            partial = extended;
+        }
+
+        // Initial values that depended on later variables are checked once everything is assigned
+        let mut result = Vec::new();
+        for valuation in partial {
+            let scope = if current.is_empty() {
+                &valuation
+            } else {
+                current
+            };
+            let mut consistent = true;
+            for (i, (name, _)) in self.variables.iter().enumerate() {
+                if let Some(values) = self.candidates(i, assignments.get(name), scope)? {
+                    consistent &= values.contains(valuation[i].as_ref().unwrap());
+                }
+            }
+            if consistent {
+                result.push(valuation.into_iter().map(Option::unwrap).collect());
+            }
+        }
+        Ok(result)
+    }
+
+    fn state_name(&self, state: &[Value]) -> String {
+        let assignments: Vec<String> = self
+            .variables
+            .iter()
+            .zip(state.iter())
+            .map(|((name, _), value)| format!("{name}={value}"))
+            .collect();
+        assignments.join(", ")
+    }
+
+    /// Converts a property to a `CTLFormula`, collecting the expressions used as atoms
+    fn formula(
+        &self,
+        expr: &Expr,
+        atoms: &mut Vec<(Position, Expr)>,
+        position: Position,
+    ) -> Result<CTLFormula<String>, SmvError> {
+        let mut convert = |e: &Expr| self.formula(e, atoms, position).map(Box::new);
+
+        Ok(match expr {
+            Expr::Bool(true) => CTLFormula::True,
+            Expr::Bool(false) => CTLFormula::False,
+            Expr::Not(inner) => CTLFormula::Not(convert(inner)?),
+            Expr::Binary(BinaryOp::And, a, b) => CTLFormula::And(convert(a)?, convert(b)?),
+            Expr::Binary(BinaryOp::Or, a, b) => CTLFormula::Or(convert(a)?, convert(b)?),
+            Expr::Binary(BinaryOp::Implies, a, b) => CTLFormula::Imply(convert(a)?, convert(b)?),
+            Expr::Binary(BinaryOp::Iff, a, b) => {
+                let (a, b) = (convert(a)?, convert(b)?);
+                CTLFormula::And(
+                    Box::new(CTLFormula::Imply(a.clone(), b.clone())),
+                    Box::new(CTLFormula::Imply(b, a)),
+                )
+            }
+            Expr::Binary(BinaryOp::Xor, a, b) => {
+                let (a, b) = (convert(a)?, convert(b)?);
+                CTLFormula::Or(
+                    Box::new(CTLFormula::And(
+                        a.clone(),
+                        Box::new(CTLFormula::Not(b.clone())),
+                    )),
+                    Box::new(CTLFormula::And(Box::new(CTLFormula::Not(a)), b)),
+                )
+            }
+            Expr::Temporal(op, inner) => {
+                let inner = convert(inner)?;
+                match *op {
+                    "AG" => CTLFormula::AG(inner),
+                    "AF" => CTLFormula::AF(inner),
+                    "AX" => CTLFormula::AX(inner),
+                    "EG" => CTLFormula::EG(inner),
+                    "EF" => CTLFormula::EF(inner),
+                    _ => CTLFormula::EX(inner),
+                }
+            }
+            Expr::Until(quantifier, a, b) => {
+                let (a, b) = (convert(a)?, convert(b)?);
+                if *quantifier == "A" {
+                    CTLFormula::AU(a, b)
+                } else {
+                    CTLFormula::EU(a, b)
+                }
+            }
+            atom if atom.is_temporal() => {
+                return Err(Self::error(
+                    position,
+                    format!("Temporal operators are not allowed in `{atom}`"),
+                ))
+            }
+            atom => {
+                if !atoms.iter().any(|(_, a)| a == atom) {
+                    let start = self
+                        .spans
+                        .iter()
+                        .find(|(_, e)| e == atom)
+                        .map_or(position, |(start, _)| *start);
+                    atoms.push((start, atom.clone()));
+                }
+                CTLFormula::Atom(atom.to_string())
+            }
+        })
+    }
+
+    fn compile(&self) -> Result<SmvProgram, SmvError> {
+        for (name, (position, _)) in self.init.iter().chain(self.next.iter()) {
+            if self.variable(name).is_none() {
+                return Err(Self::error(
+                    *position,
+                    format!("`{name}` is not a variable"),
+                ));
+            }
+        }
+        self.check_define_cycles()?;
+        for (position, expr) in self
+            .init
+            .values()
+            .chain(self.next.values())
+            .chain(self.defines.values())
+            .chain(self.specs.iter())
+        {
+            self.check_names(expr, *position)?;
+        }
+
+        let mut atoms = Vec::new();
+        let mut specs = Vec::new();
+        for (position, spec) in self.specs.iter() {
+            specs.push(self.formula(spec, &mut atoms, *position)?);
+        }
+
+        // Explore the reachable states breadth first
+        let initial = self.valuations(&self.init, &Vec::new())?;
+        let mut seen: HashSet<Vec<Value>> = initial.iter().cloned().collect();
+        let mut queue: VecDeque<Vec<Value>> = initial.iter().cloned().collect();
+        let mut states = HashMap::new();
+
+        while let Some(state) = queue.pop_front() {
+            let current: Valuation = state.iter().cloned().map(Some).collect();
+
+            let mut labels = Vec::new();
+            for ((name, _), value) in self.variables.iter().zip(state.iter()) {
+                labels.push(format!("{name}={value}"));
+                if *value == Value::Bool(true) {
+                    labels.push(name.clone());
+                }
+            }
+            let mut defines: Vec<&String> = self.defines.keys().collect();
+            defines.sort();
+            for name in defines {
+                let (position, define) = &self.defines[name];
+                if let Ok(Some(Value::Bool(true))) = self.evaluate(define, &current, *position) {
+                    labels.push(name.clone());
+                }
+            }
+            for (position, atom) in atoms.iter() {
+                let name = atom.to_string();
+                match self.evaluate(atom, &current, *position)? {
+                    Some(Value::Bool(true)) if !labels.contains(&name) => labels.push(name),
+                    Some(Value::Bool(_)) => {}
+                    _ => {
+                        return Err(Self::error(
+                            *position,
+                            format!("Atom `{atom}` is not boolean"),
+                        ))
+                    }
+                }
+            }
+
+            let mut transitions = Vec::new();
+            for next in self.valuations(&self.next, &current)? {
+                transitions.push(self.state_name(&next));
+                if seen.insert(next.clone()) {
+                    queue.push_back(next);
+                }
+            }
+
+            states.insert(self.state_name(&state), (labels, transitions));
+        }
+
+        let initial = initial.iter().map(|state| self.state_name(state)).collect();
+        Ok(SmvProgram {
+            model: HashedDiscreteModel::new(states).with_initial_states(initial),
+            specs,
+        })
+    }
+}
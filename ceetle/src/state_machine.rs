@@ -0,0 +1,112 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+use super::{cache::Cache, HashedDiscreteModel, Model};
+
+/// # `StateMachine`
+/// A type whose values are the states of a model, with a method giving the next states and predicates giving the atoms.
+///
+/// This trait is usually implemented with the `ctl_model` attribute on the `impl` block of the type:
+/// the method marked `#[ctl(next)]` gives the successors and every method marked `#[ctl(atom)]` is an atom named after the method,
+/// or after the given name with `#[ctl(atom = "name")]`.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ctl_model, ctl, verify, CTLFormula, StateMachine, StateMachineModel};
+///
+/// #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// enum Light { Red, Green, Yellow }
+///
+/// #[ctl_model]
+/// impl Light {
+///     #[ctl(next)]
+///     fn next(&self) -> Vec<Self> {
+///         match self {
+///             Light::Red => vec![Light::Green],
+///             Light::Green => vec![Light::Yellow],
+///             Light::Yellow => vec![Light::Red],
+///         }
+///     }
+///
+///     #[ctl(atom = "stop")]
+///     fn is_red(&self) -> bool {
+///         *self == Light::Red
+///     }
+/// }
+///
+/// let model = StateMachineModel::new(vec![Light::Green]);
+/// assert!(verify(&model, &Light::Green, &ctl!(AF(Atom("stop")))));
+/// ```
+pub trait StateMachine: Clone + Eq + Hash {
+    /// The states that follow `self`
+    fn successors(&self) -> Vec<Self>;
+    /// The names of all atoms
+    fn atoms() -> &'static [&'static str];
+    /// Whether the atom named `atom` holds in `self`. Unknown atoms never hold
+    fn holds(&self, atom: &str) -> bool;
+}
+
+/// # `StateMachineModel`
+/// A model over the values of a `StateMachine`, where the successors of a state are computed the first time they are needed and then cached.
+/// States reachable from the initial states are thus only explored as far as the verification goes.
+///
+/// The atoms of the model are the names returned by `StateMachine::atoms`.
+#[derive(Debug)]
+pub struct StateMachineModel<S: StateMachine> {
+    initial: Vec<S>,
+    successors: Cache<S, Vec<S>>,
+}
+
+impl<S: StateMachine> StateMachineModel<S> {
+    /// # `new`
+    /// Creates a model whose initial states are `initial`
+    pub fn new(initial: Vec<S>) -> Self {
+        Self {
+            initial,
+            successors: Cache::new(),
+        }
+    }
+
+    /// # `initial_states`
+    /// Returns the initial states of the model
+    pub fn initial_states(&self) -> &Vec<S> {
+        &self.initial
+    }
+
+    /// # `explore`
+    /// Explores all states reachable from the initial states and returns them as a `HashedDiscreteModel`, e.g. to use with the functions that need an `EnumerableModel`.
+    /// This does not terminate if infinitely many states are reachable.
+    pub fn explore(&self) -> HashedDiscreteModel<S, &'static str> {
+        let mut states = HashMap::new();
+        let mut queue: VecDeque<S> = self.initial.iter().cloned().collect();
+
+        while let Some(state) = queue.pop_front() {
+            if states.contains_key(&state) {
+                continue;
+            }
+            let labels = S::atoms()
+                .iter()
+                .copied()
+                .filter(|atom| state.holds(atom))
+                .collect();
+            let nexts = self.transitions(&state).clone();
+            queue.extend(nexts.iter().cloned());
+            states.insert(state, (labels, nexts));
+        }
+
+        HashedDiscreteModel::new(states).with_initial_states(self.initial.clone())
+    }
+}
+
+impl<S: StateMachine> Model<S, &'static str> for StateMachineModel<S> {
+    fn state_has(&self, state: &S, atom: &&'static str) -> bool {
+        state.holds(atom)
+    }
+
+    fn transitions(&self, state: &S) -> &Vec<S> {
+        self.successors
+            .get_or_insert_with(state, || state.successors())
+    }
+}
@@ -0,0 +1,457 @@
+use std::{error::Error, fmt, hash::Hash, mem};
+
+use super::{cache::Cache, counterexample, witness, CTLFormula, ComponentAtom, Composition, Model};
+
+/// Error returned when a symmetry group, a composition or a formula does not fit symmetry reduction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymmetryError {
+    /// The permutation is not a permutation of the indices of the group
+    InvalidPermutation(Vec<usize>),
+    /// The group permutes a different number of indices than the composition has components
+    WrongSize { expected: usize, found: usize },
+    /// The group exchanges two components that are not the same model
+    DifferentComponents(String, String),
+    /// The formula can tell apart states that the group exchanges
+    AsymmetricFormula,
+}
+
+impl fmt::Display for SymmetryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPermutation(permutation) => {
+                write!(f, "{permutation:?} is not a permutation of the indices")
+            }
+            Self::WrongSize { expected, found } => {
+                write!(
+                    f,
+                    "the group permutes {found} indices, but there are {expected} components"
+                )
+            }
+            Self::DifferentComponents(first, second) => write!(
+                f,
+                "components `{first}` and `{second}` are exchanged but are not the same model"
+            ),
+            Self::AsymmetricFormula => {
+                write!(f, "the formula is not invariant under the symmetry group")
+            }
+        }
+    }
+}
+
+impl Error for SymmetryError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Elements {
+    // Every permutation of these indices, in increasing order
+    Full(Vec<usize>),
+    Listed(Vec<Vec<usize>>),
+}
+
+/// # `SymmetryGroup`
+/// A group of permutations of the indices `0..size` of the components of a `Composition`.
+///
+/// A permutation `p` moves the state of component `i` to component `p[i]`. The states that a permutation of the group maps to each other are
+/// symmetric copies of each other, which is the case when the permuted components are identical processes.
+///
+/// ## Examples
+/// ```
+/// use ceetle::SymmetryGroup;
+///
+/// let ring = SymmetryGroup::new(3, vec![vec![1, 2, 0]]).unwrap();
+/// assert_eq!(ring.order(), 3);
+/// assert_eq!(ring.canonical(&["b", "c", "a"]), vec!["a", "b", "c"]);
+/// assert_eq!(ring.canonical(&["b", "a", "c"]), vec!["a", "c", "b"]);
+///
+/// let workers = SymmetryGroup::full(4, vec![0, 1, 2]).unwrap();
+/// assert_eq!(workers.order(), 6);
+/// assert_eq!(workers.canonical(&[3, 1, 2, 0]), vec![1, 2, 3, 0]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymmetryGroup {
+    size: usize,
+    generators: Vec<Vec<usize>>,
+    elements: Elements,
+}
+
+impl SymmetryGroup {
+    /// # `new`
+    /// Returns the group generated by the given permutations of `0..size`, which contains every composition of them.
+    /// Returns an error if one of them is not a permutation of `0..size`
+    pub fn new(size: usize, generators: Vec<Vec<usize>>) -> Result<Self, SymmetryError> {
+        for generator in &generators {
+            let mut sorted = generator.clone();
+            sorted.sort_unstable();
+            if sorted != (0..size).collect::<Vec<usize>>() {
+                return Err(SymmetryError::InvalidPermutation(generator.clone()));
+            }
+        }
+
+        let mut elements = vec![(0..size).collect::<Vec<usize>>()];
+        let mut next = 0;
+        while next < elements.len() {
+            for generator in &generators {
+                let element: Vec<usize> = elements[next].iter().map(|&i| generator[i]).collect();
+                if !elements.contains(&element) {
+                    elements.push(element);
+                }
+            }
+            next += 1;
+        }
+
+        Ok(Self {
+            size,
+            generators,
+            elements: Elements::Listed(elements),
+        })
+    }
+
+    /// # `full`
+    /// Returns the group of every permutation of `indices` that leaves the other indices of `0..size` in place,
+    /// for components that are all interchangeable. Returns an error if `indices` are not distinct indices of `0..size`
+    pub fn full(size: usize, mut indices: Vec<usize>) -> Result<Self, SymmetryError> {
+        indices.sort_unstable();
+        indices.dedup();
+        if indices.last().is_some_and(|&i| i >= size) {
+            return Err(SymmetryError::InvalidPermutation(indices));
+        }
+
+        // The transpositions of the first index with each other index generate every permutation
+        let generators = indices
+            .iter()
+            .skip(1)
+            .map(|&j| {
+                let mut generator: Vec<usize> = (0..size).collect();
+                generator.swap(indices[0], j);
+                generator
+            })
+            .collect();
+
+        Ok(Self {
+            size,
+            generators,
+            elements: Elements::Full(indices),
+        })
+    }
+
+    /// # `order`
+    /// Returns the number of permutations in the group
+    pub fn order(&self) -> usize {
+        match &self.elements {
+            Elements::Full(indices) => (1..=indices.len()).product(),
+            Elements::Listed(elements) => elements.len(),
+        }
+    }
+
+    /// # `canonical`
+    /// Returns the smallest state that a permutation of the group maps `state` to, which is the same for all symmetric copies of `state`.
+    ///
+    /// For a `full` group, the states of the permuted components are sorted. Otherwise every permutation of the group is tried.
+    pub fn canonical<S: Clone + Ord>(&self, state: &[S]) -> Vec<S> {
+        match &self.elements {
+            Elements::Full(indices) => {
+                let mut locals: Vec<&S> = indices.iter().map(|&i| &state[i]).collect();
+                locals.sort();
+                let mut canonical = state.to_vec();
+                for (&i, local) in indices.iter().zip(locals) {
+                    canonical[i] = local.clone();
+                }
+                canonical
+            }
+            Elements::Listed(elements) => elements
+                .iter()
+                .map(|permutation| permute(permutation, state))
+                .min()
+                .unwrap(),
+        }
+    }
+}
+
+fn permute<S: Clone>(permutation: &[usize], state: &[S]) -> Vec<S> {
+    let mut permuted = state.to_vec();
+    for (i, local) in state.iter().enumerate() {
+        permuted[permutation[i]] = local.clone();
+    }
+    permuted
+}
+
+/// # `SymmetricComposition`
+/// The quotient of a `Composition` by a `SymmetryGroup`, built by [`Composition::symmetric`].
+///
+/// The successors of a state are the canonical states of its successors in the composition, so the quotient only explores one state
+/// of each class of symmetric states. They are computed the first time they are needed and then cached, like those of the composition.
+///
+/// The quotient does not implement `Model`, since the functions that take a model would also accept formulas that tell apart symmetric states
+/// and give wrong results for them. Its `verify`, `witness` and `counterexample` methods check that the formula is symmetric first.
+pub struct SymmetricComposition<'c, 'a, S, A> {
+    composition: &'c Composition<'a, S, A>,
+    group: SymmetryGroup,
+    successors: Cache<Vec<S>, Vec<Vec<S>>>,
+}
+
+impl<'a, S: Clone + Eq + Hash + 'a, A: 'a> Composition<'a, S, A> {
+    /// # `symmetric`
+    /// Returns the quotient of the composition by `group`, where symmetric states are merged into their canonical state.
+    ///
+    /// Returns an error if `group` does not permute as many indices as there are components, or if it exchanges two components
+    /// that are not the same model, as only copies of the same process are symmetric.
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::builder::{ag, and, atom, not, or};
+    /// use ceetle::{model, reachable, ComponentAtom, Composition, HashedDiscreteModel, SymmetryGroup};
+    ///
+    /// let process = model! {
+    ///     idle -> wait;
+    ///     wait -> crit;
+    ///     crit [crit] -> idle;
+    /// };
+    /// let system = Composition::interleaving(vec![("p1", &process), ("p2", &process), ("p3", &process)]);
+    /// let quotient = system.symmetric(SymmetryGroup::full(3, vec![0, 1, 2]).unwrap()).unwrap();
+    ///
+    /// let start = vec!["idle", "idle", "idle"];
+    /// assert_eq!(reachable(&system, &[start.clone()]).len(), 27);
+    /// assert_eq!(quotient.reachable(&[start.clone()]).len(), 10);
+    ///
+    /// let crit = |name| atom(ComponentAtom::new(name, "crit"));
+    /// let two = or(and(crit("p1"), crit("p2")), or(and(crit("p1"), crit("p3")), and(crit("p2"), crit("p3"))));
+    /// assert_eq!(quotient.verify(&start, &ag(not(two))), Ok(false));
+    /// assert!(quotient.verify(&start, &crit("p1")).is_err());
+    /// ```
+    pub fn symmetric(
+        &self,
+        group: SymmetryGroup,
+    ) -> Result<SymmetricComposition<'_, 'a, S, A>, SymmetryError> {
+        if group.size != self.names.len() {
+            return Err(SymmetryError::WrongSize {
+                expected: self.names.len(),
+                found: group.size,
+            });
+        }
+        for generator in &group.generators {
+            for (i, &j) in generator.iter().enumerate() {
+                let (first, second) = (self.components[i], self.components[j]);
+                if !std::ptr::addr_eq(first, second) {
+                    return Err(SymmetryError::DifferentComponents(
+                        self.names[i].clone(),
+                        self.names[j].clone(),
+                    ));
+                }
+            }
+        }
+
+        Ok(SymmetricComposition {
+            composition: self,
+            group,
+            successors: Cache::new(),
+        })
+    }
+}
+
+impl<S: Clone + Ord + Hash, A: PartialEq + Clone> SymmetricComposition<'_, '_, S, A> {
+    /// # `verify`
+    /// Verifies `formula` in `state` on the quotient, which gives the same result as on the composition.
+    ///
+    /// The formula must not tell apart symmetric states: every temporal subformula, and every subformula directly under a temporal operator,
+    /// must stay the same up to the order of `∧` and `∨` when the components are renamed by a permutation of the group.
+    /// For example, `AG(¬(p1.crit ∧ p2.crit))` is symmetric for the group that exchanges `p1` and `p2`, but `AG(p1.crit)` is not.
+    /// Returns an error if the formula is not symmetric.
+    pub fn verify(
+        &self,
+        state: &[S],
+        formula: &CTLFormula<ComponentAtom<A>>,
+    ) -> Result<bool, SymmetryError> {
+        self.check(formula)?;
+        Ok(super::verify(&Quotient(self), &state.to_vec(), formula))
+    }
+
+    /// # `reachable`
+    /// Returns the states of the quotient that can be reached from `from`, which are canonical states except for those of `from`
+    pub fn reachable(&self, from: &[Vec<S>]) -> Vec<Vec<S>> {
+        super::reachable(&Quotient(self), from)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// # `witness`
+    /// Returns the [`witness`] of a symmetric `formula` in `state`, found on the quotient and mapped back to states of the composition.
+    ///
+    /// The path starts in `state` and each state is a successor of the previous one in the composition. When the witness is a lasso, its last state
+    /// may only be a symmetric copy of the earlier state it loops back to. Returns an error if the formula is not symmetric, like `verify`.
+    pub fn witness(
+        &self,
+        state: &[S],
+        formula: &CTLFormula<ComponentAtom<A>>,
+    ) -> Result<Option<Vec<Vec<S>>>, SymmetryError> {
+        self.check(formula)?;
+        Ok(witness(&Quotient(self), &state.to_vec(), formula).map(|path| self.concrete(path)))
+    }
+
+    /// # `counterexample`
+    /// Returns the [`counterexample`] of a symmetric `formula` in `state`, found on the quotient and mapped back to states of the composition like `witness`.
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::builder::{ag, and, atom, not};
+    /// use ceetle::{model, ComponentAtom, Composition, HashedDiscreteModel, SymmetryGroup};
+    ///
+    /// let process = model! {
+    ///     idle -> crit;
+    ///     crit [crit] -> idle;
+    /// };
+    /// let system = Composition::interleaving(vec![("p1", &process), ("p2", &process)]);
+    /// let quotient = system.symmetric(SymmetryGroup::full(2, vec![0, 1]).unwrap()).unwrap();
+    ///
+    /// let crit = |name| atom(ComponentAtom::new(name, "crit"));
+    /// let mutex = ag(not(and(crit("p1"), crit("p2"))));
+    /// let path = quotient.counterexample(&["crit", "idle"], &mutex).unwrap().unwrap();
+    /// assert_eq!(path, vec![vec!["crit", "idle"], vec!["crit", "crit"]]);
+    /// ```
+    pub fn counterexample(
+        &self,
+        state: &[S],
+        formula: &CTLFormula<ComponentAtom<A>>,
+    ) -> Result<Option<Vec<Vec<S>>>, SymmetryError> {
+        self.check(formula)?;
+        Ok(counterexample(&Quotient(self), &state.to_vec(), formula)
+            .map(|path| self.concrete(path)))
+    }
+
+    fn check(&self, formula: &CTLFormula<ComponentAtom<A>>) -> Result<(), SymmetryError> {
+        let mut stack = vec![(formula, true)];
+        while let Some((formula, under_temporal)) = stack.pop() {
+            let temporal = !matches!(
+                formula,
+                CTLFormula::True
+                    | CTLFormula::False
+                    | CTLFormula::Atom(_)
+                    | CTLFormula::Not(_)
+                    | CTLFormula::And(_, _)
+                    | CTLFormula::Or(_, _)
+                    | CTLFormula::Imply(_, _)
+            );
+            if temporal || under_temporal {
+                let symmetric = self
+                    .group
+                    .generators
+                    .iter()
+                    .all(|generator| equivalent(formula, &self.rename(generator, formula)));
+                if !symmetric {
+                    return Err(SymmetryError::AsymmetricFormula);
+                }
+            }
+            stack.extend(formula.children().into_iter().map(|f| (f, temporal)));
+        }
+        Ok(())
+    }
+
+    /// The formula with the atoms of each component `i` moved to component `permutation[i]`
+    fn rename(
+        &self,
+        permutation: &[usize],
+        formula: &CTLFormula<ComponentAtom<A>>,
+    ) -> CTLFormula<ComponentAtom<A>> {
+        match formula {
+            CTLFormula::Atom(atom) => {
+                let names = &self.composition.names;
+                match names.iter().position(|name| *name == atom.component) {
+                    Some(i) => CTLFormula::Atom(ComponentAtom::new(
+                        &names[permutation[i]],
+                        atom.atom.clone(),
+                    )),
+                    None => formula.clone(),
+                }
+            }
+            _ => formula.with_children(
+                formula
+                    .children()
+                    .into_iter()
+                    .map(|f| self.rename(permutation, f))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Replaces each state of a path of the quotient after the first by a successor in the composition of the previous state with the same canonical state
+    fn concrete(&self, path: Vec<Vec<S>>) -> Vec<Vec<S>> {
+        let mut states = path.into_iter();
+        let mut concrete = vec![states.next().unwrap()];
+        for next in states {
+            let current = concrete.last().unwrap();
+            let successor = self
+                .composition
+                .transitions(current)
+                .iter()
+                .find(|successor| self.group.canonical(successor) == next)
+                .expect("The quotient has a transition that the composition does not have!")
+                .clone();
+            concrete.push(successor);
+        }
+        concrete
+    }
+}
+
+/// Returns true if the formulas are the same up to the order and grouping of the operands of `∧` and `∨`
+fn equivalent<T: PartialEq>(first: &CTLFormula<T>, second: &CTLFormula<T>) -> bool {
+    match (first, second) {
+        (CTLFormula::Atom(a), CTLFormula::Atom(b)) => a == b,
+        (CTLFormula::And(_, _), CTLFormula::And(_, _))
+        | (CTLFormula::Or(_, _), CTLFormula::Or(_, _)) => {
+            let (mut left, mut right) = (vec![], vec![]);
+            operands(first, &mut left);
+            operands(second, &mut right);
+            left.len() == right.len()
+                && left
+                    .into_iter()
+                    .all(|f| match right.iter().position(|g| equivalent(f, g)) {
+                        Some(i) => {
+                            right.swap_remove(i);
+                            true
+                        }
+                        None => false,
+                    })
+        }
+        _ => {
+            mem::discriminant(first) == mem::discriminant(second)
+                && first
+                    .children()
+                    .into_iter()
+                    .zip(second.children())
+                    .all(|(f, g)| equivalent(f, g))
+        }
+    }
+}
+
+/// Collects the operands of a chain of the same `∧` or `∨` operator
+fn operands<'f, T: PartialEq>(formula: &'f CTLFormula<T>, result: &mut Vec<&'f CTLFormula<T>>) {
+    for child in formula.children() {
+        if mem::discriminant(child) == mem::discriminant(formula) {
+            operands(child, result);
+        } else {
+            result.push(child);
+        }
+    }
+}
+
+// The quotient as a model, kept private so that only symmetric formulas are checked on it
+struct Quotient<'q, 'c, 'a, S, A>(&'q SymmetricComposition<'c, 'a, S, A>);
+
+impl<S: Clone + Ord + Hash, A> Model<Vec<S>, ComponentAtom<A>> for Quotient<'_, '_, '_, S, A> {
+    fn state_has(&self, state: &Vec<S>, atom: &ComponentAtom<A>) -> bool {
+        self.0.composition.state_has(state, atom)
+    }
+
+    fn transitions(&self, state: &Vec<S>) -> &Vec<Vec<S>> {
+        let quotient = self.0;
+        quotient.successors.get_or_insert_with(state, || {
+            let mut nexts: Vec<Vec<S>> = vec![];
+            for successor in quotient.composition.transitions(state) {
+                let canonical = quotient.group.canonical(successor);
+                if !nexts.contains(&canonical) {
+                    nexts.push(canonical);
+                }
+            }
+            nexts
+        })
+    }
+}
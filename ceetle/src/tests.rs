@@ -1,4 +1,5 @@
 use crate::*;
+use crate::builder::*;
 use std::{collections::HashMap, vec};
 
 #[test]
@@ -170,7 +171,10 @@ fn check_au() {
         &"s0",
         &ctl!(AU(Atom("p"), AG(Or(Atom("r"), Atom("q")))))
     ));
-    assert!(!verify(&model, &"s0", &ctl!(AU(Atom("p"), Atom("r")))));
+    // s1 satisfies r, so every path from s0 is p until r
+    assert!(verify(&model, &"s0", &ctl!(AU(Atom("p"), Atom("r")))));
+    assert!(!verify(&model, &"s0", &ctl!(AU(Atom("r"), Atom("q")))));
+    assert!(!verify(&model, &"s2", &ctl!(AU(Atom("q"), Atom("r")))));
 }
 
 #[test]
@@ -192,3 +196,1403 @@ fn readme_test() {
         &ctl!(EF(AG(And(Atom("p"), Atom("q")))))
     )); // Evaluates to true
 }
+
+#[test]
+fn check_normal_forms() {
+    let model = VecDiscreteModel::new(vec![
+        ("s0", vec!["p"], vec!["s1", "s2"]),
+        ("s1", vec!["p", "q"], vec!["s1", "s3"]),
+        ("s2", vec![], vec!["s0", "s3"]),
+        ("s3", vec!["p", "q"], vec!["s3"]),
+    ]);
+    let states = ["s0", "s1", "s2", "s3"];
+
+    let formulas = vec![
+        ctl!(Not(AG(Imply(Atom("p"), AF(Atom("q")))))),
+        ctl!(Not(EX(Or(Atom("p"), Not(Atom("q")))))),
+        ctl!(Not(AU(Atom("p"), Atom("q")))),
+        ctl!(Imply(EF(Atom("q")), AX(Atom("p")))),
+        ctl!(Or(EU(Atom("p"), Atom("q")), AG(Atom("q")))),
+    ];
+
+    for formula in formulas {
+        let nnf = formula.clone().to_nnf();
+        let enf = formula.clone().to_enf();
+        let expanded = formula.clone().expand_derived();
+
+        for state in states.iter() {
+            let expected = verify(&model, state, &formula);
+            assert_eq!(verify(&model, state, &nnf), expected, "{formula} ≢ {nnf}");
+            assert_eq!(verify(&model, state, &enf), expected, "{formula} ≢ {enf}");
+            assert_eq!(verify(&model, state, &expanded), expected, "{formula} ≢ {expanded}");
+        }
+    }
+}
+
+#[test]
+fn check_simplify() {
+    assert_eq!(ctl!(Not(Not(Atom("p")))).simplify(), ctl!(Atom("p")));
+    assert_eq!(ctl!(Or(Atom("p"), And(Not(True), Atom("q")))).simplify(), ctl!(Atom("p")));
+    assert_eq!(ctl!(Imply(Atom("p"), Atom("p"))).simplify(), CTLFormula::True);
+    assert_eq!(ctl!(And(Atom("p"), Not(Atom("p")))).simplify(), CTLFormula::False);
+    assert_eq!(ctl!(EF(EF(Or(Atom("p"), Atom("p"))))).simplify(), ctl!(EF(Atom("p"))));
+    assert_eq!(ctl!(AG(Imply(True, EX(Atom("q"))))).simplify(), ctl!(AG(EX(Atom("q")))));
+}
+
+#[test]
+fn check_rewriter() {
+    let mut rewriter = Rewriter::new();
+    rewriter
+        .add_rule(
+            "double-negation",
+            ctl!(Not(Not(Atom(Pattern::meta("f"))))),
+            ctl!(Atom(Pattern::meta("f"))),
+        )
+        .unwrap()
+        .add_rule(
+            "idempotent-and",
+            ctl!(And(Atom(Pattern::meta("f")), Atom(Pattern::meta("f")))),
+            ctl!(Atom(Pattern::meta("f"))),
+        )
+        .unwrap()
+        .add_rule(
+            "ready",
+            ctl!(Atom(Pattern::Atom("ready"))),
+            ctl!(And(Atom(Pattern::Atom("idle")), Not(Atom(Pattern::Atom("busy"))))),
+        )
+        .unwrap();
+
+    let result = rewriter
+        .rewrite(ctl!(AG(And(Not(Not(Atom("ready"))), Atom("ready")))))
+        .unwrap();
+    assert_eq!(result.formula, ctl!(AG(And(Atom("idle"), Not(Atom("busy"))))));
+    assert_eq!(result.fired, vec!["double-negation", "idempotent-and", "ready"]);
+
+    assert_eq!(
+        rewriter.add_rule("unbound", ctl!(True), ctl!(Atom(Pattern::meta("g")))).err(),
+        Some(RewriteError::UnboundMetavariable {
+            rule: "unbound".to_string(),
+            name: "g".to_string()
+        })
+    );
+}
+
+#[test]
+fn check_rewriter_termination() {
+    let mut rewriter = Rewriter::new();
+    rewriter
+        .add_rule(
+            "commute",
+            ctl!(Or(Atom(Pattern::meta("f")), Atom(Pattern::meta("g")))),
+            ctl!(Or(Atom(Pattern::meta("g")), Atom(Pattern::meta("f")))),
+        )
+        .unwrap();
+
+    assert_eq!(
+        rewriter.rewrite(ctl!(Or(Atom("p"), Atom("q")))).err(),
+        Some(RewriteError::Cycle {
+            rules: vec!["commute".to_string(), "commute".to_string()]
+        })
+    );
+
+    let mut rewriter = Rewriter::new().with_max_steps(5);
+    rewriter
+        .add_rule("grow", ctl!(Atom(Pattern::Atom("p"))), ctl!(EX(Atom(Pattern::Atom("p")))))
+        .unwrap();
+    assert_eq!(
+        rewriter.rewrite(ctl!(Atom("p"))).err(),
+        Some(RewriteError::StepLimitExceeded { steps: 5 })
+    );
+}
+
+#[test]
+fn check_printers() {
+    let f = ctl!(Or(
+        AU(Atom("p"), Imply(Atom("q"), Imply(Atom("r"), Atom("p")))),
+        Not(EX(And(Atom("q"), Or(Atom("r"), True))))
+    ));
+
+    assert_eq!(
+        f.display(Notation::Ascii).to_string(),
+        "A[p U q -> r -> p] | !EX(q & (r | true))"
+    );
+    assert_eq!(
+        f.display(Notation::Unicode).to_string(),
+        "A[p U q → r → p] ∨ ¬EX(q ∧ (r ∨ ⊤))"
+    );
+    assert_eq!(
+        ctl!(AG(Imply(Atom("x_1"), AF(Atom("y"))))).display(Notation::Latex).to_string(),
+        "\\mathbf{AG}\\left(\\mathit{x\\_1} \\rightarrow \\mathbf{AF}\\,\\mathit{y}\\right)"
+    );
+}
+
+#[test]
+fn check_printers_round_trip() {
+    let s = |name: &str| name.to_string();
+    let formulas = vec![
+        ctl!(Imply(Imply(Atom(s("p")), Atom(s("q"))), Atom(s("r")))),
+        ctl!(And(Atom(s("p")), And(Atom(s("q")), Atom(s("r"))))),
+        ctl!(Or(And(Atom(s("p")), Atom(s("q"))), Not(Or(Atom(s("p")), Atom(s("q")))))),
+        ctl!(AG(Imply(Atom(s("req")), AF(Atom(s("proc1.ack")))))),
+        ctl!(EU(Not(Atom(s("E"))), AX(EG(Atom(s("x > 3")))))),
+        ctl!(Not(Not(AU(True, Not(True))))),
+        ctl!(EF(And(Atom(s("quote\"d")), Atom(s("back\\slash"))))),
+    ];
+
+    for formula in formulas {
+        for notation in [Notation::Ascii, Notation::Unicode] {
+            let printed = formula.display(notation).to_string();
+            assert_eq!(parse_formula(&printed), Ok(formula.clone()), "{printed}");
+        }
+    }
+
+    let f = ctl!(AG(Imply(Atom(s("p")), EU(Atom(s("q")), Not(Atom(s("r")))))));
+    assert_eq!(f.to_string().parse(), Ok(f));
+}
+
+#[test]
+fn check_parse_errors() {
+    assert_eq!(parse_formula("AG(p &").unwrap_err().position, 6);
+    assert_eq!(parse_formula("A[p q]").unwrap_err().position, 4);
+    assert_eq!(parse_formula("p q").unwrap_err().position, 2);
+    assert!(parse_formula("p $ q").is_err());
+    assert!(parse_formula("\"p").is_err());
+}
+
+#[test]
+fn check_builder() {
+    let p = || atom("p");
+    let q = || atom("q");
+
+    assert_eq!(
+        ag(p().implies(af(q()))),
+        ctl!(AG(Imply(Atom("p"), AF(Atom("q")))))
+    );
+    assert_eq!(
+        eu(p(), q() & !p()) | ex(p().and(q()).or(CTLFormula::True)),
+        ctl!(Or(
+            EU(Atom("p"), And(Atom("q"), Not(Atom("p")))),
+            EX(Or(And(Atom("p"), Atom("q")), True))
+        ))
+    );
+    assert_eq!(
+        au(not(p()), eg(ef(ax(imply(p(), or(q(), and(p(), q()))))))),
+        ctl!(AU(
+            Not(Atom("p")),
+            EG(EF(AX(Imply(Atom("p"), Or(Atom("q"), And(Atom("p"), Atom("q")))))))
+        ))
+    );
+
+    let model = VecDiscreteModel::new(vec![
+        ("s0", vec!["p"], vec!["s1"]),
+        ("s1", vec!["q"], vec!["s0"]),
+    ]);
+    assert!(verify(&model, &"s0", &ag(p() | q())));
+    assert!(!verify(&model, &"s0", &ef(p() & q())));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn check_formula_serialization() {
+    let formula = ctl!(AG(Imply(
+        Atom("p".to_string()),
+        EU(True, Atom("q".to_string()))
+    )));
+    let json = serde_json::to_string(&formula).unwrap();
+    assert_eq!(
+        json,
+        r#"{"version":1,"formula":{"op":"AG","args":{"op":"Imply","args":[{"op":"Atom","args":"p"},{"op":"EU","args":[{"op":"True"},{"op":"Atom","args":"q"}]}]}}}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<CTLFormula<String>>(&json).unwrap(),
+        formula
+    );
+
+    assert!(serde_json::from_str::<CTLFormula<String>>(
+        r#"{"version":2,"formula":{"op":"True"}}"#
+    )
+    .is_err());
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn check_model_serialization() {
+    let model = VecDiscreteModel::new(vec![
+        ("s0", vec!["p"], vec!["s0", "s1"]),
+        ("s1", vec!["p", "q"], vec!["s0"]),
+    ])
+    .with_initial_states(vec!["s0"]);
+
+    let json = serde_json::to_string(&model).unwrap();
+    assert_eq!(
+        json,
+        r#"{"version":1,"states":[{"name":"s0","labels":["p"],"transitions":["s0","s1"]},{"name":"s1","labels":["p","q"],"transitions":["s0"]}],"initial":["s0"]}"#
+    );
+
+    let hashed: HashedDiscreteModel<String, String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(hashed.initial_states(), &vec!["s0".to_string()]);
+    assert!(hashed.state_has(&"s1".to_string(), &"q".to_string()));
+    assert!(verify(
+        &hashed,
+        &"s0".to_string(),
+        &ctl!(EX(Atom("q".to_string())))
+    ));
+
+    let restored: VecDiscreteModel<String, String> =
+        serde_json::from_str(&serde_json::to_string(&hashed).unwrap()).unwrap();
+    assert_eq!(restored.transitions(&"s0".to_string()).len(), 2);
+
+    // The states of a hashed model are sorted, whatever the order of the map
+    let states: HashMap<u32, (Vec<&str>, Vec<u32>)> =
+        (0..20).rev().map(|i| (i, (vec![], vec![(i + 1) % 20]))).collect();
+    let json = serde_json::to_string(&HashedDiscreteModel::new(states)).unwrap();
+    let names: Vec<u32> = serde_json::from_str::<VecDiscreteModel<u32, String>>(&json)
+        .unwrap()
+        .states()
+        .into_iter()
+        .copied()
+        .collect();
+    assert_eq!(names, (0..20).collect::<Vec<_>>());
+
+    assert!(serde_json::from_str::<VecDiscreteModel<String, String>>(
+        r#"{"version":2,"states":[]}"#
+    )
+    .is_err());
+}
+
+#[test]
+fn check_dot_export() {
+    let model = VecDiscreteModel::new(vec![
+        ("s0", vec!["p"], vec!["s1", "s2"]),
+        ("s1", vec!["p", "q"], vec!["s2"]),
+        ("s2", vec![], vec!["s0"]),
+    ])
+    .with_initial_states(vec!["s0"]);
+
+    let formula = ctl!(EX(Atom("q")));
+    let options = DotOptions::new()
+        .with_name("example")
+        .with_formula(&formula)
+        .with_path(vec!["s0", "s1"])
+        .with_clusters(|s| (*s != "s2").then(|| "upper".to_string()));
+
+    assert_eq!(
+        to_dot(&model, &options),
+        r#"digraph "example" {
+    "s2" [label="s2\n{}", atoms="", style=filled, fillcolor=lightpink];
+    subgraph "cluster_upper" {
+        label="upper";
+        "s0" [label="s0\n{p}", atoms="p", style=filled, fillcolor=palegreen, color=blue, penwidth=2];
+        "s1" [label="s1\n{p, q}", atoms="p, q", style=filled, fillcolor=lightpink, color=blue, penwidth=2];
+    }
+    "__initial0" [shape=point];
+    "__initial0" -> "s0";
+    "s0" -> "s1" [color=blue, penwidth=2];
+    "s0" -> "s2";
+    "s1" -> "s2";
+    "s2" -> "s0";
+}
+"#
+    );
+
+    // The points of the initial arrows do not take the name of a state
+    let model: VecDiscreteModel<&str, &str> =
+        VecDiscreteModel::new(vec![("__initial0", vec![], vec!["__initial0"])])
+            .with_initial_states(vec!["__initial0"]);
+    let dot = to_dot(&model, &DotOptions::new());
+    assert!(dot.contains("\"___initial0\" [shape=point];"));
+    assert!(dot.contains("\"___initial0\" -> \"__initial0\";"));
+}
+
+#[test]
+fn check_dot_import() {
+    let model = from_dot(
+        r#"
+        /* Mutual exclusion */
+        digraph mutex {
+            node [shape=circle];
+            start [shape=point];
+            start -> idle;
+            idle [label="n1 n2"];
+            "wait 1" [atoms="t1, n2"]; busy [atoms="c1,n2", initial=true]
+            idle -> "wait 1" -> busy -> idle; // loop
+            subgraph cluster_x { done; }
+            busy -> done -> done [color=red];
+        }
+        "#,
+    )
+    .unwrap();
+
+    let s = |name: &str| name.to_string();
+    assert_eq!(model.initial_states(), &vec![s("busy"), s("idle")]);
+    assert_eq!(model.labels(&s("wait 1")), &vec![s("t1"), s("n2")]);
+    assert_eq!(model.transitions(&s("busy")), &vec![s("idle"), s("done")]);
+    assert_eq!(model.states().len(), 4);
+    assert!(verify(&model, &s("idle"), &ctl!(EF(Atom(s("c1"))))));
+
+    let undirected = from_dot_with(
+        "graph { a [props=\"p\", start=yes]; a -- b }",
+        &DotImportOptions::new()
+            .with_atom_attributes(&["props"])
+            .with_initial_attribute("start"),
+    )
+    .unwrap();
+    assert_eq!(undirected.initial_states(), &vec![s("a")]);
+    assert_eq!(undirected.transitions(&s("b")), &vec![s("a")]);
+    assert!(undirected.state_has(&s("a"), &s("p")));
+
+    let error = from_dot("digraph {\n  a -> ;\n}").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 8));
+    assert!(from_dot("digraph { a [label=\"p] }").is_err());
+    assert!(from_dot("tree { }").is_err());
+
+    // A subgraph stands for all the nodes it mentions, including those declared before it
+    let model = from_dot("digraph { a; b; { a b } -> c; c -> { a; d } }").unwrap();
+    assert_eq!(model.transitions(&s("a")), &vec![s("c")]);
+    assert_eq!(model.transitions(&s("b")), &vec![s("c")]);
+    assert_eq!(model.transitions(&s("c")), &vec![s("a"), s("d")]);
+}
+
+#[test]
+fn check_dot_round_trip() {
+    let s = |name: &str| name.to_string();
+    let mut states = HashMap::new();
+    states.insert(s("idle"), (vec![s("n1"), s("n2")], vec![s("wait \"1\"")]));
+    states.insert(s("wait \"1\""), (vec![s("t1")], vec![s("back\\slash")]));
+    states.insert(s("back\\slash"), (vec![], vec![s("idle"), s("back\\slash")]));
+    let model = HashedDiscreteModel::new(states).with_initial_states(vec![s("idle")]);
+
+    let formula = ctl!(EF(Atom(s("t1"))));
+    for options in [
+        DotOptions::new(),
+        DotOptions::new().with_labels(false).with_formula(&formula),
+    ] {
+        let imported = from_dot(&to_dot(&model, &options)).unwrap();
+        assert_eq!(imported.initial_states(), model.initial_states());
+        assert_eq!(imported.states().len(), 3);
+        for state in model.states() {
+            assert_eq!(imported.labels(state), model.labels(state));
+            assert_eq!(imported.transitions(state), model.transitions(state));
+        }
+    }
+}
+
+#[test]
+fn check_smv() {
+    let program = parse_smv(
+        "
+        MODULE main -- a two-process mutex
+        VAR
+            turn : {p1, p2};
+            busy : boolean;
+            count : 0..2;
+        ASSIGN
+            init(busy) := FALSE;
+            init(count) := 0;
+            next(busy) := !busy;
+            next(turn) := case
+                busy : {p1, p2};
+                TRUE : turn;
+            esac;
+            next(count) := (count + 1) mod 3;
+        DEFINE
+            idle := !busy;
+        SPEC AG (busy -> AX idle)
+        SPEC EF (count = 2 & turn = p2)
+        CTLSPEC AG AF count = 0
+        ",
+    )
+    .unwrap();
+
+    let s = |name: &str| name.to_string();
+    assert_eq!(program.specs.len(), 3);
+    assert_eq!(
+        program.specs[1],
+        ctl!(EF(And(Atom(s("count = 2")), Atom(s("turn = p2")))))
+    );
+
+    let model = &program.model;
+    let initial = s("turn=p1, busy=FALSE, count=0");
+    assert_eq!(model.initial_states().len(), 2);
+    assert!(model.state_has(&initial, &s("idle")));
+    assert!(model.state_has(&initial, &s("count=0")));
+    assert_eq!(
+        model.transitions(&initial),
+        &vec![s("turn=p1, busy=TRUE, count=1")]
+    );
+    for spec in program.specs.iter() {
+        assert!(verify(model, &initial, spec));
+    }
+}
+
+#[test]
+fn check_smv_errors() {
+    let error =
+        parse_smv("MODULE main\nVAR\n  x : 0..1;\nASSIGN\n  next(x) := x + 1;\n").unwrap_err();
+    assert_eq!((error.line, error.column), (5, 3));
+    assert!(parse_smv("MODULE other\n").is_err());
+    assert!(parse_smv("MODULE main\nVAR x : boolean;\nSPEC AG next(x)").is_err());
+    assert!(parse_smv("MODULE main\nVAR x : boolean;\nASSIGN init(y) := TRUE;").is_err());
+    let error = parse_smv("MODULE main\nVAR busy : boolean;\nFAIRNESS busy\n").unwrap_err();
+    assert_eq!((error.line, error.column), (3, 1));
+    assert!(error.message.contains("not supported"), "{error}");
+
+    // DEFINE cycles are rejected instead of being evaluated forever
+    let error = parse_smv("MODULE main\nVAR x : boolean;\nDEFINE\n  a := b;\n  b := !a;\n").unwrap_err();
+    assert_eq!((error.line, error.column), (4, 3));
+    assert!(parse_smv("MODULE main\nVAR x : boolean;\nDEFINE a := a;").is_err());
+
+    // A name is declared once, as a variable or as a DEFINE
+    let error = parse_smv("MODULE main\nVAR x : boolean;\nDEFINE\n  d := x;\n  d := !x;\n").unwrap_err();
+    assert_eq!((error.line, error.column), (5, 3));
+    assert!(error.message.contains("defined twice"), "{error}");
+    let error = parse_smv("MODULE main\nVAR x : boolean;\nDEFINE x := TRUE;\n").unwrap_err();
+    assert_eq!((error.line, error.column), (3, 8));
+    assert!(parse_smv("MODULE main\nDEFINE d := TRUE;\nVAR d : boolean;\n").is_err());
+
+    // Misspelled names are not read as values of an enumeration
+    let program = "MODULE main\nVAR\n  state : {idle, busy};\nASSIGN\n  next(state) := busy;\n";
+    assert!(parse_smv(&format!("{program}SPEC AG (state = idle | state = busy)")).is_ok());
+    let error = parse_smv(&format!("{program}SPEC AG (stat = idle)")).unwrap_err();
+    assert_eq!((error.line, error.column), (6, 1));
+    assert!(parse_smv(&format!("{program}SPEC AG (state = idel)")).is_err());
+
+    // Arithmetic overflow is an error
+    let error = parse_smv(
+        "MODULE main\nVAR x : 0..1;\nDEFINE big := 9223372036854775807;\nSPEC AG (big + x > 0)",
+    )
+    .unwrap_err();
+    assert!(error.message.contains("overflow"), "{error}");
+    assert!(parse_smv("MODULE main\nVAR x : 0..1;\nSPEC AG (-9223372036854775807 - 2 * x < 0)").is_err());
+
+    // Errors in atoms are reported where the atom is
+    let error = parse_smv("MODULE main\nVAR x : 0..1;\nSPEC AG (x = 0)\nSPEC EF (x = 1 & x + 1)").unwrap_err();
+    assert_eq!((error.line, error.column), (4, 18));
+}
+
+#[test]
+fn check_witnesses() {
+    let model = VecDiscreteModel::new(vec![
+        ("s0", vec!["p"], vec!["s1", "s2"]),
+        ("s1", vec!["p"], vec!["s3"]),
+        ("s2", vec!["q"], vec!["s2"]),
+        ("s3", vec!["p"], vec!["s1"]),
+    ]);
+
+    assert_eq!(
+        witness(&model, &"s0", &ctl!(EG(Atom("p")))),
+        Some(vec!["s0", "s1", "s3", "s1"])
+    );
+    assert_eq!(
+        witness(&model, &"s0", &ctl!(EU(Atom("p"), Atom("q")))),
+        Some(vec!["s0", "s2"])
+    );
+    assert_eq!(
+        counterexample(&model, &"s0", &ctl!(AF(Atom("q")))),
+        Some(vec!["s0", "s1", "s3", "s1"])
+    );
+    assert_eq!(
+        counterexample(&model, &"s0", &ctl!(AX(Atom("p")))),
+        Some(vec!["s0", "s2"])
+    );
+    assert_eq!(counterexample(&model, &"s0", &ctl!(EF(Atom("q")))), None);
+
+    // Paths that end in a state without transitions
+    let model = VecDiscreteModel::new(vec![("s0", vec![], vec!["s1"]), ("s1", vec![], vec![])]);
+    assert!(!verify(&model, &"s0", &ctl!(AF(Atom("q")))));
+    assert_eq!(
+        counterexample(&model, &"s0", &ctl!(AF(Atom("q")))),
+        Some(vec!["s0", "s1"])
+    );
+    assert_eq!(
+        witness(&model, &"s0", &ctl!(EG(Not(Atom("q"))))),
+        Some(vec!["s0", "s1"])
+    );
+    assert_eq!(
+        witness(&model, &"s1", &ctl!(EG(Not(Atom("q"))))),
+        Some(vec!["s1"])
+    );
+}
+
+#[test]
+fn check_model_macro() {
+    let model = model! {
+        idle [ready] -> busy;
+        busy ["in use", ready] -> idle, busy;
+        "shut down";
+        init idle;
+    };
+
+    assert_eq!(model.initial_states(), &vec!["idle"]);
+    assert_eq!(model.transitions(&"busy"), &vec!["idle", "busy"]);
+    assert_eq!(model.labels(&"busy"), &vec!["in use", "ready"]);
+    assert!(model.transitions(&"shut down").is_empty());
+    assert!(verify(&model, &"idle", &ctl!(AG(Atom("ready")))));
+    assert!(!verify(&model, &"idle", &ctl!(False)));
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct Counter {
+    value: u8,
+    enabled: bool,
+}
+
+#[ctl_model]
+impl Counter {
+    #[ctl(next)]
+    fn next(&self) -> Vec<Self> {
+        let mut nexts = vec![Counter {
+            enabled: !self.enabled,
+            ..*self
+        }];
+        if self.enabled {
+            nexts.push(Counter {
+                value: (self.value + 1) % 4,
+                ..*self
+            });
+        }
+        nexts
+    }
+
+    #[ctl(atom)]
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    #[ctl(atom = "zero")]
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct Unbounded(u64);
+
+#[ctl_model]
+impl Unbounded {
+    #[ctl(next)]
+    fn next(&self) -> Vec<Self> {
+        vec![Unbounded(self.0 + 1)]
+    }
+
+    #[ctl(atom = "zero")]
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+#[test]
+fn check_state_machine_model() {
+    let start = Counter {
+        value: 0,
+        enabled: false,
+    };
+    let model = StateMachineModel::new(vec![start.clone()]);
+
+    assert_eq!(Counter::atoms(), &["enabled", "zero"]);
+    assert!(verify(&model, &start, &ctl!(Atom("zero"))));
+    assert!(verify(&model, &start, &ctl!(AX(Atom("enabled")))));
+    assert!(verify(&model, &start, &ctl!(AG(EF(Atom("zero"))))));
+    assert!(!verify(&model, &start, &ctl!(AG(Atom("zero")))));
+
+    let explored = model.explore();
+    assert_eq!(explored.states().len(), 8);
+    assert_eq!(explored.labels(&start), &vec!["zero"]);
+
+    // Formulas without unbounded operators only explore as far as their `EX` and `AX` look
+    let counter = StateMachineModel::new(vec![Unbounded(0)]);
+    assert!(verify(&counter, &Unbounded(0), &ctl!(Atom("zero"))));
+    assert!(verify(&counter, &Unbounded(0), &ctl!(AX(AX(Not(Atom("zero")))))));
+    assert!(!verify(&counter, &Unbounded(1), &ctl!(EX(Atom("zero")))));
+    assert_eq!(
+        satisfying_states(
+            &counter,
+            &[Unbounded(0), Unbounded(1)],
+            &ctl!(Or(Atom("zero"), AX(Atom("zero"))))
+        ),
+        vec![&Unbounded(0)]
+    );
+}
+
+#[test]
+fn check_cache() {
+    let cache: cache::Cache<u32, Vec<u32>> = cache::Cache::new();
+    let first = cache.get_or_insert_with(&0, || vec![0]);
+    // Values computed inside another computation are kept, and earlier references stay valid while the cache grows
+    let nested = cache.get_or_insert_with(&1, || {
+        cache.get_or_insert_with(&2, || vec![2]);
+        vec![1]
+    });
+    for i in 3..1000 {
+        cache.get_or_insert_with(&i, || vec![i]);
+    }
+
+    assert_eq!(first, &vec![0]);
+    assert_eq!(nested, &vec![1]);
+    assert_eq!(cache.get_or_insert_with(&2, Vec::new), &vec![2]);
+    assert_eq!(cache.get_or_insert_with(&999, Vec::new), &vec![999]);
+}
+
+#[test]
+fn check_model_builder() {
+    let model = ModelBuilder::new()
+        .with_state("s0", vec!["p"])
+        .with_state("s1", vec!["q"])
+        .with_transition("s0", "s1")
+        .with_transition("s0", "s1")
+        .with_transition("s1", "s0")
+        .with_initial("s0");
+    let vec_model = model.clone().build_vec().unwrap();
+    assert_eq!(vec_model.states(), vec![&"s0", &"s1"]);
+    assert_eq!(vec_model.transitions(&"s0"), &vec!["s1"]);
+    assert!(verify(
+        &model.clone().build().unwrap(),
+        &"s0",
+        &ctl!(AG(EF(Atom("q"))))
+    ));
+
+    assert_eq!(
+        model.clone().with_state("s0", vec![]).build().unwrap_err(),
+        ModelError::DuplicateState("s0")
+    );
+    assert_eq!(
+        model.with_initial("s2").build_vec().unwrap_err(),
+        ModelError::UnknownState("s2")
+    );
+}
+
+#[test]
+fn check_model_mutations() {
+    let mut model = model! {
+        s0 [p] -> s1;
+        s1 [q] -> s0;
+        init s0;
+    };
+
+    model.add_state("s2", vec!["r"]).unwrap();
+    model.add_transition(&"s1", "s2").unwrap();
+    assert_eq!(
+        model.add_transition(&"s1", "s9"),
+        Err(ModelError::UnknownState("s9"))
+    );
+    assert_eq!(
+        model.add_state("s2", vec![]),
+        Err(ModelError::DuplicateState("s2"))
+    );
+    assert!(verify(&model, &"s0", &ctl!(EF(Atom("r")))));
+
+    assert_eq!(model.set_labels(&"s2", vec!["p", "r"]), Ok(vec!["r"]));
+    model.remove_transition(&"s1", &"s0").unwrap();
+    assert_eq!(
+        model.remove_transition(&"s1", &"s0"),
+        Err(ModelError::UnknownTransition("s1", "s0"))
+    );
+    assert_eq!(model.remove_state(&"s1"), Ok(vec!["q"]));
+    assert!(model.transitions(&"s0").is_empty());
+
+    let other = model! {
+        s0 [q] -> s3;
+        s3 -> s0;
+        init s3;
+    };
+    model.merge(other).unwrap();
+    assert_eq!(model.labels(&"s0"), &vec!["p", "q"]);
+    assert_eq!(model.initial_states(), &vec!["s0", "s3"]);
+    let dangling = HashedDiscreteModel::new(HashMap::from([("s4", (vec![], vec!["s5"]))]));
+    assert_eq!(model.merge(dangling), Err(ModelError::UnknownState("s5")));
+    assert_eq!(model.states().len(), 3);
+
+    let mut vec_model = VecDiscreteModel::new(vec![("a", vec![1], vec!["a"])]);
+    vec_model.add_state("b", vec![2]).unwrap();
+    vec_model.add_transition(&"b", "a").unwrap();
+    assert_eq!(vec_model.remove_state(&"a"), Ok(vec![1]));
+    assert!(vec_model.transitions(&"b").is_empty());
+}
+
+#[test]
+fn check_validation() {
+    let model = VecDiscreteModel::new(vec![
+        ("s0", vec!["p"], vec!["s1", "s4"]),
+        ("s1", vec!["q"], vec![]),
+        ("s2", vec!["p"], vec!["s2"]),
+        ("s1", vec!["r"], vec!["s0"]),
+    ])
+    .with_initial_states(vec!["s0", "s5"]);
+
+    let diagnostics = validate(&model);
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic::DuplicateState("s1"),
+            Diagnostic::DanglingTransition {
+                from: "s0",
+                to: "s4"
+            },
+            Diagnostic::UnknownInitialState("s5"),
+            Diagnostic::Deadlock("s1"),
+            Diagnostic::Unreachable("s2"),
+        ]
+    );
+    let severities: Vec<Severity> = diagnostics.iter().map(|d| d.severity()).collect();
+    assert_eq!(
+        severities,
+        vec![
+            Severity::Error,
+            Severity::Error,
+            Severity::Error,
+            Severity::Warning,
+            Severity::Info
+        ]
+    );
+    assert_eq!(
+        diagnostics[1].to_string(),
+        "error: transition from \"s0\" leads to \"s4\", which is not in the model"
+    );
+
+    let formulas = [ctl!(AG(Imply(Atom("p"), AF(Atom("z")))))];
+    let diagnostics = validate_with_formulas(&model, &formulas);
+    assert_eq!(
+        diagnostics[5..],
+        [Diagnostic::UndefinedAtom("z"), Diagnostic::UnusedAtom("q")]
+    );
+
+    let fine = model! { a [p] -> a; init a; };
+    assert!(validate_with_formulas(&fine, &[ctl!(AG(Atom("p")))]).is_empty());
+}
+
+#[test]
+fn check_bisimulation_quotients() {
+    let model = VecDiscreteModel::new(vec![
+        ("s0", vec!["p"], vec!["s1", "s2"]),
+        ("s1", vec!["p"], vec!["s3"]),
+        ("s2", vec!["p"], vec!["s4"]),
+        ("s3", vec!["q"], vec!["s3"]),
+        ("s4", vec!["q"], vec!["s4"]),
+        ("s5", vec!["p"], vec!["s5", "s3"]),
+        ("s6", vec!["p"], vec![]),
+        ("s7", vec!["p"], vec!["s6"]),
+    ])
+    .with_initial_states(vec!["s0", "s5"]);
+
+    let strong = bisimulation_quotient(&model);
+    assert_eq!(
+        strong.classes,
+        vec![
+            vec!["s0"],
+            vec!["s1", "s2"],
+            vec!["s3", "s4"],
+            vec!["s5"],
+            vec!["s6"],
+            vec!["s7"]
+        ]
+    );
+    assert_eq!(strong.model.initial_states(), &vec![0, 3]);
+
+    let stuttering = stuttering_bisimulation_quotient(&model);
+    assert_eq!(
+        stuttering.classes,
+        vec![
+            vec!["s0", "s1", "s2"],
+            vec!["s3", "s4"],
+            vec!["s5"],
+            vec!["s6", "s7"]
+        ]
+    );
+    assert_eq!(*stuttering.model.transitions(&2), vec![2, 1]);
+    assert_eq!(*stuttering.model.transitions(&3), vec![3]);
+
+    let formulas = [
+        ctl!(EG(Atom("p"))),
+        ctl!(AF(Atom("q"))),
+        ctl!(AU(Atom("p"), Atom("q"))),
+        ctl!(EF(AG(Atom("q")))),
+        ctl!(AG(Imply(Atom("p"), EF(Atom("q"))))),
+    ];
+    let next_formulas = [ctl!(AX(Atom("q"))), ctl!(EX(EX(Atom("p"))))];
+    for state in model.states() {
+        for formula in formulas.iter().chain(next_formulas.iter()) {
+            let class = strong.class_of(state).unwrap();
+            assert_eq!(
+                verify(&model, state, formula),
+                verify(&strong.model, &class, formula)
+            );
+        }
+        for formula in formulas.iter() {
+            let class = stuttering.class_of(state).unwrap();
+            assert_eq!(
+                verify(&model, state, formula),
+                verify(&stuttering.model, &class, formula)
+            );
+        }
+    }
+
+    // The strong quotient cannot be reduced further, while the stuttering quotient merges states that `AX` tells apart
+    assert_eq!(bisimulation_quotient(&strong.model).classes.len(), strong.classes.len());
+    assert_eq!(stuttering.class_of(&"s0"), stuttering.class_of(&"s1"));
+    assert!(!verify(&model, &"s0", &next_formulas[0]));
+    assert!(verify(&model, &"s1", &next_formulas[0]));
+}
+
+#[test]
+fn check_equivalences() {
+    let original = VecDiscreteModel::new(vec![
+        ("s0", vec!["idle"], vec!["s1"]),
+        ("s1", vec!["busy"], vec!["s0", "s2"]),
+        ("s2", vec!["done"], vec!["s2"]),
+    ]);
+    let refactored = model! {
+        a [idle] -> b1, b2;
+        b1 [busy] -> a, c;
+        b2 [busy] -> a, c;
+        c [done] -> c;
+    };
+    assert!(bisimilar(&original, &"s0", &refactored, &"a").is_ok());
+    assert!(simulates(&original, &"s0", &refactored, &"a").is_ok());
+    assert!(simulates(&refactored, &"a", &original, &"s0").is_ok());
+
+    let broken = model! {
+        a [idle] -> b1, b2;
+        b1 [busy] -> a, c;
+        b2 [busy] -> a;
+        c [done] -> c;
+    };
+    let formula = bisimilar(&original, &"s0", &broken, &"a").unwrap_err();
+    assert_eq!(formula, ctl!(Not(EX(Not(EX(Atom("done")))))));
+    assert!(verify(&original, &"s0", &formula));
+    assert!(!verify(&broken, &"a", &formula));
+
+    assert!(simulates(&broken, &"a", &original, &"s0").is_ok());
+    assert!(simulates(&original, &"s0", &broken, &"a").is_ok());
+    assert_eq!(
+        bisimilar(&broken, &"a", &original, &"s0"),
+        Err(ctl!(EX(Not(EX(Atom("done"))))))
+    );
+}
+
+#[test]
+fn check_compositions() {
+    let process = model! {
+        idle -> wait;
+        wait [waiting] -> crit;
+        crit [crit] -> idle;
+    };
+    let stuck = model! { halt [halted]; };
+
+    let system = Composition::interleaving(vec![("p1", &process), ("p2", &process)]);
+    assert_eq!(system.components(), vec!["p1", "p2"]);
+    assert_eq!(
+        *system.transitions(&vec!["idle", "crit"]),
+        vec![vec!["wait", "crit"], vec!["idle", "idle"]]
+    );
+    assert!(system.state_has(&vec!["idle", "crit"], &ComponentAtom::new("p2", "crit")));
+    assert!(!system.state_has(&vec!["idle", "crit"], &ComponentAtom::new("p3", "crit")));
+    assert_eq!(ComponentAtom::new("p1", "crit").to_string(), "p1.crit");
+
+    let crit = |name| atom(ComponentAtom::new(name, "crit"));
+    let start = vec!["idle", "idle"];
+    assert!(verify(&system, &start, &ef(and(crit("p1"), crit("p2")))));
+    assert_eq!(reachable(&system, &[start]).len(), 9);
+
+    let lockstep = Composition::synchronous(vec![("p", &process), ("halt", &stuck)]);
+    assert!(lockstep.transitions(&vec!["idle", "halt"]).is_empty());
+    let free = Composition::interleaving(vec![("p", &process), ("halt", &stuck)]);
+    assert_eq!(
+        *free.transitions(&vec!["idle", "halt"]),
+        vec![vec!["wait", "halt"]]
+    );
+
+    let sender = LabelledDiscreteModel::new(vec![
+        ("ready", vec![], vec![("send", "sent")]),
+        ("sent", vec!["sent"], vec![("ack", "ready")]),
+    ]);
+    let receiver = LabelledDiscreteModel::new(vec![
+        ("empty", vec![], vec![("send", "full")]),
+        ("full", vec!["full"], vec![("consume", "done")]),
+        ("done", vec![], vec![("ack", "empty")]),
+    ]);
+    let channel = Composition::synchronized(vec![("tx", &sender), ("rx", &receiver)]);
+    let start = vec!["ready", "empty"];
+    assert_eq!(*channel.transitions(&start), vec![vec!["sent", "full"]]);
+    let full = atom(ComponentAtom::new("rx", "full"));
+    let sent = atom(ComponentAtom::new("tx", "sent"));
+    assert!(verify(&channel, &start, &ag(imply(full, sent))));
+}
+
+#[test]
+fn check_partial_order_reduction() {
+    let process = model! {
+        idle -> think;
+        think -> wait;
+        wait [waiting] -> crit;
+        crit [crit] -> idle;
+    };
+    let system =
+        Composition::interleaving(vec![("p1", &process), ("p2", &process), ("p3", &process)]);
+    let start = vec!["idle", "idle", "idle"];
+    let crit = |name| atom(ComponentAtom::new(name, "crit"));
+    let waiting = |name| atom(ComponentAtom::new(name, "waiting"));
+
+    let formulas = vec![
+        ag(ef(crit("p1"))),
+        ef(and(crit("p1"), crit("p2"))),
+        ag(imply(waiting("p1"), ef(crit("p1")))),
+        ctl!(EG(Not(Atom(ComponentAtom::new("p2", "crit"))))),
+        ctl!(AF(Atom(ComponentAtom::new("p3", "waiting")))),
+        ctl!(EU(
+            Not(Atom(ComponentAtom::new("p1", "crit"))),
+            Atom(ComponentAtom::new("p2", "crit"))
+        )),
+    ];
+    for formula in &formulas {
+        let reduced = system.reduce(formula).unwrap();
+        assert_eq!(
+            verify(&reduced, &start, formula),
+            verify(&system, &start, formula),
+            "{formula}"
+        );
+    }
+
+    // Only one of the independent invisible steps out of the start state is explored, and every explored step is a step of the system
+    let reduced = system.reduce(&formulas[0]).unwrap();
+    let initial = vec![start.clone()];
+    assert_eq!(system.transitions(&start).len(), 3);
+    assert_eq!(reduced.transitions(&start).len(), 1);
+    assert_eq!(reachable(&system, &initial).len(), 64);
+    let explored = reachable(&reduced, &initial);
+    assert!(explored.len() < 64);
+    assert!(explored.iter().all(|state| reduced
+        .transitions(state)
+        .iter()
+        .all(|next| system.transitions(state).contains(next))));
+    assert_eq!(
+        system
+            .reduce(&ctl!(AX(Atom(ComponentAtom::new("p1", "crit")))))
+            .err(),
+        Some(ReductionError::NextOperator)
+    );
+
+    let sender = LabelledDiscreteModel::new(vec![
+        ("ready", vec![], vec![("prepare", "loaded")]),
+        ("loaded", vec![], vec![("send", "ready")]),
+    ]);
+    let receiver = LabelledDiscreteModel::new(vec![
+        ("empty", vec![], vec![("send", "full")]),
+        ("full", vec!["full"], vec![("consume", "empty")]),
+    ]);
+    let channel = Composition::synchronized(vec![("tx", &sender), ("rx", &receiver)]);
+    let start = vec!["ready", "empty"];
+    let formula = ag(ef(atom(ComponentAtom::new("rx", "full"))));
+    let reduced = channel.reduce(&formula).unwrap();
+    assert!(verify(&reduced, &start, &formula));
+    assert_eq!(reduced.transitions(&start).len(), 1);
+}
+
+#[test]
+fn check_symmetry_reduction() {
+    let process = model! {
+        idle -> wait;
+        wait [waiting] -> crit, idle;
+        crit [crit] -> idle;
+    };
+    let clock = model! {
+        tick -> tock;
+        tock [tock] -> tick;
+    };
+    let system = Composition::interleaving(vec![
+        ("p1", &process),
+        ("p2", &process),
+        ("p3", &process),
+        ("clock", &clock),
+    ]);
+    let start = vec!["idle", "idle", "idle", "tick"];
+    let crit = |name| atom(ComponentAtom::new(name, "crit"));
+    let waiting = |name| atom(ComponentAtom::new(name, "waiting"));
+    let any = |f: &dyn Fn(&'static str) -> CTLFormula<ComponentAtom<&'static str>>| {
+        or(f("p1"), or(f("p2"), f("p3")))
+    };
+    let all = |f: &dyn Fn(&'static str) -> CTLFormula<ComponentAtom<&'static str>>| {
+        and(f("p1"), and(f("p2"), f("p3")))
+    };
+    let tock = atom(ComponentAtom::new("clock", "tock"));
+
+    let formulas = vec![
+        ef(all(&crit)),
+        ag(imply(any(&waiting), ef(any(&crit)))),
+        ctl!(EG(Not(Atom(ComponentAtom::new("clock", "tock"))))),
+        ag(ef(and(tock.clone(), all(&waiting)))),
+        CTLFormula::AU(Box::new(not(any(&crit))), Box::new(any(&waiting))),
+    ];
+    for group in [
+        SymmetryGroup::full(4, vec![0, 1, 2]).unwrap(),
+        SymmetryGroup::new(4, vec![vec![1, 2, 0, 3]]).unwrap(),
+    ] {
+        let quotient = system.symmetric(group.clone()).unwrap();
+        for formula in &formulas {
+            assert_eq!(
+                quotient.verify(&start, formula),
+                Ok(verify(&system, &start, formula)),
+                "{formula}"
+            );
+        }
+        // Every state the quotient explores is the canonical state of its class
+        assert!(quotient
+            .reachable(std::slice::from_ref(&start))
+            .iter()
+            .all(|state| group.canonical(state) == *state));
+
+        let path = quotient
+            .witness(&start, &ef(and(tock.clone(), all(&crit))))
+            .unwrap()
+            .unwrap();
+        assert_eq!(path[0], start);
+        assert!(path
+            .windows(2)
+            .all(|pair| system.transitions(&pair[0]).contains(&pair[1])));
+        assert_eq!(
+            quotient.verify(&start, &ef(crit("p1"))),
+            Err(SymmetryError::AsymmetricFormula)
+        );
+    }
+
+    let initial = vec![start.clone()];
+    let quotient = system
+        .symmetric(SymmetryGroup::full(4, vec![0, 1, 2]).unwrap())
+        .unwrap();
+    assert_eq!(reachable(&system, &initial).len(), 54);
+    assert_eq!(quotient.reachable(&initial).len(), 20);
+    assert_eq!(
+        system
+            .symmetric(SymmetryGroup::full(4, vec![0, 3]).unwrap())
+            .err(),
+        Some(SymmetryError::DifferentComponents(
+            "p1".to_string(),
+            "clock".to_string()
+        ))
+    );
+    assert_eq!(
+        SymmetryGroup::new(3, vec![vec![0, 0, 1]]),
+        Err(SymmetryError::InvalidPermutation(vec![0, 0, 1]))
+    );
+}
+
+#[test]
+fn check_transition_systems() {
+    let model = TransitionSystem::new()
+        .with_variable("req", VariableType::Bool, "{FALSE, TRUE}")
+        .with_variable("x", VariableType::Int(0, 2), "0")
+        .with_command("req & x < 2 -> x' = x + 1, req' = {FALSE, TRUE}")
+        .with_command("!req -> req' = TRUE")
+        .with_command("x = 2 -> x' = 0")
+        .with_atom("x >= 1 & req")
+        .explore()
+        .unwrap();
+
+    let initial = model.initial_states();
+    assert_eq!(initial.len(), 2);
+    assert_eq!(initial[1].to_string(), "req=TRUE, x=0");
+    assert_eq!(initial[1].get("x"), Some(&Value::Int(0)));
+    assert_eq!(initial[1].get("y"), None);
+    assert_eq!(model.states().len(), 6);
+    assert_eq!(
+        model
+            .transitions(&initial[1])
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>(),
+        vec!["req=FALSE, x=1", "req=TRUE, x=1"]
+    );
+    assert!(model.state_has(&initial[1], &"req".to_string()));
+    let busy = ctl!(Atom("x >= 1 & req".to_string()));
+    assert!(verify(&model, &initial[0], &ef(busy.clone())));
+    assert!(!verify(&model, &initial[0], &busy));
+
+    let error = TransitionSystem::new()
+        .with_variable("x", VariableType::Int(0, 2), "0")
+        .with_command("TRUE -> x' = x + 1")
+        .explore()
+        .unwrap_err();
+    assert_eq!(error.message, "Value `3` is not in the type of `x`");
+
+    let error = TransitionSystem::new()
+        .with_variable("x", VariableType::Int(0, 2), "0")
+        .with_command("x < 2 -> y' = 1")
+        .explore()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "`y` is not a variable at column 10 of `x < 2 -> y' = 1`"
+    );
+
+    let error = TransitionSystem::new()
+        .with_variable("x", VariableType::Int(0, 2), "0")
+        .with_command("x + 1 -> x' = 1")
+        .explore()
+        .unwrap_err();
+    assert_eq!(error.message, "Guard `x + 1` is not boolean");
+
+    let error = TransitionSystem::new()
+        .with_variable(
+            "mode",
+            VariableType::Enum(vec!["up".to_string(), "down".to_string()]),
+            "up",
+        )
+        .with_command("mode = upp -> mode' = down")
+        .explore()
+        .unwrap_err();
+    assert_eq!(error.message, "`upp` is not a value of `mode`");
+
+    let error = TransitionSystem::new()
+        .with_variable("x", VariableType::Int(0, 2), "0")
+        .with_atom("xx = 2")
+        .explore()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "`xx` is not a variable, a `DEFINE` nor a value of an enumeration at column 1 of `xx = 2`"
+    );
+
+    let error = TransitionSystem::new()
+        .with_variable(
+            "x",
+            VariableType::Int(i64::MAX - 1, i64::MAX),
+            "9223372036854775807",
+        )
+        .with_command("TRUE -> x' = x + 1")
+        .explore()
+        .unwrap_err();
+    assert_eq!(error.message, "Integer overflow in `x + 1`");
+}
+
+#[test]
+fn check_cone_of_influence() {
+    let system = TransitionSystem::new()
+        .with_variable("request", VariableType::Bool, "{FALSE, TRUE}")
+        .with_variable("grant", VariableType::Bool, "FALSE")
+        .with_variable("round", VariableType::Int(0, 3), "0")
+        .with_variable("data", VariableType::Int(0, 7), "0..2")
+        .with_variable("checksum", VariableType::Int(0, 7), "0")
+        .with_command("request & !grant -> grant' = TRUE, data' = (data + 3) mod 8")
+        .with_command("grant -> grant' = FALSE, request' = {FALSE, TRUE}")
+        .with_command("!request & round < 3 -> round' = round + 1, request' = TRUE")
+        .with_command("round = 3 -> round' = 0, checksum' = (checksum + data) mod 8")
+        .with_atom("round > 1")
+        .with_atom("checksum = 0");
+    let atom = |text: &str| ctl!(Atom(text.to_string()));
+
+    let formulas = vec![
+        ag(imply(
+            atom("request"),
+            CTLFormula::AF(Box::new(atom("grant"))),
+        )),
+        ag(ef(atom("round > 1"))),
+        ctl!(EX(EX(Atom("grant".to_string())))),
+        ef(and(atom("grant"), atom("round=3"))),
+        ag(ef(atom("checksum = 0"))),
+        ef(atom("unknown")),
+    ];
+    let model = system.explore().unwrap();
+    for formula in &formulas {
+        let quotient = system.explore_for(formula).unwrap();
+        let expected = model
+            .initial_states()
+            .iter()
+            .all(|s| verify(&model, s, formula));
+        let found = quotient
+            .model
+            .initial_states()
+            .iter()
+            .all(|s| verify(&quotient.model, s, formula));
+        assert_eq!(found, expected, "{formula}");
+        assert!(quotient.classes.len() <= model.states().len());
+    }
+
+    assert_eq!(
+        system.cone_of_influence(&formulas[0]).unwrap(),
+        vec!["request", "grant", "round"]
+    );
+    assert_eq!(
+        system.cone_of_influence(&formulas[4]).unwrap(),
+        vec!["request", "grant", "round", "data", "checksum"]
+    );
+    // The states only assign the variables in the cone, so variables outside it no longer multiply the states
+    let quotient = system.explore_for(&formulas[1]).unwrap();
+    assert!(quotient
+        .classes
+        .iter()
+        .flatten()
+        .all(|s| s.get("round").is_some()
+            && s.get("data").is_none()
+            && s.get("checksum").is_none()));
+    assert!(quotient.classes.len() < system.explore_for(&formulas[4]).unwrap().classes.len());
+    assert!(quotient.classes.len() < model.states().len());
+}
+
+#[test]
+fn check_predicate_atoms() {
+    let start = Counter {
+        value: 0,
+        enabled: false,
+    };
+    let machine = StateMachineModel::new(vec![start.clone()]);
+    let model = PredicateModel::new(&machine)
+        .with_predicate("big", |s: &Counter| s.value > 5)
+        .with_predicate("big", |s: &Counter| s.value >= 3);
+
+    assert!(verify(&model, &start, &ctl!(EF(Atom("big")))));
+    assert!(!verify(&model, &start, &ctl!(EF(Atom("huge")))));
+
+    let odd = Predicate::new("odd", |s: &Counter| s.value % 2 == 1);
+    let enabled = Predicate::new("enabled", |s: &Counter| s.enabled);
+    let formula = ctl!(AG(Imply(Atom(odd.clone()), EF(Not(Atom(odd.clone()))))));
+    assert!(verify(&model, &start, &formula));
+    assert!(!verify(
+        &model,
+        &start,
+        &ctl!(AG(Imply(Atom(odd.clone()), Atom(enabled))))
+    ));
+    assert_eq!(format!("{odd:?}"), "Predicate(\"odd\")");
+    assert_eq!(odd, odd.clone());
+    assert_ne!(odd, Predicate::new("odd", |s: &Counter| s.value % 2 == 1));
+}
+
+#[test]
+fn check_modal_models() {
+    let states = vec![
+        ("s0", vec!["p"], vec!["s1"]),
+        ("s1", vec![], vec!["s0", "s2"]),
+        ("s2", vec!["q"], vec![]),
+    ];
+    let complete = ModalModel::new(states.clone());
+    let model = VecDiscreteModel::new(states);
+    let formulas = vec![
+        ctl!(AG(Imply(Atom("p"), AF(Atom("q"))))),
+        ctl!(EG(Not(Atom("q")))),
+        ctl!(AX(AX(Atom("q")))),
+        ctl!(AU(Not(Atom("q")), Atom("q"))),
+        ef(and(atom("q"), ctl!(AX(False)))),
+    ];
+    for formula in &formulas {
+        assert_eq!(
+            complete.check(&"s0", formula),
+            Truth::from(verify(&model, &"s0", formula)),
+            "{formula}"
+        );
+    }
+
+    let partial = complete
+        .clone()
+        .with_may_transition("s2", "s2")
+        .with_unknown_atom("s1", "q");
+    assert_eq!(partial.check(&"s0", &ctl!(EF(Atom("q")))), Truth::True);
+    assert_eq!(
+        partial.check(&"s0", &ctl!(AX(Not(Atom("q"))))),
+        Truth::Unknown
+    );
+    assert_eq!(
+        partial.refinement(&"s0", &ctl!(AX(Not(Atom("q"))))),
+        Some(Refinement::Atom("s1", "q"))
+    );
+    assert_eq!(partial.check(&"s2", &ctl!(EX(True))), Truth::Unknown);
+    // `s2` either has no transitions or loops, and `q` holds forever in both cases
+    assert_eq!(partial.check(&"s2", &ctl!(EG(Atom("q")))), Truth::True);
+    let escaping = complete.clone().with_may_transition("s2", "s1");
+    assert_eq!(escaping.check(&"s2", &ctl!(EG(Atom("q")))), Truth::Unknown);
+    assert_eq!(
+        escaping.refinement(&"s2", &ctl!(EG(Atom("q")))),
+        Some(Refinement::Transition("s2", "s1"))
+    );
+    assert_eq!(partial.refinement(&"s0", &ctl!(EF(Atom("q")))), None);
+
+    let (holds, refined) = escaping.resolve(&"s2", &ctl!(AF(Not(Atom("q")))), |refinement| {
+        assert_eq!(*refinement, Refinement::Transition("s2", "s1"));
+        true
+    });
+    assert!(holds);
+    assert_eq!(refined.check(&"s2", &ctl!(EX(True))), Truth::True);
+}
+
+#[test]
+fn check_incremental_checker() {
+    let mut model = VecDiscreteModel::new(vec![
+        ("s0", vec!["start"], vec!["s1"]),
+        ("s1", vec![], vec!["s2", "s3"]),
+        ("s2", vec!["done"], vec!["s0"]),
+        ("s3", vec!["fail"], vec![]),
+    ]);
+    let formulas = vec![
+        ctl!(AG(Not(Atom("fail")))),
+        ctl!(AG(Imply(Atom("start"), AF(Atom("done"))))),
+        ctl!(EG(Not(Atom("fail")))),
+        ctl!(AX(EX(Atom("done")))),
+    ];
+    let mut checker = IncrementalChecker::new(&model);
+    for formula in &formulas {
+        checker = checker.with_property("s0", formula.clone());
+    }
+    let results = |model: &VecDiscreteModel<&str, &str>| {
+        formulas
+            .iter()
+            .map(|f| verify(model, &"s0", f))
+            .collect::<Vec<bool>>()
+    };
+    assert_eq!(checker.verdicts(), results(&model));
+    assert_eq!(checker.verdicts(), vec![false, false, true, true]);
+
+    let delta = ModelDelta::new()
+        .with_removed_transition("s1", "s3")
+        .with_added_transition("s3", "s0");
+    assert_eq!(checker.apply(&delta), vec![0, 1]);
+    model = VecDiscreteModel::new(vec![
+        ("s0", vec!["start"], vec!["s1"]),
+        ("s1", vec![], vec!["s2"]),
+        ("s2", vec!["done"], vec!["s0"]),
+        ("s3", vec!["fail"], vec!["s0"]),
+    ]);
+    assert_eq!(checker.verdicts(), results(&model));
+
+    // Removals are applied first, so the label stays
+    let delta = ModelDelta::new()
+        .with_removed_label("s2", "done")
+        .with_added_label("s2", "done");
+    assert!(checker.apply(&delta).is_empty());
+    assert!(checker.apply(&ModelDelta::new()).is_empty());
+
+    let delta = ModelDelta::new()
+        .with_removed_label("s2", "done")
+        .with_added_label("s1", "fail");
+    assert_eq!(checker.apply(&delta), vec![0, 1, 2, 3]);
+    model = VecDiscreteModel::new(vec![
+        ("s0", vec!["start"], vec!["s1"]),
+        ("s1", vec!["fail"], vec!["s2"]),
+        ("s2", vec![], vec!["s0"]),
+        ("s3", vec!["fail"], vec!["s0"]),
+    ]);
+    assert_eq!(checker.verdicts(), results(&model));
+
+    // Only `s3` can reach the edited state, so the fixpoints keep their sets in the other states
+    let delta = ModelDelta::new()
+        .with_removed_transition("s3", "s0")
+        .with_added_transition("s3", "s3");
+    assert!(checker.apply(&delta).is_empty());
+    model = VecDiscreteModel::new(vec![
+        ("s0", vec!["start"], vec!["s1"]),
+        ("s1", vec!["fail"], vec!["s2"]),
+        ("s2", vec![], vec!["s0"]),
+        ("s3", vec!["fail"], vec!["s3"]),
+    ]);
+    assert_eq!(checker.verdicts(), results(&model));
+
+    // `s3` cannot reach the edited states, so the fixpoints are taken from its sets when they are recomputed
+    let delta = ModelDelta::new()
+        .with_removed_label("s1", "fail")
+        .with_added_transition("s2", "s3");
+    assert_eq!(checker.apply(&delta), vec![2]);
+    model = VecDiscreteModel::new(vec![
+        ("s0", vec!["start"], vec!["s1"]),
+        ("s1", vec![], vec!["s2"]),
+        ("s2", vec![], vec!["s0", "s3"]),
+        ("s3", vec!["fail"], vec!["s3"]),
+    ]);
+    assert_eq!(checker.verdicts(), results(&model));
+    assert_eq!(checker.verdicts(), vec![false, false, true, false]);
+}
@@ -1,9 +1,12 @@
 use std::fmt;
 
 /// Computional Tree Logic formula (See [Wikipedia](https://en.wikipedia.org/wiki/Computation_tree_logic))
-/// 
-/// `CTLFormula` represents a Computional Tree Logic formula that is used to verify models. 
+///
+/// `CTLFormula` represents a Computional Tree Logic formula that is used to verify models.
 /// It will almost always be easier to generate `CTLFormula`s using the [`ctl`](crate::ctl) macro.
+///
+/// With the `serde` feature enabled, formulas can be serialized, see `FORMULA_SCHEMA_VERSION` for their layout.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum CTLFormula<T: PartialEq> {
     True,
     False,
@@ -74,3 +77,70 @@ impl<T: fmt::Display + PartialEq> fmt::Display for CTLFormula<T> {
         }
     }
 }
+
+impl<T: PartialEq> CTLFormula<T> {
+    /// Returns the distinct atoms of the formula in the order they first appear
+    pub(crate) fn atoms(&self) -> Vec<&T> {
+        let mut atoms = vec![];
+        let mut stack = vec![self];
+        while let Some(formula) = stack.pop() {
+            if let Self::Atom(atom) = formula {
+                if !atoms.contains(&atom) {
+                    atoms.push(atom);
+                }
+            }
+            stack.extend(formula.children().into_iter().rev());
+        }
+        atoms
+    }
+
+    /// Returns the immediate subformulas of the formula from left to right
+    pub(crate) fn children(&self) -> Vec<&CTLFormula<T>> {
+        match self {
+            Self::True | Self::False | Self::Atom(_) => vec![],
+            Self::Not(f)
+            | Self::AG(f)
+            | Self::AF(f)
+            | Self::AX(f)
+            | Self::EG(f)
+            | Self::EF(f)
+            | Self::EX(f) => {
+                vec![f]
+            }
+            Self::And(f, g)
+            | Self::Or(f, g)
+            | Self::Imply(f, g)
+            | Self::AU(f, g)
+            | Self::EU(f, g) => {
+                vec![f, g]
+            }
+        }
+    }
+
+    /// Returns a copy of the formula with the same operator but with its immediate subformulas replaced by `children`
+    pub(crate) fn with_children(&self, children: Vec<CTLFormula<T>>) -> CTLFormula<T>
+    where
+        T: Clone,
+    {
+        let mut children = children.into_iter().map(Box::new);
+        let mut next = || children.next().expect("Wrong number of subformulas!");
+
+        match self {
+            Self::True => Self::True,
+            Self::False => Self::False,
+            Self::Atom(atom) => Self::Atom(atom.clone()),
+            Self::Not(_) => Self::Not(next()),
+            Self::AG(_) => Self::AG(next()),
+            Self::AF(_) => Self::AF(next()),
+            Self::AX(_) => Self::AX(next()),
+            Self::EG(_) => Self::EG(next()),
+            Self::EF(_) => Self::EF(next()),
+            Self::EX(_) => Self::EX(next()),
+            Self::And(_, _) => Self::And(next(), next()),
+            Self::Or(_, _) => Self::Or(next(), next()),
+            Self::Imply(_, _) => Self::Imply(next(), next()),
+            Self::AU(_, _) => Self::AU(next(), next()),
+            Self::EU(_, _) => Self::EU(next(), next()),
+        }
+    }
+}
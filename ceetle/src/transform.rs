@@ -0,0 +1,278 @@
+use super::CTLFormula;
+
+// ================== TRANSFORMATIONS ==================
+// All transformations below preserve the semantics of the formula on models
+// where every state has at least one transition (the usual CTL assumption).
+
+impl<T: PartialEq + Clone> CTLFormula<T> {
+    /// # `to_nnf`
+    /// Returns the formula in negation normal form, where `Not` is only ever applied directly to an `Atom` and `Imply` is eliminated.
+    ///
+    /// Negations are pushed inwards using the De Morgan laws and the dualities of the temporal operators:
+    /// - `¬AG(f) ≡ EF(¬f)`, `¬AF(f) ≡ EG(¬f)`, `¬AX(f) ≡ EX(¬f)` and vice versa
+    /// - `¬A[f U g] ≡ E[¬g U (¬f∧¬g)] ∨ EG(¬g)` and vice versa
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::{ctl, CTLFormula};
+    ///
+    /// let f = ctl!(Not(AG(Imply(Atom("p"), Atom("q")))));
+    /// assert_eq!(f.to_nnf(), ctl!(EF(And(Atom("p"), Not(Atom("q"))))));
+    /// ```
+    pub fn to_nnf(self) -> Self {
+        nnf(self, false)
+    }
+
+    /// # `to_enf`
+    /// Returns the formula in existential normal form, where the only temporal operators are `EX`, `EU` and `EG`
+    /// and the only boolean connectives are `Not` and `And`.
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::{ctl, CTLFormula};
+    ///
+    /// let f = ctl!(AG(Atom("p")));
+    /// assert_eq!(f.to_enf(), ctl!(Not(EU(True, Not(Atom("p"))))));
+    /// ```
+    pub fn to_enf(self) -> Self {
+        enf(self)
+    }
+
+    /// # `simplify`
+    /// Returns an equivalent formula where constants are folded, double negations are removed
+    /// and idempotent operators are collapsed, e.g. `¬¬f ≡ f`, `f∧⊤ ≡ f`, `f∨f ≡ f`, `⊥→f ≡ ⊤` and `AG(AG(f)) ≡ AG(f)`.
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::{ctl, CTLFormula};
+    ///
+    /// let f = ctl!(And(True, Not(Not(AF(AF(Atom("p")))))));
+    /// assert_eq!(f.simplify(), ctl!(AF(Atom("p"))));
+    /// ```
+    pub fn simplify(self) -> Self {
+        simplify(self)
+    }
+
+    /// # `expand_derived`
+    /// Returns the formula rewritten without the derived operators `Imply`, `AG`, `AF`, `EG` and `EF`.
+    /// The result only uses the boolean connectives `Not`, `And` and `Or` together with `AX`, `EX`, `AU` and `EU`, where
+    /// - `f→g ≡ ¬f∨g`
+    /// - `EF(f) ≡ E[⊤ U f]` and `AF(f) ≡ A[⊤ U f]`
+    /// - `AG(f) ≡ ¬E[⊤ U ¬f]` and `EG(f) ≡ ¬A[⊤ U ¬f]`
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::{ctl, CTLFormula};
+    ///
+    /// let f = ctl!(Imply(Atom("p"), EF(Atom("q"))));
+    /// assert_eq!(f.expand_derived(), ctl!(Or(Not(Atom("p")), EU(True, Atom("q")))));
+    /// ```
+    pub fn expand_derived(self) -> Self {
+        expand_derived(self)
+    }
+}
+
+/// Negates a formula, removing a double negation instead of adding one
+fn negate<T: PartialEq>(formula: CTLFormula<T>) -> CTLFormula<T> {
+    match formula {
+        CTLFormula::Not(inner) => *inner,
+        formula => CTLFormula::Not(Box::new(formula)),
+    }
+}
+
+fn nnf<T: PartialEq + Clone>(formula: CTLFormula<T>, negated: bool) -> CTLFormula<T> {
+    use CTLFormula::*;
+
+    let pos = |f: Box<CTLFormula<T>>| Box::new(nnf(*f, false));
+    let neg = |f: Box<CTLFormula<T>>| Box::new(nnf(*f, true));
+
+    if !negated {
+        return match formula {
+            True => True,
+            False => False,
+            Atom(atom) => Atom(atom),
+            Not(f) => nnf(*f, true),
+            And(f, g) => And(pos(f), pos(g)),
+            Or(f, g) => Or(pos(f), pos(g)),
+            Imply(f, g) => Or(neg(f), pos(g)),
+            AG(f) => AG(pos(f)),
+            AF(f) => AF(pos(f)),
+            AX(f) => AX(pos(f)),
+            AU(f, g) => AU(pos(f), pos(g)),
+            EG(f) => EG(pos(f)),
+            EF(f) => EF(pos(f)),
+            EX(f) => EX(pos(f)),
+            EU(f, g) => EU(pos(f), pos(g)),
+        };
+    }
+
+    match formula {
+        True => False,
+        False => True,
+        Atom(atom) => Not(Box::new(Atom(atom))),
+        Not(f) => nnf(*f, false),
+        And(f, g) => Or(neg(f), neg(g)),
+        Or(f, g) => And(neg(f), neg(g)),
+        Imply(f, g) => And(pos(f), neg(g)),
+        AG(f) => EF(neg(f)),
+        AF(f) => EG(neg(f)),
+        AX(f) => EX(neg(f)),
+        EG(f) => AF(neg(f)),
+        EF(f) => AG(neg(f)),
+        EX(f) => AX(neg(f)),
+        AU(f, g) => {
+            let not_g = neg(g);
+            Or(
+                Box::new(EU(not_g.clone(), Box::new(And(neg(f), not_g.clone())))),
+                Box::new(EG(not_g)),
+            )
+        }
+        EU(f, g) => {
+            let not_g = neg(g);
+            Or(
+                Box::new(AU(not_g.clone(), Box::new(And(neg(f), not_g.clone())))),
+                Box::new(AG(not_g)),
+            )
+        }
+    }
+}
+
+fn enf<T: PartialEq + Clone>(formula: CTLFormula<T>) -> CTLFormula<T> {
+    use CTLFormula::*;
+
+    let not = |f: CTLFormula<T>| Box::new(negate(f));
+
+    match formula {
+        True => True,
+        False => negate(True),
+        Atom(atom) => Atom(atom),
+        Not(f) => negate(enf(*f)),
+        And(f, g) => And(Box::new(enf(*f)), Box::new(enf(*g))),
+        Or(f, g) => negate(And(not(enf(*f)), not(enf(*g)))),
+        Imply(f, g) => negate(And(Box::new(enf(*f)), not(enf(*g)))),
+        AG(f) => negate(EU(Box::new(True), not(enf(*f)))),
+        AF(f) => negate(EG(not(enf(*f)))),
+        AX(f) => negate(EX(not(enf(*f)))),
+        AU(f, g) => {
+            let not_g = negate(enf(*g));
+            let not_f = negate(enf(*f));
+            And(
+                not(EU(
+                    Box::new(not_g.clone()),
+                    Box::new(And(Box::new(not_f), Box::new(not_g.clone()))),
+                )),
+                not(EG(Box::new(not_g))),
+            )
+        }
+        EG(f) => EG(Box::new(enf(*f))),
+        EF(f) => EU(Box::new(True), Box::new(enf(*f))),
+        EX(f) => EX(Box::new(enf(*f))),
+        EU(f, g) => EU(Box::new(enf(*f)), Box::new(enf(*g))),
+    }
+}
+
+fn simplify<T: PartialEq + Clone>(formula: CTLFormula<T>) -> CTLFormula<T> {
+    use CTLFormula::*;
+
+    match formula {
+        True => True,
+        False => False,
+        Atom(atom) => Atom(atom),
+        Not(f) => match simplify(*f) {
+            True => False,
+            False => True,
+            f => negate(f),
+        },
+        And(f, g) => match (simplify(*f), simplify(*g)) {
+            (False, _) | (_, False) => False,
+            (True, f) | (f, True) => f,
+            (f, g) if f == g => f,
+            (f, g) if is_negation_of(&f, &g) => False,
+            (f, g) => And(Box::new(f), Box::new(g)),
+        },
+        Or(f, g) => match (simplify(*f), simplify(*g)) {
+            (True, _) | (_, True) => True,
+            (False, f) | (f, False) => f,
+            (f, g) if f == g => f,
+            (f, g) if is_negation_of(&f, &g) => True,
+            (f, g) => Or(Box::new(f), Box::new(g)),
+        },
+        Imply(f, g) => match (simplify(*f), simplify(*g)) {
+            (False, _) | (_, True) => True,
+            (True, g) => g,
+            (f, False) => negate(f),
+            (f, g) if f == g => True,
+            (f, g) => Imply(Box::new(f), Box::new(g)),
+        },
+        // Constants are only folded where the result does not depend on
+        // whether the state has any transitions
+        AG(f) => match simplify(*f) {
+            f @ (True | False) => f,
+            AG(f) => AG(f),
+            f => AG(Box::new(f)),
+        },
+        AF(f) => match simplify(*f) {
+            True => True,
+            AF(f) => AF(f),
+            f => AF(Box::new(f)),
+        },
+        AX(f) => match simplify(*f) {
+            True => True,
+            f => AX(Box::new(f)),
+        },
+        EG(f) => match simplify(*f) {
+            False => False,
+            EG(f) => EG(f),
+            f => EG(Box::new(f)),
+        },
+        EF(f) => match simplify(*f) {
+            f @ (True | False) => f,
+            EF(f) => EF(f),
+            f => EF(Box::new(f)),
+        },
+        EX(f) => match simplify(*f) {
+            False => False,
+            f => EX(Box::new(f)),
+        },
+        AU(f, g) => match (simplify(*f), simplify(*g)) {
+            (_, True) => True,
+            (False, g) => g,
+            (f, g) => AU(Box::new(f), Box::new(g)),
+        },
+        EU(f, g) => match (simplify(*f), simplify(*g)) {
+            (_, g @ (True | False)) => g,
+            (False, g) => g,
+            (f, g) => EU(Box::new(f), Box::new(g)),
+        },
+    }
+}
+
+/// Returns true if one of the formulas is syntactically the negation of the other
+fn is_negation_of<T: PartialEq>(f: &CTLFormula<T>, g: &CTLFormula<T>) -> bool {
+    matches!(f, CTLFormula::Not(inner) if **inner == *g)
+        || matches!(g, CTLFormula::Not(inner) if **inner == *f)
+}
+
+fn expand_derived<T: PartialEq + Clone>(formula: CTLFormula<T>) -> CTLFormula<T> {
+    use CTLFormula::*;
+
+    let expand = |f: Box<CTLFormula<T>>| Box::new(expand_derived(*f));
+
+    match formula {
+        True => True,
+        False => False,
+        Atom(atom) => Atom(atom),
+        Not(f) => Not(expand(f)),
+        And(f, g) => And(expand(f), expand(g)),
+        Or(f, g) => Or(expand(f), expand(g)),
+        Imply(f, g) => Or(Box::new(negate(expand_derived(*f))), expand(g)),
+        AG(f) => negate(EU(Box::new(True), Box::new(negate(expand_derived(*f))))),
+        AF(f) => AU(Box::new(True), expand(f)),
+        AX(f) => AX(expand(f)),
+        AU(f, g) => AU(expand(f), expand(g)),
+        EG(f) => negate(AU(Box::new(True), Box::new(negate(expand_derived(*f))))),
+        EF(f) => EU(Box::new(True), expand(f)),
+        EX(f) => EX(expand(f)),
+        EU(f, g) => EU(expand(f), expand(g)),
+    }
+}
@@ -0,0 +1,650 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    fmt,
+};
+
+use super::{
+    bisimulation_quotient,
+    smv::{tokenize_smv, Expr, Program, SmvError, SmvParser, Token, Type},
+    CTLFormula, EnumerableModel, HashedDiscreteModel, Model, Quotient, Value,
+};
+
+/// The type of a variable of a `TransitionSystem`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VariableType {
+    /// `TRUE` or `FALSE`
+    Bool,
+    /// An integer between the two bounds, both included
+    Int(i64, i64),
+    /// One of the given symbols
+    Enum(Vec<String>),
+}
+
+/// Error returned by [`TransitionSystem::explore`] when a variable, command or atom is invalid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransitionSystemError {
+    /// The declaration the error was found in, e.g. the text of a command
+    pub text: String,
+    /// Character column in `text` where the error was found, starting at 1
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TransitionSystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at column {} of `{}`",
+            self.message, self.column, self.text
+        )
+    }
+}
+
+impl Error for TransitionSystemError {}
+
+/// # `Valuation`
+/// A state of a `TransitionSystem`, giving the value of every variable in the order they were declared.
+/// It is displayed as the assignments of the variables, e.g. `x=1, ready=TRUE`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Valuation {
+    values: Vec<(String, Value)>,
+}
+
+impl Valuation {
+    /// # `get`
+    /// Returns the value of the variable named `variable`, or `None` if there is no such variable
+    pub fn get(&self, variable: &str) -> Option<&Value> {
+        self.values
+            .iter()
+            .find(|(name, _)| name == variable)
+            .map(|(_, value)| value)
+    }
+}
+
+impl fmt::Display for Valuation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let assignments: Vec<String> = self
+            .values
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+        write!(f, "{}", assignments.join(", "))
+    }
+}
+
+/// # `TransitionSystem`
+/// A system described by typed variables and guarded commands, which is explored to a model with [`explore`](TransitionSystem::explore).
+///
+/// - A variable has a type and an initial value, which may be a set such as `{0, 1}` or a range such as `0..3` to start with any of its values
+/// - A command `guard -> x' = e, y' = f` can be taken in every state where `guard` holds, and gives `x` the value of `e` and `y` the value of `f`
+///   in the next state. The other variables keep their values. Like initial values, `e` may be a set for a nondeterministic choice.
+///   The guard cannot use `->` outside parentheses, since the first `->` ends it
+/// - An atom is a boolean expression such as `x > 3`, which labels the states where it holds
+///
+/// Expressions use the syntax of [`parse_smv`](crate::parse_smv): the boolean operators `!`, `&`, `|`, `xor`, `->` and `<->`,
+/// the comparisons `=`, `!=`, `<`, `<=`, `>` and `>=`, the arithmetic operators `+`, `-`, `*`, `/` and `mod`, and the constants `TRUE` and `FALSE`.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ctl, verify, CTLFormula, EnumerableModel, TransitionSystem, VariableType};
+///
+/// let model = TransitionSystem::new()
+///     .with_variable("x", VariableType::Int(0, 5), "0")
+///     .with_variable("mode", VariableType::Enum(vec!["up".into(), "down".into()]), "up")
+///     .with_command("mode = up & x < 5 -> x' = x + 1")
+///     .with_command("mode = up & x = 5 -> mode' = down")
+///     .with_command("mode = down & x > 0 -> x' = x - 1")
+///     .with_command("mode = down & x = 0 -> mode' = up")
+///     .with_atom("x > 3")
+///     .explore()
+///     .unwrap();
+///
+/// let initial = &model.initial_states()[0];
+/// assert_eq!(initial.to_string(), "x=0, mode=up");
+/// assert!(verify(&model, initial, &ctl!(AG(AF(Atom("x > 3".to_string()))))));
+/// assert!(verify(&model, initial, &ctl!(AG(Not(Atom("x=6".to_string()))))));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TransitionSystem {
+    variables: Vec<(String, VariableType, String)>,
+    commands: Vec<String>,
+    atoms: Vec<String>,
+}
+
+/// The parsed declarations of a `TransitionSystem`
+struct Declarations<'a> {
+    program: Program,
+    variables: &'a [(String, VariableType, String)],
+    initial_values: Vec<Expr>,
+    commands: Vec<Command>,
+    atoms: Vec<(&'a String, Expr)>,
+}
+
+struct Command {
+    text: String,
+    guard: Expr,
+    updates: Vec<(usize, Expr)>,
+}
+
+impl TransitionSystem {
+    /// # `new`
+    /// Returns a system without variables, commands nor atoms
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # `with_variable`
+    /// Adds a variable with the given type and initial value
+    pub fn with_variable(mut self, name: &str, variable_type: VariableType, initial: &str) -> Self {
+        self.variables
+            .push((name.to_string(), variable_type, initial.to_string()));
+        self
+    }
+
+    /// # `with_command`
+    /// Adds a guarded command such as `x < 5 -> x' = x + 1`
+    pub fn with_command(mut self, command: &str) -> Self {
+        self.commands.push(command.to_string());
+        self
+    }
+
+    /// # `with_atom`
+    /// Adds a boolean expression such as `x > 3` that labels the states where it holds. The label is the expression exactly as given
+    pub fn with_atom(mut self, atom: &str) -> Self {
+        self.atoms.push(atom.to_string());
+        self
+    }
+
+    /// # `explore`
+    /// Explores the states reachable from the initial states and returns them as a model, with the initial valuations as its initial states.
+    ///
+    /// A state is labelled with
+    /// - `x=v` for every variable `x` with value `v`
+    /// - the name of every boolean variable that is true in the state
+    /// - every atom that holds in the state
+    ///
+    /// Returns an error if a declaration cannot be parsed, if an expression has the wrong type
+    /// or if a command gives a variable a value outside of its type.
+    pub fn explore(&self) -> Result<HashedDiscreteModel<Valuation, String>, TransitionSystemError> {
+        let declarations = self.parse()?;
+        let keep = vec![true; self.variables.len()];
+        declarations.explore(&keep)
+    }
+
+    /// # `cone_of_influence`
+    /// Returns the variables that can change whether `formula` holds, in the order they were declared.
+    ///
+    /// These are the variables of the atoms of `formula`, those read by the guard of any command, and all the variables that the updates
+    /// of these variables read, recursively. The guards of all commands are kept, since whether a command can be taken changes
+    /// the transitions even if it only updates other variables. An atom of `formula` is either an atom of the system, a boolean variable
+    /// or an assignment `x=v` as in the labels given by [`explore`](TransitionSystem::explore), and other atoms do not depend on any variable.
+    ///
+    /// Returns an error if a declaration cannot be parsed.
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::{ctl, CTLFormula, TransitionSystem, VariableType};
+    ///
+    /// let system = TransitionSystem::new()
+    ///     .with_variable("busy", VariableType::Bool, "FALSE")
+    ///     .with_variable("jobs", VariableType::Int(0, 3), "0")
+    ///     .with_variable("log", VariableType::Int(0, 9), "0")
+    ///     .with_command("!busy -> busy' = TRUE, jobs' = {0, 1, 2, 3}")
+    ///     .with_command("busy -> busy' = FALSE, log' = (log + jobs) mod 10");
+    ///
+    /// let formula = ctl!(AG(AF(Atom("busy".to_string()))));
+    /// assert_eq!(system.cone_of_influence(&formula).unwrap(), vec!["busy"]);
+    /// let formula = ctl!(EF(Atom("log=5".to_string())));
+    /// assert_eq!(system.cone_of_influence(&formula).unwrap(), vec!["busy", "jobs", "log"]);
+    /// ```
+    pub fn cone_of_influence(
+        &self,
+        formula: &CTLFormula<String>,
+    ) -> Result<Vec<String>, TransitionSystemError> {
+        let declarations = self.parse()?;
+        let cone = declarations.cone(formula);
+        Ok(self
+            .variables
+            .iter()
+            .zip(cone)
+            .filter(|(_, keep)| *keep)
+            .map(|((name, _, _), _)| name.clone())
+            .collect())
+    }
+
+    /// # `explore_for`
+    /// Explores a reduced model of the system that is only meant for verifying `formula`, and returns it as a quotient.
+    ///
+    /// The states only give values to the variables of the [`cone_of_influence`](TransitionSystem::cone_of_influence) of `formula`,
+    /// and are only labelled with the atoms of `formula`. Then the states that are bisimilar are merged, like by [`bisimulation_quotient`].
+    /// A state of the system and its part in the cone satisfy the same formulas over these atoms, so for every such formula `f`
+    /// verifying `f` in an initial state of the quotient gives the same result as in the initial states of the model given by `explore`
+    /// that it stands for.
+    ///
+    /// The updates of the variables outside the cone are not evaluated, so errors in them are not reported.
+    ///
+    /// ## Examples
+    /// ```
+    /// use ceetle::{ctl, verify, CTLFormula, EnumerableModel, TransitionSystem, VariableType};
+    ///
+    /// let system = TransitionSystem::new()
+    ///     .with_variable("busy", VariableType::Bool, "FALSE")
+    ///     .with_variable("jobs", VariableType::Int(0, 3), "0")
+    ///     .with_variable("log", VariableType::Int(0, 9), "0")
+    ///     .with_command("!busy -> busy' = TRUE, jobs' = {0, 1, 2, 3}")
+    ///     .with_command("busy -> busy' = FALSE, log' = (log + jobs) mod 10");
+    /// let formula = ctl!(AG(Imply(Atom("busy".to_string()), AF(Not(Atom("busy".to_string()))))));
+    ///
+    /// let quotient = system.explore_for(&formula).unwrap();
+    /// assert_eq!(quotient.classes.len(), 2);
+    /// let initial = &quotient.model.initial_states()[0];
+    /// assert!(verify(&quotient.model, initial, &formula));
+    ///
+    /// let model = system.explore().unwrap();
+    /// assert_eq!(model.states().len(), 80);
+    /// ```
+    pub fn explore_for(
+        &self,
+        formula: &CTLFormula<String>,
+    ) -> Result<Quotient<Valuation, String>, TransitionSystemError> {
+        let mut declarations = self.parse()?;
+        let keep = declarations.cone(formula);
+        let atoms = formula.atoms();
+        declarations.atoms.retain(|(text, _)| atoms.contains(text));
+        let model = declarations.explore(&keep)?;
+
+        let states = model
+            .states()
+            .into_iter()
+            .map(|state| {
+                let labels = model
+                    .labels(state)
+                    .iter()
+                    .filter(|label| atoms.contains(label))
+                    .cloned()
+                    .collect();
+                (state.clone(), (labels, model.transitions(state).clone()))
+            })
+            .collect();
+        let projected =
+            HashedDiscreteModel::new(states).with_initial_states(model.initial_states().clone());
+        Ok(bisimulation_quotient(&projected))
+    }
+
+    fn parse(&self) -> Result<Declarations<'_>, TransitionSystemError> {
+        let mut program = Program::default();
+        for (name, variable_type, _) in self.variables.iter() {
+            let fail = |message: String| {
+                Err(TransitionSystemError {
+                    text: name.clone(),
+                    column: 1,
+                    message,
+                })
+            };
+            if program.variable(name).is_some() {
+                return fail(format!("Variable `{name}` is declared twice"));
+            }
+            let variable_type = match variable_type {
+                VariableType::Bool => Type::Boolean,
+                VariableType::Int(low, high) if low > high => {
+                    return fail(format!("The range {low}..{high} of `{name}` is empty"))
+                }
+                VariableType::Int(low, high) => Type::Range(*low, *high),
+                VariableType::Enum(symbols) if symbols.is_empty() => {
+                    return fail(format!("The enumeration of `{name}` is empty"))
+                }
+                VariableType::Enum(symbols) => {
+                    Type::Enumeration(symbols.iter().cloned().map(Value::Symbol).collect())
+                }
+            };
+            program.variables.push((name.clone(), variable_type));
+        }
+
+        let initial_values = self
+            .variables
+            .iter()
+            .map(|(_, _, initial)| {
+                let mut parser = parser(initial)?;
+                let expr = parser.expression().map_err(|e| error(initial, e))?;
+                end(&parser).map_err(|e| error(initial, e))?;
+                program
+                    .check_names(&expr, (1, 1))
+                    .map_err(|e| error(initial, e))?;
+                Ok(expr)
+            })
+            .collect::<Result<Vec<Expr>, TransitionSystemError>>()?;
+        let commands = self
+            .commands
+            .iter()
+            .map(|command| parse_command(&program, command))
+            .collect::<Result<Vec<Command>, TransitionSystemError>>()?;
+        let atoms = self
+            .atoms
+            .iter()
+            .map(|atom| {
+                let mut parser = parser(atom)?;
+                let expr = parser.expression().map_err(|e| error(atom, e))?;
+                end(&parser).map_err(|e| error(atom, e))?;
+                if expr.is_temporal() {
+                    return Err(TransitionSystemError {
+                        text: atom.clone(),
+                        column: 1,
+                        message: "Temporal operators are not allowed in atoms".to_string(),
+                    });
+                }
+                program
+                    .check_names(&expr, (1, 1))
+                    .map_err(|e| error(atom, e))?;
+                Ok((atom, expr))
+            })
+            .collect::<Result<Vec<(&String, Expr)>, TransitionSystemError>>()?;
+
+        Ok(Declarations {
+            program,
+            variables: &self.variables,
+            initial_values,
+            commands,
+            atoms,
+        })
+    }
+}
+
+impl Declarations<'_> {
+    /// The variables in the cone of influence of `formula`
+    fn cone(&self, formula: &CTLFormula<String>) -> Vec<bool> {
+        let program = &self.program;
+        let mut cone = vec![false; program.variables.len()];
+        for atom in formula.atoms() {
+            let name = atom.split_once('=').map_or(atom.as_str(), |(name, _)| name);
+            match self.atoms.iter().find(|(text, _)| *text == atom) {
+                Some((_, expr)) => read(program, expr, &mut cone),
+                None => {
+                    if let Some(i) = program.variable(name) {
+                        cone[i] = true;
+                    }
+                }
+            }
+        }
+        for command in self.commands.iter() {
+            read(program, &command.guard, &mut cone);
+        }
+
+        loop {
+            let before = cone.clone();
+            for command in self.commands.iter() {
+                for (variable, expr) in command.updates.iter() {
+                    if cone[*variable] {
+                        read(program, expr, &mut cone);
+                    }
+                }
+            }
+            if cone == before {
+                return cone;
+            }
+        }
+    }
+
+    /// Explores the values of the variables in `keep` reachable from the initial states, ignoring the other variables
+    fn explore(
+        &self,
+        keep: &[bool],
+    ) -> Result<HashedDiscreteModel<Valuation, String>, TransitionSystemError> {
+        let program = &self.program;
+        let kept: Vec<usize> = (0..keep.len()).filter(|&i| keep[i]).collect();
+
+        // The initial values are evaluated without any variable assigned, so they must be constant
+        let unassigned = vec![None; program.variables.len()];
+        let mut choices = vec![];
+        for &i in kept.iter() {
+            let (name, _, text) = &self.variables[i];
+            let expr = &self.initial_values[i];
+            match program
+                .candidates(i, Some(&((1, 1), expr.clone())), &unassigned)
+                .map_err(|e| error(text, e))?
+            {
+                Some(values) => choices.push(values),
+                None => {
+                    return Err(TransitionSystemError {
+                        text: text.clone(),
+                        column: 1,
+                        message: format!("The initial value of `{name}` must not use variables"),
+                    })
+                }
+            }
+        }
+        let initial = product(&choices);
+
+        let valuation = |values: &Vec<Value>| Valuation {
+            values: kept
+                .iter()
+                .map(|&i| program.variables[i].0.clone())
+                .zip(values.iter().cloned())
+                .collect(),
+        };
+
+        let mut seen: HashSet<Vec<Value>> = initial.iter().cloned().collect();
+        let mut queue: VecDeque<Vec<Value>> = initial.iter().cloned().collect();
+        let mut states = HashMap::new();
+
+        while let Some(state) = queue.pop_front() {
+            let mut current: Vec<Option<Value>> = vec![None; program.variables.len()];
+            for (&i, value) in kept.iter().zip(state.iter()) {
+                current[i] = Some(value.clone());
+            }
+
+            let mut labels = vec![];
+            for (&i, value) in kept.iter().zip(state.iter()) {
+                let name = &program.variables[i].0;
+                labels.push(format!("{name}={value}"));
+                if *value == Value::Bool(true) {
+                    labels.push(name.clone());
+                }
+            }
+            for (text, expr) in self.atoms.iter() {
+                match program
+                    .evaluate(expr, &current, (1, 1))
+                    .map_err(|e| error(text, e))?
+                {
+                    Some(Value::Bool(true)) => labels.push((*text).clone()),
+                    Some(Value::Bool(false)) => {}
+                    _ => {
+                        return Err(TransitionSystemError {
+                            text: (*text).clone(),
+                            column: 1,
+                            message: format!("Atom `{expr}` is not boolean"),
+                        })
+                    }
+                }
+            }
+
+            let mut nexts = vec![];
+            for command in self.commands.iter() {
+                for next in command.successors(program, &current, keep)? {
+                    if !nexts.contains(&next) {
+                        nexts.push(next);
+                    }
+                }
+            }
+            for next in nexts.iter() {
+                if seen.insert(next.clone()) {
+                    queue.push_back(next.clone());
+                }
+            }
+
+            states.insert(
+                valuation(&state),
+                (labels, nexts.iter().map(valuation).collect()),
+            );
+        }
+
+        let initial = initial.iter().map(valuation).collect();
+        Ok(HashedDiscreteModel::new(states).with_initial_states(initial))
+    }
+}
+
+impl Command {
+    /// The values of the variables in `keep` after taking the command in `current`, none if its guard does not hold
+    fn successors(
+        &self,
+        program: &Program,
+        current: &Vec<Option<Value>>,
+        keep: &[bool],
+    ) -> Result<Vec<Vec<Value>>, TransitionSystemError> {
+        let fail = |message: String| {
+            Err(TransitionSystemError {
+                text: self.text.clone(),
+                column: 1,
+                message,
+            })
+        };
+
+        match program
+            .evaluate(&self.guard, current, (1, 1))
+            .map_err(|e| error(&self.text, e))?
+        {
+            Some(Value::Bool(true)) => {}
+            Some(Value::Bool(false)) => return Ok(vec![]),
+            _ => return fail(format!("Guard `{}` is not boolean", self.guard)),
+        }
+
+        let mut choices = vec![];
+        for (i, value) in current.iter().enumerate() {
+            if !keep[i] {
+                continue;
+            }
+            match self.updates.iter().find(|(variable, _)| *variable == i) {
+                Some((_, expr)) => {
+                    let values = program
+                        .candidates(i, Some(&((1, 1), expr.clone())), current)
+                        .map_err(|e| error(&self.text, e))?;
+                    choices.push(values.unwrap_or_default());
+                }
+                None => choices.push(vec![value.clone().unwrap()]),
+            }
+        }
+        Ok(product(&choices))
+    }
+}
+
+/// Marks the variables that `expr` reads
+fn read(program: &Program, expr: &Expr, variables: &mut [bool]) {
+    match expr {
+        Expr::Name(name) => {
+            if let Some(i) = program.variable(name) {
+                variables[i] = true;
+            }
+        }
+        Expr::Set(elements) => elements.iter().for_each(|e| read(program, e, variables)),
+        Expr::Not(e) | Expr::Negate(e) | Expr::Temporal(_, e) => read(program, e, variables),
+        Expr::Binary(_, a, b) | Expr::Until(_, a, b) => {
+            read(program, a, variables);
+            read(program, b, variables);
+        }
+        Expr::Case(branches) => {
+            for (condition, value) in branches {
+                read(program, condition, variables);
+                read(program, value, variables);
+            }
+        }
+        Expr::Bool(_) | Expr::Int(_) | Expr::Range(..) => {}
+    }
+}
+
+fn parser(text: &str) -> Result<SmvParser, TransitionSystemError> {
+    Ok(SmvParser {
+        tokens: tokenize_smv(text).map_err(|e| error(text, e))?,
+        current: 0,
+        program: Program::default(),
+        in_property: false,
+    })
+}
+
+fn error(text: &str, e: SmvError) -> TransitionSystemError {
+    TransitionSystemError {
+        text: text.to_string(),
+        column: e.column,
+        message: e.message,
+    }
+}
+
+fn end(parser: &SmvParser) -> Result<(), SmvError> {
+    match parser.peek() {
+        Token::End => Ok(()),
+        token => parser.error(format!("Expected end of input but found {token}")),
+    }
+}
+
+fn parse_command(program: &Program, text: &str) -> Result<Command, TransitionSystemError> {
+    let mut parser = parser(text)?;
+    let mut parse = || -> Result<Command, SmvError> {
+        // The guard is parsed without implications, since `->` separates it from the updates
+        let guard = parser.binary(0)?;
+        parser.expect_symbol("->")?;
+
+        let mut updates: Vec<(usize, Expr)> = vec![];
+        loop {
+            let name = parser.identifier()?;
+            let Some(variable) = program.variable(&name) else {
+                parser.current -= 1;
+                return parser.error(format!("`{name}` is not a variable"));
+            };
+            if updates.iter().any(|(other, _)| *other == variable) {
+                parser.current -= 1;
+                return parser.error(format!("`{name}` is updated twice"));
+            }
+            parser.expect_symbol("'")?;
+            parser.expect_symbol("=")?;
+            updates.push((variable, parser.expression()?));
+
+            if !parser.is_symbol(",") {
+                break;
+            }
+            parser.next();
+        }
+        end(&parser)?;
+
+        Ok(Command {
+            text: text.to_string(),
+            guard,
+            updates,
+        })
+    };
+    let command = parse().map_err(|e| error(text, e))?;
+
+    if command.guard.is_temporal() || command.updates.iter().any(|(_, e)| e.is_temporal()) {
+        return Err(TransitionSystemError {
+            text: text.to_string(),
+            column: 1,
+            message: "Temporal operators are not allowed in commands".to_string(),
+        });
+    }
+    // Names that are neither variables nor values of an enumeration would otherwise only fail when the command is evaluated, if ever
+    program
+        .check_names(&command.guard, (1, 1))
+        .map_err(|e| error(text, e))?;
+    for (_, expr) in command.updates.iter() {
+        program
+            .check_names(expr, (1, 1))
+            .map_err(|e| error(text, e))?;
+    }
+    Ok(command)
+}
+
+/// Every way of picking one value from each `Vec`, in lexicographic order
+fn product(choices: &[Vec<Value>]) -> Vec<Vec<Value>> {
+    let mut result = vec![vec![]];
+    for values in choices {
+        result = result
+            .into_iter()
+            .flat_map(|prefix: Vec<Value>| {
+                values.iter().map(move |value| {
+                    let mut valuation = prefix.clone();
+                    valuation.push(value.clone());
+                    valuation
+                })
+            })
+            .collect();
+    }
+    result
+}
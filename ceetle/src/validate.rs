@@ -0,0 +1,237 @@
+use std::{collections::VecDeque, fmt};
+
+use super::{CTLFormula, EnumerableModel};
+
+/// How serious a `Diagnostic` is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Something that may be intended, e.g. an atom no formula uses
+    Info,
+    /// Something that makes verification results misleading, e.g. a state without successors
+    Warning,
+    /// Something that makes `verify` panic or give wrong results, e.g. a transition to a state that is not in the model
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Info => write!(f, "info"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found by [`validate`] or [`validate_with_formulas`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic<S, A> {
+    /// A transition leads to a state that is not in the model
+    DanglingTransition { from: S, to: S },
+    /// A state is in the model more than once, which can only happen in a `VecDiscreteModel`
+    DuplicateState(S),
+    /// An initial state is not in the model
+    UnknownInitialState(S),
+    /// A state has no successors. CTL assumes that every path is infinite, so formulas such as `AX(f)` hold trivially in it
+    Deadlock(S),
+    /// A state cannot be reached from the initial states
+    Unreachable(S),
+    /// An atom holds in some state but no formula uses it
+    UnusedAtom(A),
+    /// A formula uses an atom that holds in no state
+    UndefinedAtom(A),
+}
+
+impl<S, A> Diagnostic<S, A> {
+    /// # `severity`
+    /// Returns how serious the diagnostic is
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::DanglingTransition { .. }
+            | Self::DuplicateState(_)
+            | Self::UnknownInitialState(_) => Severity::Error,
+            Self::Deadlock(_) | Self::UndefinedAtom(_) => Severity::Warning,
+            Self::Unreachable(_) | Self::UnusedAtom(_) => Severity::Info,
+        }
+    }
+}
+
+impl<S: fmt::Debug, A: fmt::Debug> fmt::Display for Diagnostic<S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.severity())?;
+        match self {
+            Self::DanglingTransition { from, to } => {
+                write!(
+                    f,
+                    "transition from {from:?} leads to {to:?}, which is not in the model"
+                )
+            }
+            Self::DuplicateState(state) => {
+                write!(f, "state {state:?} is in the model more than once")
+            }
+            Self::UnknownInitialState(state) => {
+                write!(f, "initial state {state:?} is not in the model")
+            }
+            Self::Deadlock(state) => write!(f, "state {state:?} has no successors"),
+            Self::Unreachable(state) => {
+                write!(
+                    f,
+                    "state {state:?} cannot be reached from the initial states"
+                )
+            }
+            Self::UnusedAtom(atom) => write!(f, "atom {atom:?} is not used by any formula"),
+            Self::UndefinedAtom(atom) => write!(f, "atom {atom:?} holds in no state"),
+        }
+    }
+}
+
+/// Function `validate` that checks the structure of a model
+///
+/// The diagnostics are, in this order:
+/// - `DuplicateState` for each state listed more than once
+/// - `DanglingTransition` for each transition to a state that is not in the model
+/// - `UnknownInitialState` for each initial state that is not in the model
+/// - `Deadlock` for each state without successors
+/// - `Unreachable` for each state that cannot be reached from the initial states, if the model has initial states
+///
+/// A model without diagnostics of `Severity::Error` can be used with `verify` without panicking.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{validate, Diagnostic, Severity, VecDiscreteModel};
+///
+/// let model = VecDiscreteModel::new(vec![
+///     ("s0", vec!["p"], vec!["s1"]),
+///     ("s1", vec!["q"], vec!["s2"]),
+/// ]).with_initial_states(vec!["s0"]);
+///
+/// let diagnostics = validate(&model);
+/// assert_eq!(diagnostics, vec![Diagnostic::DanglingTransition { from: "s1", to: "s2" }]);
+/// assert_eq!(diagnostics[0].severity(), Severity::Error);
+/// ```
+pub fn validate<S: PartialEq + Clone, A: PartialEq + Clone>(
+    model: &dyn EnumerableModel<S, A>,
+) -> Vec<Diagnostic<S, A>> {
+    let mut diagnostics = vec![];
+    let states = model.states();
+
+    // States listed more than once are reported once, and only their first entry is checked further
+    let mut distinct: Vec<&S> = vec![];
+    for state in states.iter() {
+        if !distinct.contains(state) {
+            distinct.push(state);
+        } else if !diagnostics.contains(&Diagnostic::DuplicateState((*state).clone())) {
+            diagnostics.push(Diagnostic::DuplicateState((*state).clone()));
+        }
+    }
+
+    for from in distinct.iter() {
+        for to in model.transitions(from) {
+            if !distinct.contains(&to) {
+                diagnostics.push(Diagnostic::DanglingTransition {
+                    from: (*from).clone(),
+                    to: to.clone(),
+                });
+            }
+        }
+    }
+
+    let initial = model.initial_states();
+    for state in initial.iter() {
+        if !distinct.contains(&state) {
+            diagnostics.push(Diagnostic::UnknownInitialState(state.clone()));
+        }
+    }
+
+    for state in distinct.iter() {
+        if model.transitions(state).is_empty() {
+            diagnostics.push(Diagnostic::Deadlock((*state).clone()));
+        }
+    }
+
+    if !initial.is_empty() {
+        let mut reached: Vec<&S> = initial.iter().filter(|s| distinct.contains(s)).collect();
+        let mut queue: VecDeque<&S> = reached.iter().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            for next in model.transitions(state) {
+                if distinct.contains(&next) && !reached.contains(&next) {
+                    reached.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        for state in distinct.iter() {
+            if !reached.contains(state) {
+                diagnostics.push(Diagnostic::Unreachable((*state).clone()));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Function `validate_with_formulas` that checks the structure of a model and the atoms used by the formulas to verify on it
+///
+/// The diagnostics are those of [`validate`], followed by
+/// - `UndefinedAtom` for each atom of the formulas that holds in no state, which is often a typo
+/// - `UnusedAtom` for each atom that holds in some state but is used by none of the formulas
+///
+/// ## Examples
+/// ```
+/// use ceetle::{validate_with_formulas, ctl, CTLFormula, Diagnostic, VecDiscreteModel};
+///
+/// let model = VecDiscreteModel::new(vec![
+///     ("s0", vec!["ready"], vec!["s1"]),
+///     ("s1", vec!["done"], vec!["s1"]),
+/// ]);
+///
+/// let diagnostics = validate_with_formulas(&model, &[ctl!(AF(Atom("dnoe")))]);
+/// assert_eq!(diagnostics, vec![
+///     Diagnostic::UndefinedAtom("dnoe"),
+///     Diagnostic::UnusedAtom("ready"),
+///     Diagnostic::UnusedAtom("done"),
+/// ]);
+/// ```
+pub fn validate_with_formulas<S: PartialEq + Clone, A: PartialEq + Clone>(
+    model: &dyn EnumerableModel<S, A>,
+    formulas: &[CTLFormula<A>],
+) -> Vec<Diagnostic<S, A>> {
+    let mut diagnostics = validate(model);
+
+    let mut labels: Vec<&A> = vec![];
+    let mut seen: Vec<&S> = vec![];
+    for state in model.states() {
+        if seen.contains(&state) {
+            continue;
+        }
+        seen.push(state);
+        for atom in model.labels(state) {
+            if !labels.contains(&atom) {
+                labels.push(atom);
+            }
+        }
+    }
+
+    let mut used: Vec<&A> = vec![];
+    for formula in formulas {
+        for atom in formula.atoms() {
+            if !used.contains(&atom) {
+                used.push(atom);
+            }
+        }
+    }
+
+    for atom in used.iter() {
+        if !labels.contains(atom) {
+            diagnostics.push(Diagnostic::UndefinedAtom((*atom).clone()));
+        }
+    }
+    for atom in labels.iter() {
+        if !used.contains(atom) {
+            diagnostics.push(Diagnostic::UnusedAtom((*atom).clone()));
+        }
+    }
+
+    diagnostics
+}
@@ -1,11 +1,18 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, hash::Hash};
 
-use super::{CTLFormula, Model};
+use super::{analysis::Graph, CTLFormula, Model};
 
 /// Function `verify` that verifies that a CTL formula holds 
 /// 
 /// The `verify` function takes a `Model`, a state and a `CTLFormula` and checks if the formula holds in the given state of that model.
 /// 
+/// Paths that end in a state without transitions are considered as paths too. For example, `EG(f)` holds in a state without transitions where `f` holds
+/// and `AF(f)` does not hold in a state without transitions where `f` does not hold. `AX(f)` always holds in such a state and `EX(f)` never does.
+/// 
+/// If the formula has no `EU`, `EF`, `EG`, `AU`, `AF` nor `AG`, only the states within as many transitions as it nests `EX` and `AX` are explored,
+/// so such formulas can be checked on models with infinitely many states, such as a `StateMachineModel`. Otherwise all states reachable from `state` are explored.
+/// Looking states up takes `O(n)` since they are only `PartialEq`, so [`satisfying_states`] is faster for large models whose states implement `Hash`.
+/// 
 /// ## Examples
 /// ```
 /// use ceetle::{VecDiscreteModel, verify, ctl, CTLFormula};
@@ -26,334 +33,162 @@ pub fn verify<S: PartialEq, T: PartialEq>(
     state: &S,
     formula: &CTLFormula<T>,
 ) -> bool {
-    match formula {
-        CTLFormula::True => true,
-        CTLFormula::False => false,
-        CTLFormula::Atom(atom) => model.state_has(&state, &atom),
-        CTLFormula::And(formula1, formula2) => verify(model, state, formula1) && verify(model, state, formula2),
-        CTLFormula::Or(formula1, formula2) => verify(model, state, formula1) || verify(model, state, formula2),
-        CTLFormula::Not(formula) => !verify(model, state, formula),
-        CTLFormula::Imply(formula1, formula2) => !verify(model, state, formula1) || verify(model, state, formula2),
-        CTLFormula::AG(subformula) => check_all_paths_global(model, state, subformula),
-        CTLFormula::AF(subformula) => check_all_future(model, state, subformula),
-        CTLFormula::AX(subformula) => check_all_nexts(model, state, subformula),
-        CTLFormula::AU(formula, until) => check_all_until(model, state, formula, until),
-        CTLFormula::EG(subformula) => check_any_path_global(model, state, subformula),
-        CTLFormula::EF(subformula) => check_any_future(model, state, subformula),
-        CTLFormula::EX(subformula) => check_any_next(model, state, subformula),
-        CTLFormula::EU(formula, until) => check_any_until(model, state, formula, until),
-    }
+    let graph = Graph::explore_up_to(model, vec![state], depth(formula));
+    satisfying(model, &graph, formula)[0]
 }
 
-// ================== VERIFIERS ==================
-// Most algorithms below are different versions of BFS
-
-fn check_all_nexts<S: PartialEq, T: PartialEq>(
-    model: &dyn Model<S, T>,
-    state: &S,
+/// Function `satisfying_states` that returns the states in which a CTL formula holds
+/// 
+/// The formula is checked in all of `states` at once, which is much faster than calling `verify` for each of them.
+/// The states where it holds are returned in the order of `states`, without duplicates.
+/// 
+/// ## Examples
+/// ```
+/// use ceetle::{model, satisfying_states, ctl, CTLFormula, EnumerableModel, HashedDiscreteModel};
+/// 
+/// let model = model! {
+///     s0 -> s1;
+///     s1 [done] -> s1;
+///     s2 -> s2;
+/// };
+/// 
+/// let states = ["s0", "s1", "s2"];
+/// assert_eq!(satisfying_states(&model, &states, &ctl!(AF(Atom("done")))), vec![&"s0", &"s1"]);
+/// ```
+pub fn satisfying_states<'a, S: Eq + Hash, T: PartialEq>(
+    model: &'a dyn Model<S, T>,
+    states: &'a [S],
     formula: &CTLFormula<T>,
-) -> bool {
-    // Iter of next states
-    let nexts = model.transitions(state);
-
-    for next in nexts.iter() {
-        if !verify(model, next, &formula) {
-            return false;
-        }
-    }
-
-    return true;
+) -> Vec<&'a S> {
+    let graph = Graph::explore_hashed(model, states.iter().collect(), depth(formula));
+    let holds = satisfying(model, &graph, formula);
+    (0..graph.starts).filter(|&i| holds[i]).map(|i| graph.states[i]).collect()
 }
 
-fn check_any_next<S: PartialEq, T: PartialEq>(
-    model: &dyn Model<S, T>,
-    state: &S,
-    formula: &CTLFormula<T>,
-) -> bool {
-    // Iter of next states
-    let nexts = model.transitions(state);
-
-    for next in nexts.iter() {
-        if verify(model, next, &formula) {
-            return true;
+/// The number of nested `EX` and `AX` in a formula, or `None` if it has an operator that looks arbitrarily far ahead
+pub(crate) fn depth<T: PartialEq>(formula: &CTLFormula<T>) -> Option<usize> {
+    match formula {
+        CTLFormula::True | CTLFormula::False | CTLFormula::Atom(_) => Some(0),
+        CTLFormula::Not(formula) => depth(formula),
+        CTLFormula::And(formula1, formula2) | CTLFormula::Or(formula1, formula2) | CTLFormula::Imply(formula1, formula2) => {
+            Some(depth(formula1)?.max(depth(formula2)?))
         }
+        CTLFormula::EX(formula) | CTLFormula::AX(formula) => Some(depth(formula)? + 1),
+        _ => None,
     }
-
-    return false;
 }
 
-fn check_all_paths_global<S: PartialEq, T: PartialEq>(
-    model: &dyn Model<S, T>,
-    state: &S,
-    formula: &CTLFormula<T>,
-) -> bool {
-    if !verify(model, state, formula) {
-        return false;
-    }
-
-    // ============================================================
-    //                        Algorithm
-    // BFS that returns false if the formula is not valid for some
-    // state
-    // ============================================================
-    let mut visited: Vec<&S> = Vec::from([state]);
-    let mut queue: VecDeque<&S> = VecDeque::new();
-
-    for i in model.transitions(state).iter() {
-        queue.push_back(i);
-    }
-
-    while let Some(state) = queue.pop_front() {
-        if visited.contains(&state) {
-            continue;
-        }
-
-        if !verify(model, state, formula) {
-            return false;
-        }
-
-        // Add next states of current state
-        for i in model.transitions(state).iter() {
-            queue.push_back(i);
-        }
-
-        visited.push(state);
-    }
-
-    return true;
-}
+// ================== VERIFIERS ==================
+// The formula is checked in all states of the graph at once, from the innermost subformulas outwards.
+// Each function below returns, for every state of the graph, whether the formula holds in it.
+// If the graph was explored only up to `depth(formula)` transitions, the results are only right in the start states.
 
-fn check_any_path_global<S: PartialEq, T: PartialEq>(
+pub(crate) fn satisfying<S: PartialEq, T: PartialEq>(
     model: &dyn Model<S, T>,
-    state: &S,
+    graph: &Graph<S>,
     formula: &CTLFormula<T>,
-) -> bool {
-    if !verify(model, state, formula) {
-        return false;
-    }
+) -> Vec<bool> {
+    let n = graph.states.len();
+    let check = |formula| satisfying(model, graph, formula);
 
-    // ============================================================
-    //                        Algorithm
-    // BFS that returns true if it finds a loop where the formula
-    // is valid for all states along that path
-    // ============================================================
-    let mut visited: Vec<&S> = Vec::from([state]);
-    let mut queue: VecDeque<&S> = VecDeque::new();
-
-    for i in model.transitions(state).iter() {
-        if verify(model, i, formula) {
-            queue.push_back(i);
+    match formula {
+        CTLFormula::True => vec![true; n],
+        CTLFormula::False => vec![false; n],
+        CTLFormula::Atom(atom) => graph.states.iter().map(|state| model.state_has(state, atom)).collect(),
+        CTLFormula::Not(formula) => not(check(formula)),
+        CTLFormula::And(formula1, formula2) => zip(check(formula1), check(formula2), |a, b| a && b),
+        CTLFormula::Or(formula1, formula2) => zip(check(formula1), check(formula2), |a, b| a || b),
+        CTLFormula::Imply(formula1, formula2) => zip(check(formula1), check(formula2), |a, b| !a || b),
+        CTLFormula::EX(subformula) => check_any_next(graph, &check(subformula)),
+        CTLFormula::AX(subformula) => {
+            // AX(f) ≡ ¬EX(¬f)
+            not(check_any_next(graph, &not(check(subformula))))
+        }
+        CTLFormula::EU(formula, until) => check_any_until(graph, &check(formula), check(until)),
+        CTLFormula::EF(subformula) => check_any_until(graph, &vec![true; n], check(subformula)),
+        CTLFormula::EG(subformula) => check_any_path_global(graph, check(subformula)),
+        CTLFormula::AG(subformula) => {
+            // AG(f) ≡ ¬EF(¬f)
+            not(check_any_until(graph, &vec![true; n], not(check(subformula))))
+        }
+        CTLFormula::AF(subformula) => {
+            // AF(f) ≡ ¬EG(¬f)
+            not(check_any_path_global(graph, not(check(subformula))))
+        }
+        CTLFormula::AU(formula, until) => {
+            // A[f U g] ≡ ¬(E[¬g U (¬f∧¬g)] ∨ EG(¬g))
+            let not_formula = not(check(formula));
+            let not_until = not(check(until));
+            let stuck = zip(not_formula, not_until.clone(), |a, b| a && b);
+            let fails = zip(
+                check_any_until(graph, &not_until, stuck),
+                check_any_path_global(graph, not_until),
+                |a, b| a || b,
+            );
+            not(fails)
         }
     }
-
-    while let Some(state) = queue.pop_front() {
-        if visited.contains(&state) {
-            return true;
-        }
-
-        // Add next states of current state
-        for i in model.transitions(state).iter() {
-            if verify(model, i, formula) {
-                queue.push_back(i);
-            }
-        }
-
-        visited.push(state);
-    }
-
-    return true;
 }
 
-fn check_any_future<S: PartialEq, T: PartialEq>(
-    model: &dyn Model<S, T>,
-    state: &S,
-    formula: &CTLFormula<T>,
-) -> bool {
-    if verify(model, state, formula) {
-        return true;
-    }
-
-    // ============================================================
-    //                        Algorithm
-    // BFS that returns true if the formula is valid for some state
-    // ============================================================
-    let mut visited: Vec<&S> = Vec::from([state]);
-    let mut queue: VecDeque<&S> = VecDeque::new();
-
-    for i in model.transitions(state).iter() {
-        queue.push_back(i);
-    }
-
-    while let Some(state) = queue.pop_front() {
-        if verify(model, state, formula) {
-            return true;
-        }
-
-        // Add next states of current state
-        for i in model.transitions(state).iter() {
-            if !visited.contains(&i) {
-                queue.push_back(i);
-            }
-        }
-
-        visited.push(state);
-    }
-
-    return false;
+fn not(values: Vec<bool>) -> Vec<bool> {
+    values.into_iter().map(|a| !a).collect()
 }
 
-fn check_all_future<S: PartialEq, T: PartialEq>(
-    model: &dyn Model<S, T>,
-    state: &S,
-    formula: &CTLFormula<T>,
-) -> bool {
-    if verify(model, state, formula) {
-        return true;
-    }
-
-    // ============================================================
-    //                        Algorithm
-    // BFS that returns false if there is a loop where the formula
-    // is false in all states along side that path
-    // ============================================================
-    let mut visited: Vec<&S> = Vec::from([state]);
-    let mut queue: VecDeque<&S> = VecDeque::new();
-
-    for i in model.transitions(state).iter() {
-        if !verify(model, i, formula) {
-            queue.push_back(i);
-        }
-    }
-
-    while let Some(state) = queue.pop_front() {
-        if visited.contains(&state) {
-            return false;
-        }
-
-        // Add next states of current state where the formula is invalid
-        for i in model.transitions(state).iter() {
-            if !verify(model, i, formula) {
-                queue.push_back(&i);
-            }
-        }
-
-        visited.push(state);
-    }
-
-    return true;
+fn zip(left: Vec<bool>, right: Vec<bool>, f: impl Fn(bool, bool) -> bool) -> Vec<bool> {
+    left.into_iter().zip(right).map(|(a, b)| f(a, b)).collect()
 }
 
-fn check_any_until<S: PartialEq, T: PartialEq>(
-    model: &dyn Model<S, T>,
-    state: &S,
-    formula: &CTLFormula<T>,
-    until: &CTLFormula<T>,
-) -> bool {
-    if !verify(model, state, formula) {
-        return false;
-    }
-    if verify(model, state, until) {
-        return true;
-    }
+fn check_any_next<S>(graph: &Graph<S>, formula: &[bool]) -> Vec<bool> {
+    graph
+        .successors
+        .iter()
+        .map(|nexts| nexts.iter().any(|&j| formula[j]))
+        .collect()
+}
 
+fn check_any_until<S>(graph: &Graph<S>, formula: &[bool], until: Vec<bool>) -> Vec<bool> {
     // ============================================================
     //                        Algorithm
-    // BFS that returns true if it finds a state where the until
-    // formula is valid and where the first formula holds for all
-    // states along that path until the last
+    // Backwards BFS from the states where the until formula holds,
+    // through the states where the first formula holds
     // ============================================================
-    let mut visited: Vec<&S> = Vec::from([state]);
-    let mut queue: VecDeque<&S> = VecDeque::new();
-
-    for i in model.transitions(state).iter() {
-        // We found a path already!
-        if verify(model, i, until) {
-            return true;
-        }
-        if verify(model, i, formula) {
-            queue.push_back(i);
-        }
-    }
-
-    while let Some(state) = queue.pop_front() {
-        if visited.contains(&state) {
-            continue;
-        }
-
-        for i in model.transitions(state).iter() {
-            // We found a path where until holds
-            if verify(model, i, until) {
-                return true;
-            }
-
-            // Add next states of current state
-            if verify(model, i, formula) {
-                queue.push_back(i);
+    let mut result = until;
+    let mut queue: VecDeque<usize> = (0..result.len()).filter(|&i| result[i]).collect();
+
+    while let Some(i) = queue.pop_front() {
+        for &j in graph.predecessors[i].iter() {
+            if !result[j] && formula[j] {
+                result[j] = true;
+                queue.push_back(j);
             }
         }
-
-        visited.push(state);
     }
 
-    return false;
+    result
 }
 
-fn check_all_until<S: PartialEq, T: PartialEq>(
-    model: &dyn Model<S, T>,
-    state: &S,
-    formula: &CTLFormula<T>,
-    until: &CTLFormula<T>,
-) -> bool {
-    if !verify(model, state, formula) {
-        return false;
-    }
-    if verify(model, state, until) {
-        return true;
-    }
-
+fn check_any_path_global<S: PartialEq>(graph: &Graph<S>, formula: Vec<bool>) -> Vec<bool> {
     // ============================================================
     //                        Algorithm
-    // BFS that returns false if:
-    //      1. It finds a loop where the first formula only holds
-    //         along that loop
-    //      2. It finds a state where the first and until formula
-    //         do not hold
+    // A path where the formula always holds either loops forever in
+    // a cycle of such states or ends in such a state without any
+    // transitions. Those states are found with the strongly connected
+    // components of the states where the formula holds, and then
+    // E[formula U those states] is computed
     // ============================================================
-    let mut visited: Vec<&S> = Vec::from([state]);
-    let mut queue: VecDeque<&S> = VecDeque::new();
-
-    for i in model.transitions(state).iter() {
-        let first_formula_holds = verify(model, i, formula);
-
-        // If neither until or first formulas are true then return false
-        if !verify(model, i, until) && !first_formula_holds {
-            return false;
-        }
-
-        if first_formula_holds {
-            queue.push_back(i);
+    let mut ends = vec![false; formula.len()];
+    for component in graph.components_within(&formula) {
+        if graph.is_cyclic(&component) {
+            for i in component {
+                ends[i] = true;
+            }
         }
     }
-
-    while let Some(state) = queue.pop_front() {
-        if visited.contains(&state) {
-            return false;
-        }
-
-        for i in model.transitions(state).iter() {
-            let first_formula_holds = verify(model, i, formula);
-
-            if !verify(model, i, until) && !first_formula_holds {
-                return false;
-            }
-
-            if first_formula_holds {
-                queue.push_back(&i);
-            }
+    for (i, nexts) in graph.successors.iter().enumerate() {
+        if formula[i] && nexts.is_empty() {
+            ends[i] = true;
         }
-
-        visited.push(state);
     }
 
-    return true;
+    check_any_until(graph, &formula, ends)
 }
@@ -0,0 +1,187 @@
+use std::collections::VecDeque;
+
+use super::{
+    analysis::Graph,
+    verifier::{depth, satisfying},
+    CTLFormula, Model,
+};
+
+/// Function `witness` that returns a path of the model showing why a formula holds in a state
+///
+/// The path starts in `state` and depends on the outermost temporal operator of the formula:
+/// - `EX(f)`: the state followed by a successor where `f` holds
+/// - `EF(f)` and `E[f U g]`: a shortest path to a state where `f` (resp. `g`) holds
+/// - `EG(f)`: a lasso of states where `f` holds, whose last state also appears earlier in the path,
+///   or a path of states where `f` holds that ends in a state without transitions
+/// - `f∨g` and `f∧g`: the witness of the first operand that holds and is not propositional
+///
+/// For any other formula, e.g. atoms or universal operators which no single path demonstrates, the path is only `state`.
+/// Returns `None` if the formula does not hold in `state`.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{VecDiscreteModel, witness, ctl, CTLFormula};
+///
+/// let model = VecDiscreteModel::new(vec![
+///     ("s0", vec![], vec!["s1"]),
+///     ("s1", vec![], vec!["s2"]),
+///     ("s2", vec!["done"], vec!["s2"]),
+/// ]);
+///
+/// assert_eq!(witness(&model, &"s0", &ctl!(EF(Atom("done")))), Some(vec!["s0", "s1", "s2"]));
+/// assert_eq!(witness(&model, &"s2", &ctl!(Not(Atom("done")))), None);
+/// ```
+pub fn witness<S: PartialEq + Clone, T: PartialEq + Clone>(
+    model: &dyn Model<S, T>,
+    state: &S,
+    formula: &CTLFormula<T>,
+) -> Option<Vec<S>> {
+    // The states within as many transitions as `EX` needs are enough for its subformula to be right in the successors
+    let graph = Graph::explore_up_to(model, vec![state], depth(formula));
+    let holds = |formula| satisfying(model, &graph, formula);
+    if !holds(formula)[0] {
+        return None;
+    }
+
+    let path = match formula {
+        CTLFormula::EX(subformula) => {
+            let next = holds(subformula);
+            graph.successors[0]
+                .iter()
+                .find(|&&j| next[j])
+                .map(|&j| vec![0, j])
+        }
+        CTLFormula::EF(subformula) => {
+            shortest_path(&graph, &vec![true; graph.states.len()], &holds(subformula))
+        }
+        CTLFormula::EU(formula, until) => shortest_path(&graph, &holds(formula), &holds(until)),
+        CTLFormula::EG(subformula) => lasso(&graph, &holds(subformula)),
+        CTLFormula::Or(formula1, formula2) | CTLFormula::And(formula1, formula2) => {
+            return Some(
+                [formula1, formula2]
+                    .into_iter()
+                    .filter(|f| !is_propositional(f))
+                    .find_map(|f| witness(model, state, f))
+                    .unwrap_or_else(|| vec![state.clone()]),
+            );
+        }
+        _ => None,
+    };
+
+    Some(match path {
+        Some(path) => path.into_iter().map(|i| graph.states[i].clone()).collect(),
+        None => vec![state.clone()],
+    })
+}
+
+/// Function `counterexample` that returns a path of the model showing why a formula does not hold in a state
+///
+/// The counterexample is the [`witness`] of the negation of the formula, in negation normal form.
+/// For example, a counterexample of `AG(f)` is a path to a state where `f` does not hold
+/// and a counterexample of `AF(f)` is a lasso of states where `f` does not hold, or a path of such states that ends in a state without transitions.
+/// Returns `None` if the formula holds in `state`.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{VecDiscreteModel, counterexample, ctl, CTLFormula};
+///
+/// let model = VecDiscreteModel::new(vec![
+///     ("s0", vec!["safe"], vec!["s1"]),
+///     ("s1", vec!["safe"], vec!["s2"]),
+///     ("s2", vec![], vec!["s2"]),
+/// ]);
+///
+/// assert_eq!(counterexample(&model, &"s0", &ctl!(AG(Atom("safe")))), Some(vec!["s0", "s1", "s2"]));
+/// ```
+pub fn counterexample<S: PartialEq + Clone, T: PartialEq + Clone>(
+    model: &dyn Model<S, T>,
+    state: &S,
+    formula: &CTLFormula<T>,
+) -> Option<Vec<S>> {
+    witness(
+        model,
+        state,
+        &CTLFormula::Not(Box::new(formula.clone())).to_nnf(),
+    )
+}
+
+fn is_propositional<T: PartialEq>(formula: &CTLFormula<T>) -> bool {
+    match formula {
+        CTLFormula::True | CTLFormula::False | CTLFormula::Atom(_) => true,
+        CTLFormula::Not(f) => is_propositional(f),
+        CTLFormula::And(f, g) | CTLFormula::Or(f, g) | CTLFormula::Imply(f, g) => {
+            is_propositional(f) && is_propositional(g)
+        }
+        _ => false,
+    }
+}
+
+// BFS from the first state of the graph through states where `formula` holds until reaching a state where `until` holds
+fn shortest_path<S>(graph: &Graph<S>, formula: &[bool], until: &[bool]) -> Option<Vec<usize>> {
+    let mut parents: Vec<Option<usize>> = vec![None; graph.states.len()];
+    let mut visited = vec![false; graph.states.len()];
+    visited[0] = true;
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(current) = queue.pop_front() {
+        if until[current] {
+            let mut path = vec![current];
+            while let Some(parent) = parents[*path.last().unwrap()] {
+                path.push(parent);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        if !formula[current] {
+            continue;
+        }
+
+        for &next in graph.successors[current].iter() {
+            if !visited[next] {
+                visited[next] = true;
+                parents[next] = Some(current);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+// DFS from the first state of the graph through states where `formula` holds until a state on the current path is reached again
+// or a state without transitions is reached
+fn lasso<S>(graph: &Graph<S>, formula: &[bool]) -> Option<Vec<usize>> {
+    let mut path = vec![0];
+    if graph.successors[0].is_empty() {
+        return Some(path);
+    }
+    let mut on_path = vec![false; graph.states.len()];
+    let mut finished = vec![false; graph.states.len()];
+    on_path[0] = true;
+    // Index of the next successor to try for each state on the path
+    let mut next_index = vec![0];
+
+    while let Some(&current) = path.last() {
+        let i = next_index.last_mut().unwrap();
+        let Some(&next) = graph.successors[current].get(*i) else {
+            finished[current] = true;
+            on_path[current] = false;
+            path.pop();
+            next_index.pop();
+            continue;
+        };
+        *i += 1;
+
+        if !formula[next] || finished[next] {
+            continue;
+        }
+        path.push(next);
+        if on_path[next] || graph.successors[next].is_empty() {
+            return Some(path);
+        }
+        on_path[next] = true;
+        next_index.push(0);
+    }
+
+    None
+}
@@ -0,0 +1,88 @@
+use ceetle::{counterexample, satisfying_states, CTLFormula, HashedDiscreteModel, Notation};
+use serde_json::json;
+
+/// A state where a property does not hold, with a path showing why
+#[derive(Debug)]
+pub struct Failure {
+    pub state: String,
+    pub counterexample: Vec<String>,
+}
+
+/// The result of checking a property in a set of states
+#[derive(Debug)]
+pub struct SpecResult {
+    pub formula: CTLFormula<String>,
+    pub failures: Vec<Failure>,
+}
+
+impl SpecResult {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+pub fn check(
+    model: &HashedDiscreteModel<String, String>,
+    states: &[String],
+    specs: &[CTLFormula<String>],
+) -> Vec<SpecResult> {
+    specs
+        .iter()
+        .map(|formula| {
+            let holding = satisfying_states(model, states, formula);
+            SpecResult {
+                formula: formula.clone(),
+                failures: states
+                    .iter()
+                    .filter(|state| !holding.contains(state))
+                    .map(|state| Failure {
+                        state: state.clone(),
+                        counterexample: counterexample(model, state, formula)
+                            .unwrap_or_else(|| vec![state.clone()]),
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+pub fn report_text(results: &[SpecResult]) -> String {
+    let mut report = String::new();
+    for result in results {
+        let status = if result.passed() { "PASS" } else { "FAIL" };
+        report += &format!("{status} {}\n", result.formula.display(Notation::Ascii));
+        for failure in result.failures.iter() {
+            report += &format!(
+                "  from {}: {}\n",
+                failure.state,
+                failure.counterexample.join(" -> ")
+            );
+        }
+    }
+
+    let failed = results.iter().filter(|result| !result.passed()).count();
+    report += &format!("{} properties, {failed} failed\n", results.len());
+    report
+}
+
+pub fn report_json(results: &[SpecResult]) -> String {
+    let properties: Vec<_> = results
+        .iter()
+        .map(|result| {
+            json!({
+                "formula": result.formula.display(Notation::Ascii).to_string(),
+                "passed": result.passed(),
+                "failures": result.failures.iter().map(|failure| json!({
+                    "state": failure.state,
+                    "counterexample": failure.counterexample,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+
+    let report = json!({
+        "passed": results.iter().all(SpecResult::passed),
+        "properties": properties,
+    });
+    serde_json::to_string_pretty(&report).unwrap()
+}
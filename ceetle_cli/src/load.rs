@@ -0,0 +1,104 @@
+use std::{fs, path::Path};
+
+use ceetle::{
+    from_dot, parse_formula, parse_smv, validate, CTLFormula, HashedDiscreteModel, Severity,
+};
+
+/// Format of a model file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Json,
+    Dot,
+    Smv,
+}
+
+impl InputFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "dot" | "gv" => Some(Self::Dot),
+            "smv" => Some(Self::Smv),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::from_name(path.extension()?.to_str()?)
+    }
+}
+
+/// A model together with the properties declared in its file
+#[derive(Debug)]
+pub struct LoadedModel {
+    pub model: HashedDiscreteModel<String, String>,
+    pub specs: Vec<CTLFormula<String>>,
+}
+
+pub fn load_model(path: &Path, format: Option<InputFormat>) -> Result<LoadedModel, String> {
+    let format = format
+        .or_else(|| InputFormat::from_path(path))
+        .ok_or_else(|| {
+            format!(
+                "cannot tell the format of `{}`, use --input json|dot|smv",
+                path.display()
+            )
+        })?;
+    let text = fs::read_to_string(path)
+        .map_err(|error| format!("cannot read `{}`: {error}", path.display()))?;
+    parse_model(&text, format).map_err(|error| format!("{}: {error}", path.display()))
+}
+
+/// Parses a model and rejects it if `validate` finds errors in it, which would make checking panic
+pub fn parse_model(text: &str, format: InputFormat) -> Result<LoadedModel, String> {
+    let loaded = match format {
+        InputFormat::Json => serde_json::from_str(text)
+            .map(|model| LoadedModel {
+                model,
+                specs: vec![],
+            })
+            .map_err(|error| error.to_string()),
+        InputFormat::Dot => from_dot(text)
+            .map(|model| LoadedModel {
+                model,
+                specs: vec![],
+            })
+            .map_err(|error| error.to_string()),
+        InputFormat::Smv => {
+            let program = parse_smv(text).map_err(|error| error.to_string())?;
+            Ok(LoadedModel {
+                model: program.model,
+                specs: program.specs,
+            })
+        }
+    }?;
+
+    let errors: Vec<String> = validate(&loaded.model)
+        .into_iter()
+        .filter(|diagnostic| diagnostic.severity() == Severity::Error)
+        .map(|diagnostic| diagnostic.to_string())
+        .collect();
+    if !errors.is_empty() {
+        return Err(format!("invalid model\n  {}", errors.join("\n  ")));
+    }
+    Ok(loaded)
+}
+
+pub fn load_specs(path: &Path) -> Result<Vec<CTLFormula<String>>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|error| format!("cannot read `{}`: {error}", path.display()))?;
+    parse_specs(&text).map_err(|error| format!("{}:{error}", path.display()))
+}
+
+/// Parses one formula per line, skipping empty lines and `#` comments
+pub fn parse_specs(text: &str) -> Result<Vec<CTLFormula<String>>, String> {
+    let mut specs = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let spec = parse_formula(line).map_err(|error| format!("{}: {error}", i + 1))?;
+        specs.push(spec);
+    }
+    Ok(specs)
+}
@@ -0,0 +1,178 @@
+use std::{env, path::PathBuf, process::ExitCode};
+
+use ceetle::{parse_formula, EnumerableModel};
+
+mod check;
+mod load;
+mod repl;
+
+use check::{check, report_json, report_text};
+use load::{load_model, load_specs, InputFormat};
+use repl::run_repl;
+
+const USAGE: &str = "\
+Usage: ceetle check <MODEL> [OPTIONS]
+       ceetle repl <MODEL> [--input <FORMAT>]
+
+`check` checks CTL properties in the initial states of a model.
+`repl` starts an interactive session for exploring a model, type `help` in it for the list of commands.
+
+Options:
+  --spec <FORMULA>      Check the formula, e.g. `AG(req -> AF ack)`
+  --spec-file <FILE>    Check the formulas in the file, one per line
+  --state <STATE>       Check in the state instead of the initial states
+  --input <FORMAT>      Format of the model: json, dot or smv
+  --format <FORMAT>     Format of the report: text or json
+  -h, --help            Print this help
+  -V, --version         Print the version";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CheckOptions {
+    pub model: PathBuf,
+    pub input: Option<InputFormat>,
+    pub specs: Vec<String>,
+    pub spec_files: Vec<PathBuf>,
+    pub states: Vec<String>,
+    pub format: OutputFormat,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Check(CheckOptions),
+    Repl(PathBuf, Option<InputFormat>),
+    Help,
+    Version,
+}
+
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut args = args.iter();
+    let command = match args.next().map(String::as_str) {
+        None | Some("-h" | "--help" | "help") => return Ok(Command::Help),
+        Some("-V" | "--version") => return Ok(Command::Version),
+        Some(command @ ("check" | "repl")) => command,
+        Some(command) => return Err(format!("unknown command `{command}`")),
+    };
+
+    let mut model = None;
+    let mut options = CheckOptions {
+        model: PathBuf::new(),
+        input: None,
+        specs: vec![],
+        spec_files: vec![],
+        states: vec![],
+        format: OutputFormat::Text,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("missing value for `{arg}`"))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--spec" | "--spec-file" | "--state" | "--format" if command == "repl" => {
+                return Err(format!("`{arg}` is only valid for `check`"))
+            }
+            "--spec" => options.specs.push(value()?),
+            "--spec-file" => options.spec_files.push(value()?.into()),
+            "--state" => options.states.push(value()?),
+            "--input" => {
+                let name = value()?;
+                options.input = Some(
+                    InputFormat::from_name(&name)
+                        .ok_or_else(|| format!("unknown model format `{name}`"))?,
+                );
+            }
+            "--format" => {
+                options.format = match value()?.as_str() {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    other => return Err(format!("unknown report format `{other}`")),
+                }
+            }
+            flag if flag.starts_with('-') => return Err(format!("unknown option `{flag}`")),
+            path if model.is_none() => model = Some(PathBuf::from(path)),
+            extra => return Err(format!("unexpected argument `{extra}`")),
+        }
+    }
+
+    options.model = model.ok_or("missing model file")?;
+    if command == "repl" {
+        return Ok(Command::Repl(options.model, options.input));
+    }
+    Ok(Command::Check(options))
+}
+
+fn run_check(options: &CheckOptions) -> Result<bool, String> {
+    let loaded = load_model(&options.model, options.input)?;
+
+    let mut specs = loaded.specs;
+    for file in options.spec_files.iter() {
+        specs.extend(load_specs(file)?);
+    }
+    for spec in options.specs.iter() {
+        specs.push(parse_formula(spec).map_err(|error| format!("`{spec}`: {error}"))?);
+    }
+    if specs.is_empty() {
+        return Err("no properties to check, use --spec or --spec-file".to_string());
+    }
+
+    let model = &loaded.model;
+    let states = if options.states.is_empty() {
+        model.initial_states().clone()
+    } else {
+        options.states.clone()
+    };
+    if states.is_empty() {
+        return Err("the model has no initial states, use --state".to_string());
+    }
+    let known = EnumerableModel::states(model);
+    if let Some(state) = states.iter().find(|state| !known.contains(state)) {
+        return Err(format!("state `{state}` is not in the model"));
+    }
+
+    let results = check(model, &states, &specs);
+    match options.format {
+        OutputFormat::Text => print!("{}", report_text(&results)),
+        OutputFormat::Json => println!("{}", report_json(&results)),
+    }
+    Ok(results.iter().all(|result| result.passed()))
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = parse_args(&args).and_then(|command| match command {
+        Command::Help => {
+            println!("{USAGE}");
+            Ok(true)
+        }
+        Command::Version => {
+            println!("ceetle {}", env!("CARGO_PKG_VERSION"));
+            Ok(true)
+        }
+        Command::Check(options) => run_check(&options),
+        Command::Repl(path, input) => {
+            load_model(&path, input).and_then(|loaded| run_repl(loaded.model))
+        }
+    });
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(message) => {
+            eprintln!("error: {message}\nRun `ceetle --help` for usage");
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,208 @@
+use std::io::{self, BufRead, Write};
+
+use ceetle::{
+    counterexample, parse_formula, satisfying_states, verify, witness, CTLFormula, EnumerableModel,
+    HashedDiscreteModel, Model, Notation,
+};
+
+const HELP: &str = "\
+Commands:
+  <FORMULA>                 Check the formula in the current state, e.g. `AG(req -> AF ack)`
+  sat <FORMULA>             List the states where the formula holds
+  witness <FORMULA>         Show a path where the formula holds and follow it with `step`
+  counterexample <FORMULA>  Show a path where the formula does not hold and follow it with `step`
+  step / back               Move forward or backward along the last path
+  states                    List the states, marking the initial and the current state
+  goto <STATE>              Make the state the current state
+  labels [STATE]            List the atoms of a state, by default the current state
+  succ [STATE]              List the successors of a state
+  pred [STATE]              List the predecessors of a state
+  help                      Print this help
+  quit                      Exit";
+
+/// Result of a REPL command
+#[derive(Debug, PartialEq)]
+pub enum Reply {
+    Text(String),
+    Quit,
+}
+
+/// State of an interactive session over a model
+pub struct Repl {
+    model: HashedDiscreteModel<String, String>,
+    current: String,
+    path: Vec<String>,
+    // Position of the current state in `path`
+    position: usize,
+}
+
+impl Repl {
+    pub fn new(model: HashedDiscreteModel<String, String>) -> Result<Self, String> {
+        let current = match model.initial_states().first() {
+            Some(state) => state.clone(),
+            None => {
+                let mut states = EnumerableModel::states(&model);
+                states.sort();
+                states
+                    .first()
+                    .map(|state| state.to_string())
+                    .ok_or("the model has no states")?
+            }
+        };
+
+        Ok(Self {
+            model,
+            current,
+            path: vec![],
+            position: 0,
+        })
+    }
+
+    pub fn current(&self) -> &String {
+        &self.current
+    }
+
+    /// Runs one line of input, returning the text to print
+    pub fn execute(&mut self, line: &str) -> Result<Reply, String> {
+        let line = line.trim();
+        let (command, argument) = match line.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line, ""),
+        };
+
+        let text = match command {
+            "" => String::new(),
+            "quit" | "exit" => return Ok(Reply::Quit),
+            "help" => HELP.to_string(),
+            "states" => self.list_states(),
+            "goto" => {
+                self.current = self.state(argument)?.clone();
+                self.path.clear();
+                format!("current state is {}", self.current)
+            }
+            "labels" => self.model.labels(self.state(argument)?).join(", "),
+            "succ" => self.model.transitions(self.state(argument)?).join(", "),
+            "pred" => self.predecessors(self.state(argument)?).join(", "),
+            "sat" => {
+                let formula = self.formula(argument)?;
+                let all: Vec<String> = EnumerableModel::states(&self.model)
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                let mut states = satisfying_states(&self.model, &all, &formula);
+                states.sort();
+                let names: Vec<&str> = states.iter().map(|state| state.as_str()).collect();
+                format!("{} states: {}", names.len(), names.join(", "))
+            }
+            "witness" | "counterexample" => {
+                let formula = self.formula(argument)?;
+                let path = if command == "witness" {
+                    witness(&self.model, &self.current, &formula)
+                } else {
+                    counterexample(&self.model, &self.current, &formula)
+                };
+                match path {
+                    Some(path) => {
+                        self.path = path;
+                        self.position = 0;
+                        self.path.join(" -> ")
+                    }
+                    None => format!("no {command} from {}", self.current),
+                }
+            }
+            "step" | "back" => {
+                let position = match command {
+                    "step" => self.position + 1,
+                    _ => self.position.checked_sub(1).unwrap_or(self.path.len()),
+                };
+                let Some(state) = self.path.get(position) else {
+                    return Err(format!("cannot {command} along the path"));
+                };
+                self.current = state.clone();
+                self.position = position;
+                format!("current state is {}", self.current)
+            }
+            _ => {
+                let formula = self.formula(line)?;
+                let holds = if verify(&self.model, &self.current, &formula) {
+                    "holds"
+                } else {
+                    "does not hold"
+                };
+                let formula = formula.display(Notation::Ascii);
+                format!("{formula} {holds} in {}", self.current)
+            }
+        };
+        Ok(Reply::Text(text))
+    }
+
+    fn formula(&self, text: &str) -> Result<CTLFormula<String>, String> {
+        if text.is_empty() {
+            return Err("expected a formula".to_string());
+        }
+        parse_formula(text).map_err(|error| error.to_string())
+    }
+
+    // The named state, or the current state if no name is given
+    fn state(&self, name: &str) -> Result<&String, String> {
+        if name.is_empty() {
+            return Ok(&self.current);
+        }
+        EnumerableModel::states(&self.model)
+            .into_iter()
+            .find(|state| *state == name)
+            .ok_or_else(|| format!("state `{name}` is not in the model"))
+    }
+
+    fn predecessors(&self, state: &String) -> Vec<String> {
+        let mut predecessors: Vec<String> = EnumerableModel::states(&self.model)
+            .into_iter()
+            .filter(|other| self.model.transitions(other).contains(state))
+            .cloned()
+            .collect();
+        predecessors.sort();
+        predecessors
+    }
+
+    fn list_states(&self) -> String {
+        let mut states = EnumerableModel::states(&self.model);
+        states.sort();
+        let lines: Vec<String> = states
+            .into_iter()
+            .map(|state| {
+                let current = if *state == self.current { '*' } else { ' ' };
+                let initial = if self.model.initial_states().contains(state) {
+                    " (initial)"
+                } else {
+                    ""
+                };
+                format!("{current} {state}{initial}")
+            })
+            .collect();
+        lines.join("\n")
+    }
+}
+
+pub fn run_repl(model: HashedDiscreteModel<String, String>) -> Result<bool, String> {
+    let mut repl = Repl::new(model)?;
+    println!("Type `help` for the list of commands");
+
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("{}> ", repl.current());
+        io::stdout().flush().map_err(|error| error.to_string())?;
+
+        let Some(line) = lines.next() else {
+            println!();
+            return Ok(true);
+        };
+        let line = line.map_err(|error| error.to_string())?;
+
+        match repl.execute(&line) {
+            Ok(Reply::Quit) => return Ok(true),
+            Ok(Reply::Text(text)) if text.is_empty() => {}
+            Ok(Reply::Text(text)) => println!("{text}"),
+            Err(message) => println!("error: {message}"),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::*;
+use ceetle::{ctl, CTLFormula};
+use check::report_json;
+use load::{parse_model, parse_specs};
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn check_arguments() {
+    let command = parse_args(&args(&[
+        "check", "model.gv", "--spec", "AG p", "--state", "s1", "--format", "json",
+    ]))
+    .unwrap();
+
+    assert_eq!(
+        command,
+        Command::Check(CheckOptions {
+            model: "model.gv".into(),
+            input: None,
+            specs: vec!["AG p".to_string()],
+            spec_files: vec![],
+            states: vec!["s1".to_string()],
+            format: OutputFormat::Json,
+        })
+    );
+    assert_eq!(parse_args(&args(&[])).unwrap(), Command::Help);
+    assert!(parse_args(&args(&["check"])).is_err());
+    assert!(parse_args(&args(&["check", "m.json", "--format", "xml"])).is_err());
+    assert!(parse_args(&args(&["check", "m.json", "--spec"])).is_err());
+}
+
+#[test]
+fn check_reports() {
+    let loaded = parse_model(
+        "digraph { init [shape=point]; init -> s0; s0 [label=p]; s0 -> s1 -> s1; }",
+        InputFormat::Dot,
+    )
+    .unwrap();
+    let specs = parse_specs("# safety\nAG p\n\nEF !p\n").unwrap();
+    assert_eq!(specs[0], ctl!(AG(Atom("p".to_string()))));
+    assert!(parse_specs("AG p\nAG (p").unwrap_err().starts_with("2: "));
+
+    let results = check::check(&loaded.model, &["s0".to_string()], &specs);
+    assert!(!results[0].passed());
+    assert!(results[1].passed());
+    assert_eq!(results[0].failures[0].counterexample, vec!["s0", "s1"]);
+
+    let report: serde_json::Value = serde_json::from_str(&report_json(&results)).unwrap();
+    assert_eq!(report["passed"], false);
+    assert_eq!(report["properties"][0]["formula"], "AG p");
+    assert_eq!(
+        report["properties"][0]["failures"][0]["counterexample"][1],
+        "s1"
+    );
+}
+
+#[test]
+fn check_repl() {
+    let loaded = parse_model(
+        "digraph { s0 [label=p, initial=true]; s0 -> s1 -> s2 -> s2; s0 -> s2; s2 [label=q]; }",
+        InputFormat::Dot,
+    )
+    .unwrap();
+    let mut repl = repl::Repl::new(loaded.model).unwrap();
+    let mut run = |line: &str| match repl.execute(line) {
+        Ok(repl::Reply::Text(text)) => text,
+        other => panic!("unexpected reply {other:?}"),
+    };
+
+    assert_eq!(run("AF q"), "AF q holds in s0");
+    assert_eq!(run("sat EX q"), "3 states: s0, s1, s2");
+    assert_eq!(run("pred s2"), "s0, s1, s2");
+    assert_eq!(run("succ"), "s1, s2");
+    assert_eq!(run("counterexample AG !q"), "s0 -> s2");
+    assert_eq!(run("step"), "current state is s2");
+    assert_eq!(run("labels"), "q");
+    assert_eq!(run("back"), "current state is s0");
+    assert_eq!(run("states"), "* s0 (initial)\n  s1\n  s2");
+
+    assert!(repl.execute("goto s9").is_err());
+    assert!(repl.execute("sat").is_err());
+    assert_eq!(repl.execute("quit"), Ok(repl::Reply::Quit));
+    assert_eq!(
+        parse_args(&args(&["repl", "m.smv"])).unwrap(),
+        Command::Repl("m.smv".into(), None)
+    );
+    assert!(parse_args(&args(&["repl", "m.smv", "--spec", "p"])).is_err());
+}
+
+#[test]
+fn check_invalid_models() {
+    let error = parse_model(
+        r#"{"version": 1, "states": [{"name": "s0", "transitions": ["s1"]}], "initial": ["s0"]}"#,
+        InputFormat::Json,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        "invalid model\n  error: transition from \"s0\" leads to \"s1\", which is not in the model"
+    );
+
+    let error = parse_model(
+        "MODULE main\nVAR busy : boolean;\nASSIGN init(busy) := FALSE;\nFAIRNESS busy\nSPEC AF busy\n",
+        InputFormat::Smv,
+    )
+    .unwrap_err();
+    assert!(
+        error.contains("`FAIRNESS` constraints are not supported"),
+        "{error}"
+    );
+}
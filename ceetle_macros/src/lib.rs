@@ -1,7 +1,10 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parenthesized, parse::Parse, parse_macro_input, Expr, Token};
+use syn::{
+    bracketed, parenthesized, parse::Parse, parse_macro_input, punctuated::Punctuated, Expr, Ident,
+    ImplItem, ItemImpl, LitStr, Token,
+};
 
 macro_rules! process {
     ($keyword:tt in $input:tt as $out:ty) => {
@@ -52,6 +55,7 @@ mod kw {
     custom_keyword!(EF);
     custom_keyword!(EX);
     custom_keyword!(EU);
+    custom_keyword!(init);
 }
 
 enum CTLFormula {
@@ -80,7 +84,7 @@ impl Parse for CTLFormula {
             input.parse::<kw::True>()?;
             return Ok(CTLFormula::True);
         } else if lookahead.peek(kw::False) {
-            input.parse::<kw::True>()?;
+            input.parse::<kw::False>()?;
             return Ok(CTLFormula::False);
         } else if lookahead.peek(kw::Atom) {
             return process!(Atom in input as Expr);
@@ -122,7 +126,7 @@ impl Parse for CTLFormula {
 /// where `p` is any value.
 /// 
 /// ## Examples
-/// ```
+/// ```ignore
 /// let f = ctl!(AX(Atom(5))); // Translates to "AX(5)"
 /// let g = ctl!(And(Atom(1), AG(Atom(3)))); // Translates to "1 ∧ AG(3)"
 /// let h = ctl!(Or(Imply(Atom(1), Atom(3)), EU(Atom(1), Atom(2)))); // Translates to "(1 → 3) ∨ E[1 U 2]""
@@ -195,3 +199,387 @@ fn get_ctl(input: CTLFormula) -> TokenStream2 {
     };
     result
 }
+
+// ================== MODEL ==================
+
+/// A state or atom name, written as an identifier or a string literal
+struct Name {
+    value: LitStr,
+}
+
+impl Parse for Name {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(LitStr) {
+            return Ok(Name {
+                value: input.parse()?,
+            });
+        }
+        let ident: Ident = input.parse()?;
+        Ok(Name {
+            value: LitStr::new(&ident.to_string(), ident.span()),
+        })
+    }
+}
+
+struct ModelState {
+    name: Name,
+    atoms: Vec<Name>,
+    transitions: Vec<Name>,
+}
+
+struct ModelInput {
+    states: Vec<ModelState>,
+    initial: Vec<Name>,
+}
+
+// Parses `name, name, ...` up to the `;` ending the statement
+fn parse_names(input: syn::parse::ParseStream) -> syn::Result<Vec<Name>> {
+    let mut names = vec![input.parse::<Name>()?];
+    while input.peek(Token![,]) {
+        input.parse::<Token![,]>()?;
+        names.push(input.parse()?);
+    }
+    Ok(names)
+}
+
+impl Parse for ModelInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut model = ModelInput {
+            states: vec![],
+            initial: vec![],
+        };
+
+        while !input.is_empty() {
+            if input.peek(kw::init) {
+                input.parse::<kw::init>()?;
+                model.initial.extend(parse_names(input)?);
+                input.parse::<Token![;]>()?;
+                continue;
+            }
+
+            let name: Name = input.parse()?;
+            let mut atoms = vec![];
+            if input.peek(syn::token::Bracket) {
+                let content;
+                let _ = bracketed!(content in input);
+                atoms = Punctuated::<Name, Token![,]>::parse_terminated(&content)?
+                    .into_iter()
+                    .collect();
+            }
+            let mut transitions = vec![];
+            if input.peek(Token![->]) {
+                input.parse::<Token![->]>()?;
+                transitions = parse_names(input)?;
+            }
+            input.parse::<Token![;]>()?;
+
+            model.states.push(ModelState {
+                name,
+                atoms,
+                transitions,
+            });
+        }
+
+        Ok(model)
+    }
+}
+
+impl ModelInput {
+    // Checks that every state is declared once and that every referenced state is declared
+    fn check(&self) -> syn::Result<()> {
+        let mut errors: Vec<syn::Error> = vec![];
+        let mut declared: Vec<String> = vec![];
+
+        for state in self.states.iter() {
+            let name = state.name.value.value();
+            if declared.contains(&name) {
+                errors.push(syn::Error::new(
+                    state.name.value.span(),
+                    format!("state `{name}` is declared more than once"),
+                ));
+            }
+            declared.push(name);
+        }
+
+        let references = self
+            .states
+            .iter()
+            .flat_map(|state| state.transitions.iter())
+            .chain(self.initial.iter());
+        for reference in references {
+            let name = reference.value.value();
+            if !declared.contains(&name) {
+                errors.push(syn::Error::new(
+                    reference.value.span(),
+                    format!("state `{name}` is not declared"),
+                ));
+            }
+        }
+
+        match errors.into_iter().reduce(|mut all, error| {
+            all.combine(error);
+            all
+        }) {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// # `model`
+/// The `model` macro builds a `HashedDiscreteModel<&str, &str>` from a list of states.
+/// This macro uses the following syntax, where every statement ends with `;`:
+///
+/// ```text
+/// name [atom, atom, ...] -> name, name, ...;
+/// init name, name, ...;
+/// ```
+/// where the atoms and the transitions of a state are both optional and names and atoms are identifiers or string literals.
+/// It is a compile error for a transition or an `init` statement to refer to a state that is not declared, or to declare a state twice.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ctl, model, verify, CTLFormula, Model};
+///
+/// let model = model! {
+///     idle [ready] -> busy;
+///     busy ["in use"] -> idle, busy;
+///     init idle;
+/// };
+/// assert_eq!(model.transitions(&"busy"), &vec!["idle", "busy"]);
+/// assert!(verify(&model, &"idle", &ctl!(AF(Atom("in use")))));
+///
+/// // Models without atoms are `HashedDiscreteModel<&str, &str>` too
+/// let cycle = model! { a -> b; b -> a; };
+/// assert!(!verify(&cycle, &"a", &ctl!(EF(Atom("p")))));
+/// ```
+///
+/// Transitions to undeclared states do not compile:
+/// ```compile_fail
+/// use ceetle::model;
+///
+/// let model = model! { idle -> busy; };
+/// ```
+#[proc_macro]
+pub fn model(_input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(_input as ModelInput);
+    match input.check() {
+        Ok(()) => get_model(input).into(),
+        Err(error) => {
+            // Several errors expand to several `compile_error!` invocations, which need a block to form an expression
+            let errors = error.to_compile_error();
+            quote!({ #errors }).into()
+        }
+    }
+}
+
+fn get_model(input: ModelInput) -> TokenStream2 {
+    let count = input.states.len();
+    let inserts = input.states.iter().map(|state| {
+        let name = &state.name.value;
+        let atoms = state.atoms.iter().map(|atom| &atom.value);
+        let transitions = state.transitions.iter().map(|next| &next.value);
+        quote!(states.insert(#name, (vec![#(#atoms),*], vec![#(#transitions),*]));)
+    });
+    let initial = input.initial.iter().map(|state| &state.value);
+
+    quote!({
+        let mut states = ::std::collections::HashMap::with_capacity(#count);
+        #(#inserts)*
+        ::ceetle::HashedDiscreteModel::<&'static str, &'static str>::new(states)
+            .with_initial_states(vec![#(#initial),*])
+    })
+}
+
+// ================== STATE MACHINE ==================
+
+enum CtlAttribute {
+    Next,
+    Atom(Option<LitStr>),
+}
+
+impl Parse for CtlAttribute {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ident: Ident = input.parse()?;
+        match ident.to_string().as_str() {
+            "next" => Ok(CtlAttribute::Next),
+            "atom" => {
+                if input.peek(Token![=]) {
+                    input.parse::<Token![=]>()?;
+                    return Ok(CtlAttribute::Atom(Some(input.parse()?)));
+                }
+                Ok(CtlAttribute::Atom(None))
+            }
+            _ => Err(syn::Error::new(
+                ident.span(),
+                "expected `next`, `atom` or `atom = \"name\"`",
+            )),
+        }
+    }
+}
+
+/// # `ctl_model`
+/// The `ctl_model` attribute implements `StateMachine` for a type from the methods of one of its `impl` blocks, so its values can be checked with a `StateMachineModel`.
+/// In the `impl` block:
+/// - exactly one method `fn(&self) -> Vec<Self>` must be marked `#[ctl(next)]` and gives the successors of a state
+/// - every method `fn(&self) -> bool` marked `#[ctl(atom)]` is an atom named after the method, or after `name` if marked `#[ctl(atom = "name")]`
+///
+/// The type must implement `Clone`, `Eq` and `Hash`.
+///
+/// ## Examples
+/// ```
+/// use ceetle::{ctl, ctl_model, verify, CTLFormula, StateMachineModel};
+///
+/// #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// struct Door {
+///     open: bool,
+/// }
+///
+/// impl Door {
+///     fn toggled(&self) -> Self {
+///         Door { open: !self.open }
+///     }
+/// }
+///
+/// #[ctl_model]
+/// impl Door {
+///     #[ctl(next)]
+///     fn next(&self) -> Vec<Self> {
+///         vec![self.toggled()]
+///     }
+///
+///     #[ctl(atom)]
+///     fn is_open(&self) -> bool {
+///         self.open
+///     }
+/// }
+///
+/// let closed = Door { open: false };
+/// let model = StateMachineModel::new(vec![closed.clone()]);
+/// assert_eq!(<Door as ceetle::StateMachine>::atoms(), &["is_open"]);
+/// assert!(verify(&model, &closed, &ctl!(AG(AF(Atom("is_open"))))));
+/// ```
+///
+/// Methods that do not take `&self` do not compile:
+/// ```compile_fail
+/// use ceetle::ctl_model;
+///
+/// #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// struct Door {
+///     open: bool,
+/// }
+///
+/// #[ctl_model]
+/// impl Door {
+///     #[ctl(next)]
+///     fn next(&mut self) -> Vec<Self> {
+///         self.open = !self.open;
+///         vec![self.clone()]
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn ctl_model(_attribute: TokenStream, _input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(_input as ItemImpl);
+    match get_state_machine(input) {
+        Ok(output) => output.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn get_state_machine(mut input: ItemImpl) -> syn::Result<TokenStream2> {
+    if let Some((_, path, _)) = &input.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "`ctl_model` must be used on an inherent `impl` block",
+        ));
+    }
+
+    let mut next: Option<Ident> = None;
+    let mut atoms: Vec<(LitStr, Ident)> = vec![];
+
+    for item in input.items.iter_mut() {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+
+        // Take the `ctl` attributes out of the method, since they are not known to the compiler
+        let mut attributes = vec![];
+        method.attrs.retain(|attribute| {
+            if attribute.path().is_ident("ctl") {
+                attributes.push(attribute.clone());
+                return false;
+            }
+            true
+        });
+
+        for attribute in attributes {
+            let ident = method.sig.ident.clone();
+            let by_reference = matches!(
+                method.sig.inputs.first(),
+                Some(syn::FnArg::Receiver(receiver))
+                    if receiver.reference.is_some() && receiver.mutability.is_none()
+            );
+            if !by_reference || method.sig.inputs.len() != 1 {
+                return Err(syn::Error::new_spanned(
+                    &method.sig,
+                    "methods marked with `ctl` must take only `&self`",
+                ));
+            }
+
+            match attribute.parse_args::<CtlAttribute>()? {
+                CtlAttribute::Next if next.is_some() => {
+                    return Err(syn::Error::new_spanned(
+                        attribute,
+                        "only one method can be marked `#[ctl(next)]`",
+                    ));
+                }
+                CtlAttribute::Next => next = Some(ident),
+                CtlAttribute::Atom(name) => {
+                    let name =
+                        name.unwrap_or_else(|| LitStr::new(&ident.to_string(), ident.span()));
+                    if atoms.iter().any(|(other, _)| other.value() == name.value()) {
+                        return Err(syn::Error::new(
+                            name.span(),
+                            format!("atom `{}` is declared more than once", name.value()),
+                        ));
+                    }
+                    atoms.push((name, ident));
+                }
+            }
+        }
+    }
+
+    let Some(next) = next else {
+        return Err(syn::Error::new_spanned(
+            &input.self_ty,
+            "a method must be marked `#[ctl(next)]` to give the successors of a state",
+        ));
+    };
+
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+    let self_ty = &input.self_ty;
+    let names = atoms.iter().map(|(name, _)| name);
+    let checks = atoms.iter().map(|(name, method)| quote!(#name => self.#method(),));
+
+    Ok(quote!(
+        #input
+
+        impl #impl_generics ::ceetle::StateMachine for #self_ty #where_clause {
+            fn successors(&self) -> Vec<Self> {
+                self.#next()
+            }
+
+            fn atoms() -> &'static [&'static str] {
+                &[#(#names),*]
+            }
+
+            fn holds(&self, atom: &str) -> bool {
+                match atom {
+                    #(#checks)*
+                    _ => false,
+                }
+            }
+        }
+    ))
+}